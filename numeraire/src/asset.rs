@@ -1,23 +1,22 @@
 use anyhow::{anyhow, Result};
 pub use ethers::core::types::{Address, Chain};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-/*
- * CB: "ETH-USD"
- * FTX: "BTC/USD"
- * BN : "ETHUSDC"
- */
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
 /// Represents the Assets that Rubicon supports, and some others similar to them
 pub enum Asset {
     Usdc,
     Usdt,
+    /// Native ETH. It has no token address of its own: it trades on Rubicon by being wrapped into WETH, see [`Asset::settlement_asset`]
+    Eth,
     Weth,
     Wbtc,
     Dai,
     Snx,
     Op,
+    /// A token that isn't built into the SDK, usually resolved through a [`crate::prelude::TokenRegistry`]
+    Token(RegisteredToken),
 }
 
 fn asset_address_err<'a>(chain: &Chain, asset: &Asset) -> Result<&'a str> {
@@ -29,6 +28,18 @@ fn asset_address_err<'a>(chain: &Chain, asset: &Asset) -> Result<&'a str> {
 }
 
 impl Asset {
+    /// All of the assets that are built into the SDK (i.e. everything but [`Asset::Token`])
+    pub const BUILTIN: [Asset; 8] = [
+        Asset::Usdc,
+        Asset::Usdt,
+        Asset::Eth,
+        Asset::Weth,
+        Asset::Wbtc,
+        Asset::Dai,
+        Asset::Snx,
+        Asset::Op,
+    ];
+
     /// On chain assets (i.e. on etherem derived chains) have a value.
     /// Off chain assets (or non-EVM) assets do not have a value.
     /// e.g. OP and ETH and WETH have values. USD does not. EUR would not.
@@ -38,15 +49,17 @@ impl Asset {
         match self {
             Asset::Usdc => Some(6),
             Asset::Usdt => Some(6),
+            Asset::Eth => Some(18),
             Asset::Weth => Some(18),
             Asset::Wbtc => Some(8),
             Asset::Dai => Some(18),
             Asset::Snx => Some(18),
             Asset::Op => Some(18),
+            Asset::Token(token) => Some(token.decimals()),
         }
     }
 
-    fn to_address_str(&self, chain: &Chain) -> Result<&str> {
+    fn address_str(&self, chain: &Chain) -> Result<&str> {
         match chain {
             Chain::Optimism => match &self {
                 Asset::Usdc => Ok("7F5c764cBc14f9669B88837ca1490cCa17c31607"),
@@ -56,7 +69,7 @@ impl Asset {
                 Asset::Dai => Ok("DA10009cBd5D07dd0CeCc66161FC93D7c9000da1"),
                 Asset::Snx => Ok("8700dAec35aF8Ff88c16BdF0418774CB3D7599B4"),
                 Asset::Op => Ok("4200000000000000000000000000000000000042"),
-                // _ => asset_address_err(chain, self), // unreachable, for now...
+                _ => asset_address_err(chain, self),
             },
             Chain::OptimismKovan => match &self {
                 Asset::Dai => Ok("Eb22F82de678852B8dff065768490B881DD0116a"),
@@ -64,20 +77,163 @@ impl Asset {
                 Asset::Usdc => Ok("940578F6D9f9ffD9621F69dbB5B24Fd380799772"),
                 _ => asset_address_err(chain, self),
             },
+            // OP Goerli (chain id 420) is filed under Goerli, since ethers doesn't have a chain for it. These are its predeploys
+            Chain::Goerli => match &self {
+                Asset::Weth => Ok("4200000000000000000000000000000000000006"),
+                Asset::Op => Ok("4200000000000000000000000000000000000042"),
+                _ => asset_address_err(chain, self),
+            },
             _ => asset_address_err(chain, self),
         }
     }
 
+    /// Returns the token address of the asset on `chain`. Native ETH isn't a token, so this fails for [`Asset::Eth`];
+    /// use [`Asset::settlement_address`] to get the address of the WETH it settles as.
     pub fn to_address(&self, chain: &Chain) -> Result<Address> {
-        self.to_address_str(chain)
-            .map(|x| hex::decode(x).unwrap())
-            .map(|x| Address::from_slice(x.as_slice()))
+        match self {
+            Asset::Token(token) if token.chain() == chain => Ok(*token.address()),
+            Asset::Eth => Err(anyhow!(
+                "Asset {:?} is native on chain {:?} and has no token address, it settles as {:?}",
+                self,
+                chain,
+                Asset::Weth
+            )),
+            _ => self
+                .address_str(chain)
+                .map(|x| hex::decode(x).unwrap())
+                .map(|x| Address::from_slice(x.as_slice())),
+        }
+    }
+
+    /// Returns true for the chain's native asset, which is sent as `msg.value` rather than transferred as a token.
+    pub fn is_native(&self) -> bool {
+        matches!(self, Asset::Eth)
+    }
+
+    /// The token the asset is actually traded as on chain: WETH for native ETH, and the asset itself for everything else.
+    pub fn settlement_asset(&self) -> Asset {
+        match self {
+            Asset::Eth => Asset::Weth,
+            _ => *self,
+        }
+    }
+
+    /// The token address of [`Asset::settlement_asset`] on `chain`.
+    pub fn settlement_address(&self, chain: &Chain) -> Result<Address> {
+        self.settlement_asset().to_address(chain)
+    }
+
+    /// Returns the ticker symbol of the asset, e.g. `WETH`
+    pub fn symbol(&self) -> String {
+        match self {
+            Asset::Token(token) => token.symbol().to_string(),
+            _ => format!("{:?}", self).to_uppercase(),
+        }
     }
 }
 
 impl std::fmt::Display for Asset {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s = format!("{:?}", self);
-        write!(f, "{}", s.to_uppercase())
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// The longest symbol we can store inline. This matches the limit of the Uniswap token list schema.
+pub const MAX_SYMBOL_LEN: usize = 20;
+
+/// A short, fixed capacity ticker symbol. We keep this inline so that [`Asset`] can stay `Copy`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TokenSymbol {
+    len: u8,
+    bytes: [u8; MAX_SYMBOL_LEN],
+}
+
+impl TokenSymbol {
+    /// Builds a symbol, normalized to upper case. Fails if the symbol is empty, too long, or not ASCII.
+    pub fn new(symbol: &str) -> Result<Self> {
+        if symbol.is_empty() || symbol.len() > MAX_SYMBOL_LEN || !symbol.is_ascii() {
+            return Err(anyhow!(
+                "Invalid token symbol {:?}: must be 1 to {} ASCII characters",
+                symbol,
+                MAX_SYMBOL_LEN
+            ));
+        }
+        let mut bytes = [0_u8; MAX_SYMBOL_LEN];
+        bytes[..symbol.len()].copy_from_slice(symbol.to_ascii_uppercase().as_bytes());
+        Ok(Self {
+            len: symbol.len() as u8,
+            bytes,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        // we only ever store ASCII, so this can't fail
+        std::str::from_utf8(&self.bytes[..self.len as usize]).unwrap()
+    }
+}
+
+impl std::fmt::Debug for TokenSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl std::fmt::Display for TokenSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for TokenSymbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for TokenSymbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        TokenSymbol::new(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A token that lives at a known address on a single chain. These are usually handed out by a
+/// [`crate::prelude::TokenRegistry`], rather than being built by hand.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
+pub struct RegisteredToken {
+    #[serde(with = "crate::serde_helpers::chain_id")]
+    chain: Chain,
+    symbol: TokenSymbol,
+    address: Address,
+    decimals: u8,
+}
+
+impl RegisteredToken {
+    pub fn new(chain: Chain, symbol: TokenSymbol, address: Address, decimals: u8) -> Self {
+        Self {
+            chain,
+            symbol,
+            address,
+            decimals,
+        }
+    }
+
+    /// Returns a reference to the chain the token lives on
+    pub fn chain(&self) -> &Chain {
+        &self.chain
+    }
+
+    /// Returns a reference to the ticker symbol of the token
+    pub fn symbol(&self) -> &TokenSymbol {
+        &self.symbol
+    }
+
+    /// Returns a reference to the address of the token contract
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
     }
 }
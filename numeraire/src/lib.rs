@@ -1,10 +1,21 @@
 mod asset;
+mod order;
 mod pair;
+mod price;
+mod registry;
+mod serde_helpers;
 mod sized_asset;
+pub mod size_repr;
 mod swap;
+mod symbol;
 pub mod prelude {
     pub use super::asset::*;
+    pub use super::order::*;
     pub use super::pair::*;
+    pub use super::price::*;
+    pub use super::registry::*;
+    pub use super::size_repr::{SizeRepr, WithRepr};
     pub use super::sized_asset::*;
     pub use super::swap::*;
+    pub use super::symbol::*;
 }
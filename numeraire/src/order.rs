@@ -0,0 +1,302 @@
+/**
+ * An [`Order`] is what a strategy *wants*: buy or sell some amount of a pair at a limit price.
+ * It's serializable, so that intents can be logged and replayed, and converts to and from the [`AssetSwap`]
+ * that actually gets placed on chain.
+ */
+use crate::pair::Pair;
+use crate::price::Price;
+use crate::sized_asset::{ChainNativeAsset, Rounding};
+use crate::swap::AssetSwap;
+use anyhow::{anyhow, Result};
+use ethers::types::Chain;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/// Which way an order trades the base asset of its pair.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    /// Returns the other side
+    pub fn opposite(&self) -> Self {
+        match self {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        }
+    }
+}
+
+/// Which asset of the pair an order's size is denominated in.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SizeUnit {
+    Base,
+    Quote,
+}
+
+/// An intent to trade. Deserializing checks the order the same way [`Order::new`] does.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "OrderRepr", into = "OrderRepr")]
+pub struct Order {
+    pair: Pair,
+    side: Side,
+    price: Price,
+    size: Decimal,
+    size_unit: SizeUnit,
+}
+
+impl Order {
+    /// Creates an order to `side` the base of `pair` at a limit of `price`.
+    /// `size` is human readable, in units of either the base or the quote depending on `size_unit`.
+    /// Fails if `price` isn't quoted on `pair`, or if `size` isn't positive.
+    pub fn new(pair: Pair, side: Side, price: Price, size: Decimal, size_unit: SizeUnit) -> Result<Self> {
+        if price.pair() != &pair {
+            return Err(anyhow!(
+                "[Order::new]: price {} is quoted on {}, but the order is on {}",
+                price,
+                price.pair(),
+                pair
+            ));
+        }
+        if size <= Decimal::ZERO {
+            return Err(anyhow!("[Order::new]: size must be positive, got {}", size));
+        }
+        Ok(Self {
+            pair,
+            side,
+            price,
+            size,
+            size_unit,
+        })
+    }
+
+    /// Returns a reference to the pair
+    pub fn pair(&self) -> &Pair {
+        &self.pair
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    /// Returns a reference to the limit price
+    pub fn price(&self) -> &Price {
+        &self.price
+    }
+
+    /// The human readable size, in units of [`Order::size_unit`]
+    pub fn size(&self) -> Decimal {
+        self.size
+    }
+
+    pub fn size_unit(&self) -> SizeUnit {
+        self.size_unit
+    }
+
+    /// Converts the order into the swap that gets placed on chain: what we pay, and the least we accept in return.
+    /// Whichever side isn't fixed by the size is rounded in our favour, so the swap never trades through the limit price.
+    /// Fails if the order is so small that either side of the swap rounds to nothing.
+    pub fn to_swap(&self, chain: Chain) -> Result<AssetSwap> {
+        let base = *self.pair.base();
+        let quote = *self.pair.quote();
+        let (source, target) = match (self.side, self.size_unit) {
+            (Side::Sell, SizeUnit::Base) => {
+                let source = ChainNativeAsset::from_human_decimal(chain, base, self.size)?;
+                let target = self.price.quote_for(&source, Rounding::Up)?;
+                (source, target)
+            }
+            (Side::Sell, SizeUnit::Quote) => {
+                let target = ChainNativeAsset::from_human_decimal(chain, quote, self.size)?;
+                let source = self.price.base_for(&target, Rounding::Down)?;
+                (source, target)
+            }
+            (Side::Buy, SizeUnit::Base) => {
+                let target = ChainNativeAsset::from_human_decimal(chain, base, self.size)?;
+                let source = self.price.quote_for(&target, Rounding::Down)?;
+                (source, target)
+            }
+            (Side::Buy, SizeUnit::Quote) => {
+                let source = ChainNativeAsset::from_human_decimal(chain, quote, self.size)?;
+                let target = self.price.base_for(&source, Rounding::Up)?;
+                (source, target)
+            }
+        };
+        if source.size().is_zero() || target.size().is_zero() {
+            return Err(anyhow!(
+                "[Order::to_swap]: {} {} at {} is too small to trade, it would swap {} for {}",
+                self.size,
+                match self.size_unit {
+                    SizeUnit::Base => base,
+                    SizeUnit::Quote => quote,
+                },
+                self.price,
+                source,
+                target
+            ));
+        }
+        Ok(AssetSwap::new(source, target))
+    }
+
+    /// Recovers the order that a swap on `pair` represents. The size is always in units of the base.
+    /// Fails if the swap isn't between the two assets of `pair`.
+    pub fn from_swap(swap: &AssetSwap, pair: Pair) -> Result<Self> {
+        let (side, base, quote) = if swap.source().asset() == pair.base() && swap.target().asset() == pair.quote() {
+            (Side::Sell, swap.source(), swap.target())
+        } else if swap.source().asset() == pair.quote() && swap.target().asset() == pair.base() {
+            (Side::Buy, swap.target(), swap.source())
+        } else {
+            return Err(anyhow!(
+                "[Order::from_swap]: swap of {} for {} isn't on {}",
+                swap.source().asset(),
+                swap.target().asset(),
+                pair
+            ));
+        };
+        let base_size = base.to_human_decimal()?;
+        let quote_size = quote.to_human_decimal()?;
+        let value = quote_size.checked_div(base_size).ok_or(anyhow!(
+            "[Order::from_swap]: can't price a swap with a base size of {}",
+            base_size
+        ))?;
+        Self::new(pair, side, Price::new(pair, value)?, base_size, SizeUnit::Base)
+    }
+}
+
+// the serialized form of an order, checked by `Order::new` on the way in
+#[derive(Serialize, Deserialize)]
+struct OrderRepr {
+    pair: Pair,
+    side: Side,
+    price: Price,
+    size: Decimal,
+    size_unit: SizeUnit,
+}
+
+impl TryFrom<OrderRepr> for Order {
+    type Error = anyhow::Error;
+
+    fn try_from(repr: OrderRepr) -> Result<Self> {
+        Self::new(repr.pair, repr.side, repr.price, repr.size, repr.size_unit)
+    }
+}
+
+impl From<Order> for OrderRepr {
+    fn from(order: Order) -> Self {
+        Self {
+            pair: order.pair,
+            side: order.side,
+            price: order.price,
+            size: order.size,
+            size_unit: order.size_unit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::Asset;
+    use crate::sized_asset::U256;
+
+    const CHAIN: Chain = Chain::Optimism;
+
+    fn pair() -> Pair {
+        Pair::new(Asset::Weth, Asset::Usdc)
+    }
+
+    fn order(side: Side, price: Decimal, size: Decimal, size_unit: SizeUnit) -> Order {
+        Order::new(pair(), side, Price::new(pair(), price).unwrap(), size, size_unit).unwrap()
+    }
+
+    fn weth(wei: U256) -> ChainNativeAsset {
+        ChainNativeAsset::new(CHAIN, Asset::Weth, wei).unwrap()
+    }
+
+    fn usdc(wei: u64) -> ChainNativeAsset {
+        ChainNativeAsset::new(CHAIN, Asset::Usdc, U256::from(wei)).unwrap()
+    }
+
+    #[test]
+    fn orders_must_be_consistent() {
+        let price = Price::new(pair(), Decimal::new(1600, 0)).unwrap();
+        assert!(Order::new(pair().inverse(), Side::Buy, price, Decimal::ONE, SizeUnit::Base).is_err());
+        assert!(Order::new(pair(), Side::Buy, price, Decimal::ZERO, SizeUnit::Base).is_err());
+    }
+
+    #[test]
+    fn to_swap_pays_the_right_asset() {
+        let one_and_a_half = weth(U256::exp10(17) * 15);
+        let sell = order(Side::Sell, Decimal::new(1600, 0), Decimal::new(15, 1), SizeUnit::Base).to_swap(CHAIN).unwrap();
+        assert_eq!(sell.source(), &one_and_a_half);
+        assert_eq!(sell.target(), &usdc(2_400_000_000));
+        let buy = order(Side::Buy, Decimal::new(1600, 0), Decimal::new(15, 1), SizeUnit::Base).to_swap(CHAIN).unwrap();
+        assert_eq!(buy.source(), &usdc(2_400_000_000));
+        assert_eq!(buy.target(), &one_and_a_half);
+        let buy = order(Side::Buy, Decimal::new(1600, 0), Decimal::new(2400, 0), SizeUnit::Quote).to_swap(CHAIN).unwrap();
+        assert_eq!(buy.source(), &usdc(2_400_000_000));
+        assert_eq!(buy.target(), &one_and_a_half);
+    }
+
+    #[test]
+    fn to_swap_never_trades_through_the_limit() {
+        // at 3 USDC per WETH, a USDC is a third of a WETH, which isn't a whole number of wei
+        let third = U256::from(333_333_333_333_333_333_u64);
+        let sell = order(Side::Sell, Decimal::new(3, 0), Decimal::ONE, SizeUnit::Quote).to_swap(CHAIN).unwrap();
+        assert_eq!(sell.source(), &weth(third));
+        assert_eq!(sell.target(), &usdc(1_000_000));
+        let buy = order(Side::Buy, Decimal::new(3, 0), Decimal::ONE, SizeUnit::Quote).to_swap(CHAIN).unwrap();
+        assert_eq!(buy.source(), &usdc(1_000_000));
+        assert_eq!(buy.target(), &weth(third + 1));
+        // a wei of WETH is worth less than a wei of USDC at 1600
+        let wei = Decimal::new(1, 18);
+        let sell = order(Side::Sell, Decimal::new(1600, 0), wei, SizeUnit::Base).to_swap(CHAIN).unwrap();
+        assert_eq!(sell.target(), &usdc(1));
+        // and buying one would pay nothing for it
+        assert!(order(Side::Buy, Decimal::new(1600, 0), wei, SizeUnit::Base).to_swap(CHAIN).is_err());
+    }
+
+    #[test]
+    fn to_swap_refuses_to_swap_nothing() {
+        let wei = Decimal::new(1, 18);
+        // a wei of WETH at 0.5 is half a wei of USDC, which rounds down to nothing when it's what we receive
+        assert!(order(Side::Sell, Decimal::new(5, 1), wei, SizeUnit::Base).to_swap(CHAIN).is_ok());
+        assert!(order(Side::Buy, Decimal::new(5, 1), wei, SizeUnit::Base).to_swap(CHAIN).is_err());
+        // a wei of USDC at 10^13 USDC per WETH is a tenth of a wei of WETH
+        let dear = Decimal::new(10_000_000_000_000, 0);
+        assert!(order(Side::Sell, dear, Decimal::new(1, 6), SizeUnit::Quote).to_swap(CHAIN).is_err());
+        assert!(order(Side::Buy, dear, Decimal::new(1, 6), SizeUnit::Quote).to_swap(CHAIN).is_ok());
+    }
+
+    #[test]
+    fn deserializing_checks_the_order() {
+        let valid = order(Side::Buy, Decimal::new(1600, 0), Decimal::ONE, SizeUnit::Base);
+        let json = serde_json::to_value(valid).unwrap();
+        assert_eq!(serde_json::from_value::<Order>(json.clone()).unwrap(), valid);
+
+        let mut zero = json.clone();
+        zero["size"] = serde_json::to_value(Decimal::ZERO).unwrap();
+        assert!(serde_json::from_value::<Order>(zero).is_err());
+
+        let mut other_pair = json.clone();
+        other_pair["pair"] = serde_json::to_value(pair().inverse()).unwrap();
+        assert!(serde_json::from_value::<Order>(other_pair).is_err());
+
+        let mut same_assets = json;
+        same_assets["pair"]["quote"] = same_assets["pair"]["base"].clone();
+        same_assets["price"]["pair"] = same_assets["pair"].clone();
+        assert!(serde_json::from_value::<Order>(same_assets).is_err());
+    }
+
+    #[test]
+    fn from_swap_recovers_the_order() {
+        for side in [Side::Buy, Side::Sell] {
+            let order = order(side, Decimal::new(16005, 1), Decimal::new(25, 1), SizeUnit::Base);
+            let recovered = Order::from_swap(&order.to_swap(CHAIN).unwrap(), pair()).unwrap();
+            assert_eq!(recovered, order);
+        }
+        let swap = AssetSwap::new(usdc(1), ChainNativeAsset::new(CHAIN, Asset::Dai, U256::one()).unwrap());
+        assert!(Order::from_swap(&swap, pair()).is_err());
+    }
+}
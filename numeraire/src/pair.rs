@@ -1,10 +1,13 @@
 #![allow(dead_code)]
 
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 
 use crate::asset::*;
 
+/// A base asset quoted in a different quote asset. Deserializing a pair whose base and quote are the same fails, as [`Pair::new`] would.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, Hash)]
+#[serde(try_from = "PairRepr", into = "PairRepr")]
 pub struct Pair {
     base: Asset,
     quote: Asset,
@@ -30,6 +33,19 @@ impl Pair {
     pub fn quote(&self) -> &Asset {
         &self.quote
     }
+
+    /// Returns the pair with base and quote swapped, e.g. WETH-USDC becomes USDC-WETH
+    pub fn inverse(&self) -> Self {
+        Self {
+            base: self.quote,
+            quote: self.base,
+        }
+    }
+
+    /// Returns true if `asset` is either the base or the quote of the pair
+    pub fn contains(&self, asset: &Asset) -> bool {
+        &self.base == asset || &self.quote == asset
+    }
 }
 
 impl std::fmt::Display for Pair {
@@ -37,3 +53,33 @@ impl std::fmt::Display for Pair {
         write!(f, "{}-{}", self.base, self.quote)
     }
 }
+
+// the serialized form of a pair, checked on the way in
+#[derive(Serialize, Deserialize)]
+struct PairRepr {
+    base: Asset,
+    quote: Asset,
+}
+
+impl TryFrom<PairRepr> for Pair {
+    type Error = anyhow::Error;
+
+    fn try_from(repr: PairRepr) -> Result<Self> {
+        if repr.base == repr.quote {
+            return Err(anyhow!("[Pair]: the base and quote must be different, got {} for both", repr.base));
+        }
+        Ok(Self {
+            base: repr.base,
+            quote: repr.quote,
+        })
+    }
+}
+
+impl From<Pair> for PairRepr {
+    fn from(pair: Pair) -> Self {
+        Self {
+            base: pair.base,
+            quote: pair.quote,
+        }
+    }
+}
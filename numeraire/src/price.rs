@@ -0,0 +1,313 @@
+/**
+ * A price is always the amount of `pair.quote()` per unit of `pair.base()`, in human readable units.
+ * Binding the price to its pair means that a price and its inverse can't be mixed up: anything that takes a
+ * [`Price`] checks the assets it's given against the pair, and fails loudly instead of placing a mispriced order.
+ */
+use crate::asset::Asset;
+use crate::pair::Pair;
+use crate::sized_asset::{mul_div, ChainNativeAsset, Rounding, U256};
+use anyhow::{anyhow, Result};
+use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "PriceRepr", into = "PriceRepr")]
+pub struct Price {
+    pair: Pair,
+    value: Decimal,
+}
+
+impl Price {
+    /// Creates a price of `value` units of `pair.quote()` per unit of `pair.base()`. `value` must be positive.
+    pub fn new(pair: Pair, value: Decimal) -> Result<Self> {
+        if value <= Decimal::ZERO {
+            return Err(anyhow!(
+                "[Price::new]: price of {} must be positive, got {}",
+                pair,
+                value
+            ));
+        }
+        Ok(Self { pair, value })
+    }
+
+    /// Returns a reference to the pair this price is quoted on
+    pub fn pair(&self) -> &Pair {
+        &self.pair
+    }
+
+    /// Returns a reference to the base asset
+    pub fn base(&self) -> &Asset {
+        self.pair.base()
+    }
+
+    /// Returns a reference to the quote asset
+    pub fn quote(&self) -> &Asset {
+        self.pair.quote()
+    }
+
+    /// The price in units of quote/base
+    pub fn value(&self) -> Decimal {
+        self.value
+    }
+
+    /// Returns the same price quoted on the inverted pair, e.g. WETH-USDC at 1600 becomes USDC-WETH at 0.000625.
+    /// `Decimal` has 28 digits of precision, so prices without a terminating inverse are rounded.
+    pub fn inverse(&self) -> Result<Self> {
+        let value = Decimal::ONE.checked_div(self.value).ok_or(anyhow!(
+            "[Price::inverse]: can't invert {}",
+            self
+        ))?;
+        Self::new(self.pair.inverse(), value)
+    }
+
+    /// Rounds the price to a multiple of `tick`, e.g. a tick of `0.01` rounds 1600.257 to 1600.25 when rounding down.
+    pub fn round_to_tick(&self, tick: Decimal, rounding: Rounding) -> Result<Self> {
+        if tick <= Decimal::ZERO {
+            return Err(anyhow!("[Price::round_to_tick]: tick must be positive, got {}", tick));
+        }
+        let strategy = match rounding {
+            Rounding::Down => RoundingStrategy::ToZero,
+            Rounding::Up => RoundingStrategy::AwayFromZero,
+            Rounding::HalfEven => RoundingStrategy::MidpointNearestEven,
+        };
+        let ticks = self
+            .value
+            .checked_div(tick)
+            .ok_or(anyhow!("[Price::round_to_tick]: {} / {} overflows", self.value, tick))?
+            .round_dp_with_strategy(0, strategy);
+        Self::new(self.pair, ticks * tick)
+    }
+
+    /// The exact price in wei terms, as `(quote_wei, base_wei)`: `quote_wei` wei of quote buy `base_wei` wei of base.
+    pub fn wei_ratio(&self) -> Result<(U256, U256)> {
+        let (base_decimals, quote_decimals) = self.decimals()?;
+        // quote_wei / base_wei = mantissa * 10^quote_decimals / (10^scale * 10^base_decimals)
+        let exponent = quote_decimals as i64 - base_decimals as i64 - self.value.scale() as i64;
+        let mantissa = U256::from(self.value.mantissa().unsigned_abs());
+        let (numerator, denominator) = if exponent >= 0 {
+            (checked_scale_up(mantissa, exponent as u32)?, U256::one())
+        } else {
+            (mantissa, checked_scale_up(U256::one(), exponent.unsigned_abs() as u32)?)
+        };
+        Ok((numerator, denominator))
+    }
+
+    /// Converts a size of the base asset into the equivalent size of the quote asset at this price.
+    /// Fails if `base_size` isn't the base asset of the pair.
+    pub fn quote_for(&self, base_size: &ChainNativeAsset, rounding: Rounding) -> Result<ChainNativeAsset> {
+        if base_size.asset() != self.base() {
+            return Err(anyhow!(
+                "[Price::quote_for]: {} is quoted in {} per {}, but was given a size of {}",
+                self,
+                self.quote(),
+                self.base(),
+                base_size.asset()
+            ));
+        }
+        let (quote_wei, base_wei) = self.wei_ratio()?;
+        let size = mul_div(*base_size.size(), quote_wei, base_wei, rounding)
+            .ok_or(anyhow!("[Price::quote_for]: {} at {} overflows", base_size, self))?;
+        ChainNativeAsset::new(*base_size.chain(), *self.quote(), size)
+    }
+
+    /// Converts a size of the quote asset into the equivalent size of the base asset at this price.
+    /// Fails if `quote_size` isn't the quote asset of the pair.
+    pub fn base_for(&self, quote_size: &ChainNativeAsset, rounding: Rounding) -> Result<ChainNativeAsset> {
+        if quote_size.asset() != self.quote() {
+            return Err(anyhow!(
+                "[Price::base_for]: {} is quoted in {} per {}, but was given a size of {}",
+                self,
+                self.quote(),
+                self.base(),
+                quote_size.asset()
+            ));
+        }
+        let (quote_wei, base_wei) = self.wei_ratio()?;
+        let size = mul_div(*quote_size.size(), base_wei, quote_wei, rounding)
+            .ok_or(anyhow!("[Price::base_for]: {} at {} overflows", quote_size, self))?;
+        ChainNativeAsset::new(*quote_size.chain(), *self.base(), size)
+    }
+
+    /// The `(numerator, denominator)` wei amounts of an ask that sells `base_size` at this price, in the form that
+    /// `placeMarketMakingTrades`/`requote` expect: the numerator is the base paid, the denominator is the quote received.
+    /// The quote side is rounded up, so the ask never sells below this price.
+    pub fn ask_terms(&self, base_size: &ChainNativeAsset) -> Result<(U256, U256)> {
+        let quote_size = self.quote_for(base_size, Rounding::Up)?;
+        Ok((*base_size.size(), *quote_size.size()))
+    }
+
+    /// The `(numerator, denominator)` wei amounts of a bid that buys `base_size` at this price, in the form that
+    /// `placeMarketMakingTrades`/`requote` expect: the numerator is the quote paid, the denominator is the base received.
+    /// The quote side is rounded down, so the bid never buys above this price.
+    pub fn bid_terms(&self, base_size: &ChainNativeAsset) -> Result<(U256, U256)> {
+        let quote_size = self.quote_for(base_size, Rounding::Down)?;
+        Ok((*quote_size.size(), *base_size.size()))
+    }
+
+    fn decimals(&self) -> Result<(u8, u8)> {
+        let base = self
+            .base()
+            .decimals()
+            .ok_or(anyhow!("[Price]: {} has no decimals", self.base()))?;
+        let quote = self
+            .quote()
+            .decimals()
+            .ok_or(anyhow!("[Price]: {} has no decimals", self.quote()))?;
+        Ok((base, quote))
+    }
+}
+
+impl std::fmt::Display for Price {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}/{}", self.value, self.quote(), self.base())
+    }
+}
+
+fn checked_scale_up(value: U256, exponent: u32) -> Result<U256> {
+    U256::from(10)
+        .checked_pow(U256::from(exponent))
+        .and_then(|x| value.checked_mul(x))
+        .ok_or(anyhow!("[Price]: scaling {} by 10^{} overflows", value, exponent))
+}
+
+// the serialized form of a price, checked by `Price::new` on the way in
+#[derive(Serialize, Deserialize)]
+struct PriceRepr {
+    pair: Pair,
+    value: Decimal,
+}
+
+impl TryFrom<PriceRepr> for Price {
+    type Error = anyhow::Error;
+
+    fn try_from(repr: PriceRepr) -> Result<Self> {
+        Self::new(repr.pair, repr.value)
+    }
+}
+
+impl From<Price> for PriceRepr {
+    fn from(price: Price) -> Self {
+        Self {
+            pair: price.pair,
+            value: price.value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::types::Chain;
+
+    const CHAIN: Chain = Chain::Optimism;
+
+    fn price(value: Decimal) -> Price {
+        Price::new(Pair::new(Asset::Weth, Asset::Usdc), value).unwrap()
+    }
+
+    fn weth(wei: U256) -> ChainNativeAsset {
+        ChainNativeAsset::new(CHAIN, Asset::Weth, wei).unwrap()
+    }
+
+    fn usdc(wei: u64) -> ChainNativeAsset {
+        ChainNativeAsset::new(CHAIN, Asset::Usdc, U256::from(wei)).unwrap()
+    }
+
+    #[test]
+    fn prices_must_be_positive() {
+        let pair = Pair::new(Asset::Weth, Asset::Usdc);
+        assert!(Price::new(pair, Decimal::ZERO).is_err());
+        assert!(Price::new(pair, Decimal::NEGATIVE_ONE).is_err());
+    }
+
+    #[test]
+    fn deserializing_checks_the_price_and_the_pair() {
+        let json = serde_json::to_value(price(Decimal::new(1600, 0))).unwrap();
+        assert_eq!(serde_json::from_value::<Price>(json.clone()).unwrap(), price(Decimal::new(1600, 0)));
+
+        let mut negative = json.clone();
+        negative["value"] = serde_json::to_value(Decimal::NEGATIVE_ONE).unwrap();
+        assert!(serde_json::from_value::<Price>(negative).is_err());
+
+        let mut same_assets = json;
+        same_assets["pair"]["quote"] = same_assets["pair"]["base"].clone();
+        assert!(serde_json::from_value::<Price>(same_assets.clone()).is_err());
+        assert!(serde_json::from_value::<Pair>(same_assets["pair"].clone()).is_err());
+    }
+
+    #[test]
+    fn wei_ratio_accounts_for_decimals() {
+        // 1600 USDC per WETH is 1600 * 10^6 USDC wei per 10^18 WETH wei
+        assert_eq!(price(Decimal::new(1600, 0)).wei_ratio().unwrap(), (U256::from(1600), U256::exp10(12)));
+        assert_eq!(price(Decimal::new(160025, 2)).wei_ratio().unwrap(), (U256::from(160025), U256::exp10(14)));
+        // the other way around, the decimals push the numerator up instead
+        let inverse = Price::new(Pair::new(Asset::Usdc, Asset::Weth), Decimal::new(625, 6)).unwrap();
+        assert_eq!(inverse.wei_ratio().unwrap(), (U256::from(625) * U256::exp10(6), U256::one()));
+        // same decimals on both sides
+        let dai = Price::new(Pair::new(Asset::Weth, Asset::Dai), Decimal::new(15, 1)).unwrap();
+        assert_eq!(dai.wei_ratio().unwrap(), (U256::from(15), U256::from(10)));
+    }
+
+    #[test]
+    fn quote_for_and_base_for_are_exact_when_they_can_be() {
+        let price = price(Decimal::new(1600, 0));
+        let one_and_a_half = weth(U256::exp10(17) * 15);
+        let quote = price.quote_for(&one_and_a_half, Rounding::Down).unwrap();
+        assert_eq!(quote, usdc(2_400_000_000));
+        assert_eq!(price.base_for(&quote, Rounding::Up).unwrap(), one_and_a_half);
+    }
+
+    #[test]
+    fn quote_for_rounds_to_a_whole_wei() {
+        // at 1 USDC per WETH, 10^12 WETH wei are worth one USDC wei
+        let price = price(Decimal::ONE);
+        let half = U256::exp10(11) * 5;
+        for (base, down, up, half_even) in [
+            (half, 0, 1, 0),
+            (half * 3, 1, 2, 2),
+            (half * 5, 2, 3, 2),
+            (half * 5 + 1, 2, 3, 3),
+        ] {
+            assert_eq!(price.quote_for(&weth(base), Rounding::Down).unwrap(), usdc(down));
+            assert_eq!(price.quote_for(&weth(base), Rounding::Up).unwrap(), usdc(up));
+            assert_eq!(price.quote_for(&weth(base), Rounding::HalfEven).unwrap(), usdc(half_even));
+        }
+    }
+
+    #[test]
+    fn terms_never_cross_the_price() {
+        // a single wei of WETH is worth 1.6 * 10^-9 USDC wei
+        let price = price(Decimal::new(1600, 0));
+        assert_eq!(price.ask_terms(&weth(U256::one())).unwrap(), (U256::one(), U256::one()));
+        assert_eq!(price.bid_terms(&weth(U256::one())).unwrap(), (U256::zero(), U256::one()));
+    }
+
+    #[test]
+    fn sizes_must_match_the_pair() {
+        let price = price(Decimal::new(1600, 0));
+        assert!(price.quote_for(&usdc(1), Rounding::Down).is_err());
+        assert!(price.base_for(&weth(U256::one()), Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let price = Price::new(Pair::new(Asset::Usdc, Asset::Weth), Decimal::TWO).unwrap();
+        let max = ChainNativeAsset::new_max(CHAIN, Asset::Usdc).unwrap();
+        assert!(price.quote_for(&max, Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn inverse_and_ticks() {
+        let price = price(Decimal::new(1600, 0));
+        let inverse = price.inverse().unwrap();
+        assert_eq!(inverse.pair(), &Pair::new(Asset::Usdc, Asset::Weth));
+        assert_eq!(inverse.value(), Decimal::new(625, 6));
+        let price = self::price(Decimal::new(1600257, 3));
+        let tick = Decimal::new(1, 2);
+        assert_eq!(price.round_to_tick(tick, Rounding::Down).unwrap().value(), Decimal::new(160025, 2));
+        assert_eq!(price.round_to_tick(tick, Rounding::Up).unwrap().value(), Decimal::new(160026, 2));
+        let tie = self::price(Decimal::new(1600125, 3));
+        assert_eq!(tie.round_to_tick(tick, Rounding::HalfEven).unwrap().value(), Decimal::new(160012, 2));
+        assert!(price.round_to_tick(Decimal::ZERO, Rounding::Down).is_err());
+    }
+}
@@ -0,0 +1,442 @@
+/**
+ * A runtime table of tokens, so that new listings don't need an SDK release.
+ * Registries can be built in code, or loaded from JSON/TOML files. The file format is the
+ * [Uniswap token list](https://tokenlists.org) format, so existing lists can be used as-is:
+ *
+ * ```json
+ * { "tokens": [ { "chainId": 10, "address": "0x7F5c...1607", "symbol": "USDC", "decimals": 6 } ] }
+ * ```
+ *
+ * Lists often include tokens on chains that ethers doesn't know about (e.g. Base or Celo). Those tokens are skipped when loading,
+ * since nothing in the SDK could use them.
+ */
+use crate::asset::*;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Everything we know about a single token on a single chain.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TokenInfo {
+    #[serde(rename = "chainId", alias = "chain_id", with = "crate::serde_helpers::chain_id")]
+    chain: Chain,
+    address: Address,
+    symbol: String,
+    decimals: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+}
+
+impl TokenInfo {
+    pub fn new<T: ToString>(chain: Chain, symbol: T, address: Address, decimals: u8) -> Self {
+        Self {
+            chain,
+            address,
+            symbol: symbol.to_string(),
+            decimals,
+            name: None,
+        }
+    }
+
+    /// Returns a reference to the chain the token lives on
+    pub fn chain(&self) -> &Chain {
+        &self.chain
+    }
+
+    /// Returns a reference to the address of the token contract
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// The symbol as it was listed, e.g. `"USDbC"`. Lookups ignore its case.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// The long form name of the token, if the list we loaded had one
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Converts this entry into an [`Asset`]. If the entry describes one of the built-in assets
+    /// at its canonical address, the built-in variant is returned so that it compares equal to it.
+    pub fn to_asset(&self) -> Result<Asset> {
+        let builtin = Asset::BUILTIN.into_iter().find(|x| {
+            x.symbol().eq_ignore_ascii_case(&self.symbol)
+                && x.decimals() == Some(self.decimals)
+                && x.to_address(&self.chain).ok() == Some(self.address)
+        });
+        match builtin {
+            Some(asset) => Ok(asset),
+            None => Ok(Asset::Token(RegisteredToken::new(
+                self.chain,
+                TokenSymbol::new(&self.symbol)?,
+                self.address,
+                self.decimals,
+            ))),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TokenList {
+    tokens: Vec<TokenInfo>,
+}
+
+// a token list as it's read, before the tokens on unknown chains are dropped
+#[derive(Deserialize)]
+struct ListedTokens {
+    tokens: Vec<ListedToken>,
+}
+
+#[derive(Deserialize)]
+struct ListedToken {
+    #[serde(rename = "chainId", alias = "chain_id", deserialize_with = "crate::serde_helpers::known_chain_id")]
+    chain: Option<Chain>,
+    address: Address,
+    symbol: String,
+    decimals: u8,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+impl ListedTokens {
+    fn into_registry(self) -> Result<TokenRegistry> {
+        let mut registry = TokenRegistry::new();
+        for x in self.tokens {
+            if let Some(chain) = x.chain {
+                registry.try_insert(TokenInfo {
+                    chain,
+                    address: x.address,
+                    symbol: x.symbol,
+                    decimals: x.decimals,
+                    name: x.name,
+                })?;
+            }
+        }
+        Ok(registry)
+    }
+}
+
+/// Maps (chain, symbol) to token information, and (chain, address) back to the symbol.
+/// Symbols are keyed in upper case so that lookups are case insensitive, but each token keeps the case it was listed with.
+#[derive(Clone, Debug, Default)]
+pub struct TokenRegistry {
+    by_symbol: HashMap<(Chain, String), TokenInfo>,
+    by_address: HashMap<(Chain, Address), String>,
+}
+
+impl TokenRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry seeded with every built-in [`Asset`] on every chain the SDK knows an address for.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        for chain in [Chain::Optimism, Chain::OptimismKovan, Chain::Goerli] {
+            for asset in Asset::BUILTIN {
+                if let (Ok(address), Some(decimals)) = (asset.to_address(&chain), asset.decimals()) {
+                    registry.insert(TokenInfo::new(chain, asset.symbol(), address, decimals));
+                }
+            }
+        }
+        registry
+    }
+
+    /// Parses a registry from a JSON token list. Tokens on chains that ethers doesn't know about are skipped.
+    /// Fails if the list has two tokens with the same symbol on the same chain.
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        let list: ListedTokens = serde_json::from_str(json)?;
+        list.into_registry()
+    }
+
+    /// Parses a registry from a TOML token list, with one `[[tokens]]` table per token.
+    /// Tokens on chains that ethers doesn't know about are skipped.
+    /// Fails if the list has two tokens with the same symbol on the same chain.
+    pub fn from_toml_str(toml: &str) -> Result<Self> {
+        let list: ListedTokens = toml::from_str(toml)?;
+        list.into_registry()
+    }
+
+    /// Loads a registry from a file. The format is picked by the extension, which must be `.json` or `.toml`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("json") => Self::from_json_str(&contents),
+            Some("toml") => Self::from_toml_str(&contents),
+            _ => Err(anyhow!(
+                "[TokenRegistry::from_file]: can't tell the format of {:?}, expected a .json or .toml file",
+                path
+            )),
+        }
+    }
+
+    /// Serializes the registry as a JSON token list.
+    pub fn to_json_string(&self) -> Result<String> {
+        let list = TokenList {
+            tokens: self.tokens().cloned().collect(),
+        };
+        Ok(serde_json::to_string_pretty(&list)?)
+    }
+
+    /// Adds a token to the registry, replacing any existing entry with the same chain and symbol,
+    /// and any existing entry with the same chain and address (i.e. a token that's been renamed).
+    pub fn insert(&mut self, token: TokenInfo) {
+        let key = token.symbol.to_uppercase();
+        if let Some(old) = self.by_symbol.remove(&(token.chain, key.clone())) {
+            self.by_address.remove(&(old.chain, old.address));
+        }
+        if let Some(old_key) = self.by_address.remove(&(token.chain, token.address)) {
+            self.by_symbol.remove(&(token.chain, old_key));
+        }
+        self.by_address.insert((token.chain, token.address), key.clone());
+        self.by_symbol.insert((token.chain, key), token);
+    }
+
+    /// Adds a token to the registry like [`TokenRegistry::insert`], but fails rather than replacing
+    /// an existing token with the same symbol (in any case) on the same chain.
+    pub fn try_insert(&mut self, token: TokenInfo) -> Result<()> {
+        if let Some(old) = self.get(&token.chain, &token.symbol) {
+            return Err(anyhow!(
+                "[TokenRegistry::try_insert]: chain {:?} already has a token called {:?} at {:?}, can't add {:?} at {:?}",
+                token.chain,
+                old.symbol,
+                old.address,
+                token.symbol,
+                token.address
+            ));
+        }
+        self.insert(token);
+        Ok(())
+    }
+
+    /// Adds every token in `other` to this registry. Entries in `other` win on conflict.
+    pub fn extend(&mut self, other: TokenRegistry) {
+        for token in other.by_symbol.into_values() {
+            self.insert(token);
+        }
+    }
+
+    /// Iterates over every token in the registry, in no particular order.
+    pub fn tokens(&self) -> impl Iterator<Item = &TokenInfo> {
+        self.by_symbol.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_symbol.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_symbol.is_empty()
+    }
+
+    /// Looks up a token by its symbol. Symbols are case insensitive.
+    pub fn get(&self, chain: &Chain, symbol: &str) -> Option<&TokenInfo> {
+        self.by_symbol.get(&(*chain, symbol.to_uppercase()))
+    }
+
+    /// Looks up a token by its address.
+    pub fn get_by_address(&self, chain: &Chain, address: &Address) -> Option<&TokenInfo> {
+        self.by_address
+            .get(&(*chain, *address))
+            .and_then(|key| self.by_symbol.get(&(*chain, key.clone())))
+    }
+
+    /// Returns the symbol of the token at `address`, if we know about it.
+    pub fn symbol_of(&self, chain: &Chain, address: &Address) -> Option<&str> {
+        self.get_by_address(chain, address)
+            .map(|x| x.symbol())
+    }
+
+    /// Returns the address of the token with the given symbol on `chain`.
+    pub fn address_of(&self, chain: &Chain, symbol: &str) -> Result<Address> {
+        self.get(chain, symbol)
+            .map(|x| x.address)
+            .ok_or_else(|| unknown_symbol(chain, symbol))
+    }
+
+    /// Returns the decimals of the token with the given symbol on `chain`.
+    pub fn decimals_of(&self, chain: &Chain, symbol: &str) -> Result<u8> {
+        self.get(chain, symbol)
+            .map(|x| x.decimals)
+            .ok_or_else(|| unknown_symbol(chain, symbol))
+    }
+
+    /// Resolves a symbol into an [`Asset`] that can be used anywhere the built-in assets can.
+    pub fn asset(&self, chain: &Chain, symbol: &str) -> Result<Asset> {
+        self.get(chain, symbol)
+            .ok_or_else(|| unknown_symbol(chain, symbol))?
+            .to_asset()
+    }
+
+    /// Resolves a token address into an [`Asset`].
+    pub fn asset_by_address(&self, chain: &Chain, address: &Address) -> Result<Asset> {
+        self.get_by_address(chain, address)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Token at address {:?} isn't in the registry for chain {:?}",
+                    address,
+                    chain
+                )
+            })?
+            .to_asset()
+    }
+}
+
+impl FromIterator<TokenInfo> for TokenRegistry {
+    fn from_iter<I: IntoIterator<Item = TokenInfo>>(iter: I) -> Self {
+        let mut registry = Self::new();
+        for token in iter {
+            registry.insert(token);
+        }
+        registry
+    }
+}
+
+fn unknown_symbol(chain: &Chain, symbol: &str) -> anyhow::Error {
+    anyhow!(
+        "Token {:?} isn't in the registry for chain {:?}",
+        symbol,
+        chain
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // an excerpt of the Uniswap Labs default list, with tokens on chains ethers doesn't know (OP Goerli, Base, Celo)
+    const UNISWAP_DEFAULT: &str = include_str!("../fixtures/uniswap-default.tokenlist.json");
+
+    fn address(x: &str) -> Address {
+        x.parse().unwrap()
+    }
+
+    #[test]
+    fn loads_a_real_list_and_skips_unknown_chains() {
+        let registry = TokenRegistry::from_json_str(UNISWAP_DEFAULT).unwrap();
+        assert_eq!(registry.len(), 5);
+        let usdc = registry.get(&Chain::Optimism, "usdc").unwrap();
+        assert_eq!(usdc.address(), &address("0x7F5c764cBc14f9669B88837ca1490cCa17c31607"));
+        assert_eq!(usdc.decimals(), 6);
+        assert_eq!(usdc.name(), Some("USDCoin"));
+        assert_eq!(
+            registry.address_of(&Chain::Mainnet, "WETH").unwrap(),
+            address("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2")
+        );
+        assert_eq!(
+            registry.symbol_of(&Chain::Polygon, &address("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174")),
+            Some("USDC")
+        );
+        assert!(registry
+            .tokens()
+            .all(|x| !x.symbol().eq_ignore_ascii_case("USDBC") && !x.symbol().eq_ignore_ascii_case("CELO")));
+        assert_eq!(registry.asset(&Chain::Optimism, "USDC").unwrap(), Asset::Usdc);
+    }
+
+    #[test]
+    fn skips_unknown_chains_in_toml() {
+        let toml = r#"
+            [[tokens]]
+            chainId = 8453
+            address = "0xd9aAEc86B65D86f6A7B5B1b0c42FFA531710b6CA"
+            symbol = "USDbC"
+            decimals = 6
+
+            [[tokens]]
+            chainId = 10
+            address = "0x4200000000000000000000000000000000000042"
+            symbol = "OP"
+            decimals = 18
+        "#;
+        let registry = TokenRegistry::from_toml_str(toml).unwrap();
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.decimals_of(&Chain::Optimism, "op").unwrap(), 18);
+    }
+
+    #[test]
+    fn a_list_round_trips() {
+        let registry = TokenRegistry::from_json_str(UNISWAP_DEFAULT).unwrap();
+        let reloaded = TokenRegistry::from_json_str(&registry.to_json_string().unwrap()).unwrap();
+        assert_eq!(reloaded.len(), registry.len());
+        for token in registry.tokens() {
+            assert_eq!(reloaded.get(token.chain(), token.symbol()), Some(token));
+        }
+    }
+
+    #[test]
+    fn symbols_keep_their_case_but_look_up_case_insensitively() {
+        let usdbc = address("0x7F5c764cBc14f9669B88837ca1490cCa17c31607");
+        let mut registry = TokenRegistry::new();
+        registry.insert(TokenInfo::new(Chain::Optimism, "USDbC", usdbc, 6));
+        assert_eq!(registry.get(&Chain::Optimism, "usdbc").unwrap().symbol(), "USDbC");
+        assert_eq!(registry.address_of(&Chain::Optimism, "USDBC").unwrap(), usdbc);
+        assert_eq!(registry.symbol_of(&Chain::Optimism, &usdbc), Some("USDbC"));
+        let json = registry.to_json_string().unwrap();
+        assert!(json.contains("\"USDbC\""));
+        let reloaded = TokenRegistry::from_json_str(&json).unwrap();
+        assert_eq!(reloaded.get(&Chain::Optimism, "USDbC").unwrap().symbol(), "USDbC");
+    }
+
+    #[test]
+    fn a_list_with_a_duplicate_symbol_doesnt_load() {
+        let toml = r#"
+            [[tokens]]
+            chainId = 10
+            address = "0x4200000000000000000000000000000000000042"
+            symbol = "OP"
+            decimals = 18
+
+            [[tokens]]
+            chainId = 10
+            address = "0x4200000000000000000000000000000000000043"
+            symbol = "op"
+            decimals = 18
+        "#;
+        assert!(TokenRegistry::from_toml_str(toml).is_err());
+        // the same symbol on another chain is fine
+        let toml = toml.replacen("chainId = 10", "chainId = 69", 1);
+        assert_eq!(TokenRegistry::from_toml_str(&toml).unwrap().len(), 2);
+        let (op, other) = (address("0x4200000000000000000000000000000000000042"), address("0x4200000000000000000000000000000000000043"));
+        let mut registry = TokenRegistry::new();
+        registry.try_insert(TokenInfo::new(Chain::Optimism, "OP", op, 18)).unwrap();
+        assert!(registry.try_insert(TokenInfo::new(Chain::Optimism, "Op", other, 18)).is_err());
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[test]
+    fn renaming_a_token_drops_its_old_symbol() {
+        let op = address("0x4200000000000000000000000000000000000042");
+        let mut registry = TokenRegistry::new();
+        registry.insert(TokenInfo::new(Chain::Optimism, "OLD", op, 18));
+        registry.insert(TokenInfo::new(Chain::Optimism, "OP", op, 18));
+        assert_eq!(registry.len(), 1);
+        assert!(registry.get(&Chain::Optimism, "OLD").is_none());
+        assert!(registry.address_of(&Chain::Optimism, "OLD").is_err());
+        assert_eq!(registry.symbol_of(&Chain::Optimism, &op), Some("OP"));
+        // the same address on another chain is a different token
+        registry.insert(TokenInfo::new(Chain::OptimismKovan, "OLD", op, 18));
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.symbol_of(&Chain::Optimism, &op), Some("OP"));
+    }
+
+    #[test]
+    fn moving_a_symbol_drops_its_old_address() {
+        let old = address("0x4200000000000000000000000000000000000042");
+        let new = address("0x4200000000000000000000000000000000000043");
+        let mut registry = TokenRegistry::new();
+        registry.insert(TokenInfo::new(Chain::Optimism, "OP", old, 18));
+        registry.insert(TokenInfo::new(Chain::Optimism, "OP", new, 18));
+        assert_eq!(registry.len(), 1);
+        assert!(registry.symbol_of(&Chain::Optimism, &old).is_none());
+        assert_eq!(registry.address_of(&Chain::Optimism, "OP").unwrap(), new);
+    }
+}
@@ -0,0 +1,41 @@
+use ethers::core::types::Chain;
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChainRepr {
+    Id(u64),
+    Name(String),
+}
+
+/**
+ * ethers-rs only implements `Deserialize` for `Chain`, and only by name.
+ * Everything we write to disk keys chains by their EIP-155 id, so we do that here.
+ */
+pub(crate) mod chain_id {
+    use super::ChainRepr;
+    use ethers::core::types::Chain;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::str::FromStr;
+
+    pub(crate) fn serialize<S: Serializer>(chain: &Chain, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(u64::from(*chain))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Chain, D::Error> {
+        match ChainRepr::deserialize(deserializer)? {
+            ChainRepr::Id(id) => Chain::try_from(id).map_err(de::Error::custom),
+            ChainRepr::Name(name) => Chain::from_str(&name).map_err(de::Error::custom),
+        }
+    }
+}
+
+/// Like [`chain_id::deserialize`], but a chain that ethers doesn't know about becomes `None` instead of an error.
+/// Token lists cover far more chains than ethers does, and one of them shouldn't stop the rest of the list from loading.
+pub(crate) fn known_chain_id<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<Chain>, D::Error> {
+    Ok(match ChainRepr::deserialize(deserializer)? {
+        ChainRepr::Id(id) => Chain::try_from(id).ok(),
+        ChainRepr::Name(name) => Chain::from_str(&name).ok(),
+    })
+}
@@ -0,0 +1,256 @@
+/**
+ * Serde support for [`ChainNativeAsset`] and [`AssetSwap`].
+ *
+ * A serialized [`ChainNativeAsset`] carries its chain (by EIP-155 id), its asset, and its size in one of three forms,
+ * keyed by the name of the form so that it can always be read back unambiguously:
+ *
+ * - `{"chain": 10, "asset": "Weth", "wei": "1500000000000000000"}` is the default
+ * - `{"chain": 10, "asset": "Weth", "human": "1.5"}` is easier to read
+ * - `{"chain": 10, "asset": "Weth", "hex": "0x14d1120d7b160000"}` is the most compact
+ *
+ * Deserialization accepts any of the three. To pick the form that gets written, either wrap the value with
+ * [`ChainNativeAsset::with_repr`]/[`AssetSwap::with_repr`], or annotate a field with one of the modules in here, e.g.
+ *
+ * ```ignore
+ * #[derive(Serialize, Deserialize)]
+ * struct Fill {
+ *     #[serde(with = "numeraire::size_repr::human")]
+ *     swap: AssetSwap,
+ * }
+ * ```
+ */
+use crate::asset::Asset;
+use crate::sized_asset::{ChainNativeAsset, Rounding};
+use crate::swap::AssetSwap;
+use ethers::core::types::{Chain, U256};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// The form a size is serialized in.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SizeRepr {
+    /// The size in wei, as a decimal string
+    #[default]
+    Wei,
+    /// The human readable size, as a decimal string (see [`ChainNativeAsset::to_human_string`])
+    Human,
+    /// The size in wei, as a `0x` prefixed big endian hex string without leading zeros
+    Hex,
+}
+
+/// A value that serializes its sizes in a particular [`SizeRepr`].
+pub struct WithRepr<'a, T> {
+    value: &'a T,
+    repr: SizeRepr,
+}
+
+impl ChainNativeAsset {
+    /// Wraps the asset so that it serializes its size in `repr`.
+    pub fn with_repr(&self, repr: SizeRepr) -> WithRepr<'_, Self> {
+        WithRepr { value: self, repr }
+    }
+}
+
+impl AssetSwap {
+    /// Wraps the swap so that it serializes both of its sizes in `repr`.
+    pub fn with_repr(&self, repr: SizeRepr) -> WithRepr<'_, Self> {
+        WithRepr { value: self, repr }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChainNativeAssetRepr {
+    #[serde(with = "crate::serde_helpers::chain_id")]
+    chain: Chain,
+    asset: Asset,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    wei: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    human: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hex: Option<String>,
+}
+
+impl<'a> Serialize for WithRepr<'a, ChainNativeAsset> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let asset = self.value;
+        let (wei, human, hex) = match self.repr {
+            SizeRepr::Wei => (Some(asset.size().to_string()), None, None),
+            SizeRepr::Human => (None, Some(asset.to_human_string()), None),
+            SizeRepr::Hex => (None, None, Some(format!("{:#x}", asset.size()))),
+        };
+        ChainNativeAssetRepr {
+            chain: *asset.chain(),
+            asset: *asset.asset(),
+            wei,
+            human,
+            hex,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'a> Serialize for WithRepr<'a, AssetSwap> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct AssetSwapRepr<'b> {
+            source: WithRepr<'b, ChainNativeAsset>,
+            target: WithRepr<'b, ChainNativeAsset>,
+        }
+        AssetSwapRepr {
+            source: self.value.source().with_repr(self.repr),
+            target: self.value.target().with_repr(self.repr),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl Serialize for ChainNativeAsset {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.with_repr(SizeRepr::default()).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChainNativeAsset {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ChainNativeAssetRepr::deserialize(deserializer)?;
+        match (repr.wei, repr.human, repr.hex) {
+            (Some(wei), None, None) => {
+                let size = U256::from_dec_str(wei.trim()).map_err(|e| {
+                    de::Error::custom(format!("{:?} isn't a wei amount: {:?}", wei, e))
+                })?;
+                ChainNativeAsset::new(repr.chain, repr.asset, size).map_err(de::Error::custom)
+            }
+            (None, Some(human), None) => {
+                // a stored human size that isn't a whole number of wei is corrupt, so don't round it away
+                let asset = ChainNativeAsset::from_human_str(repr.chain, repr.asset, &human, Rounding::Down)
+                    .map_err(de::Error::custom)?;
+                if asset.to_human_string() != canonical_decimal(&human) {
+                    return Err(de::Error::custom(format!(
+                        "{:?} has more decimal places than {} supports",
+                        human, repr.asset
+                    )));
+                }
+                Ok(asset)
+            }
+            (None, None, Some(hex)) => {
+                let digits = hex.trim();
+                let size = U256::from_str_radix(digits.strip_prefix("0x").unwrap_or(digits), 16)
+                    .map_err(|e| de::Error::custom(format!("{:?} isn't a hex amount: {:?}", hex, e)))?;
+                ChainNativeAsset::new(repr.chain, repr.asset, size).map_err(de::Error::custom)
+            }
+            _ => Err(de::Error::custom(
+                "expected exactly one of `wei`, `human` or `hex`",
+            )),
+        }
+    }
+}
+
+// strips insignificant zeros and an explicit `+`, so that "1.50", "01.5" and "+1.5" compare equal to the "1.5" we'd write
+fn canonical_decimal(s: &str) -> String {
+    let s = s.trim();
+    let s = s.strip_prefix('+').unwrap_or(s);
+    let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+    let int = int.trim_start_matches('0');
+    let frac = frac.trim_end_matches('0');
+    match (int.is_empty(), frac.is_empty()) {
+        (true, true) => "0".to_string(),
+        (true, false) => format!("0.{}", frac),
+        (false, true) => int.to_string(),
+        (false, false) => format!("{}.{}", int, frac),
+    }
+}
+
+macro_rules! size_repr_module {
+    ($name:ident, $repr:expr) => {
+        #[doc = concat!("Serializes a field's sizes as [`", stringify!($repr), "`], for use with `#[serde(with = \"numeraire::size_repr::", stringify!($name), "\")]`.")]
+        pub mod $name {
+            use super::{SizeRepr, WithRepr};
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+            pub fn serialize<T, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                for<'a> WithRepr<'a, T>: Serialize,
+            {
+                WithRepr { value, repr: $repr }.serialize(serializer)
+            }
+
+            pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<T, D::Error> {
+                T::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+size_repr_module!(wei, SizeRepr::Wei);
+size_repr_module!(human, SizeRepr::Human);
+size_repr_module!(hex, SizeRepr::Hex);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weth(size: U256) -> ChainNativeAsset {
+        ChainNativeAsset::new(Chain::Optimism, Asset::Weth, size).unwrap()
+    }
+
+    #[test]
+    fn every_repr_round_trips() {
+        for size in [U256::zero(), U256::exp10(17) * 15, U256::MAX] {
+            let asset = weth(size);
+            for repr in [SizeRepr::Wei, SizeRepr::Human, SizeRepr::Hex] {
+                let json = serde_json::to_string(&asset.with_repr(repr)).unwrap();
+                let back: ChainNativeAsset = serde_json::from_str(&json).unwrap();
+                assert_eq!(back, asset, "{:?}: {}", repr, json);
+            }
+        }
+    }
+
+    #[test]
+    fn hex_is_a_big_endian_quantity() {
+        let asset = weth(U256::exp10(17) * 15);
+        let json = serde_json::to_value(asset.with_repr(SizeRepr::Hex)).unwrap();
+        assert_eq!(json, serde_json::json!({"chain": 10, "asset": "Weth", "hex": "0x14d1120d7b160000"}));
+        // the same size as `to_hex_string` writes it is little endian, and isn't what `hex` holds
+        assert_ne!(format!("0x{}", asset.to_hex_string()), "0x14d1120d7b160000");
+        let unprefixed: ChainNativeAsset =
+            serde_json::from_value(serde_json::json!({"chain": 10, "asset": "Weth", "hex": "14d1120d7b160000"})).unwrap();
+        assert_eq!(unprefixed, asset);
+    }
+
+    #[test]
+    fn swaps_round_trip() {
+        let swap = AssetSwap::new(
+            weth(U256::exp10(18)),
+            ChainNativeAsset::new(Chain::Optimism, Asset::Usdc, U256::from(1_600_000_000)).unwrap(),
+        );
+        for repr in [SizeRepr::Wei, SizeRepr::Human, SizeRepr::Hex] {
+            let json = serde_json::to_string(&swap.with_repr(repr)).unwrap();
+            let back: AssetSwap = serde_json::from_str(&json).unwrap();
+            assert_eq!(back.source(), swap.source());
+            assert_eq!(back.target(), swap.target());
+        }
+    }
+
+    #[test]
+    fn ambiguous_or_lossy_sizes_are_refused() {
+        let both = serde_json::json!({"chain": 10, "asset": "Usdc", "wei": "1", "hex": "0x1"});
+        assert!(serde_json::from_value::<ChainNativeAsset>(both).is_err());
+        let too_precise = serde_json::json!({"chain": 10, "asset": "Usdc", "human": "0.0000001"});
+        assert!(serde_json::from_value::<ChainNativeAsset>(too_precise).is_err());
+    }
+
+    #[test]
+    fn human_sizes_can_be_written_loosely() {
+        let expected = weth(U256::exp10(17) * 15);
+        for human in ["1.5", "+1.5", "01.50", " 1.5 ", "+001.500"] {
+            let json = serde_json::json!({"chain": 10, "asset": "Weth", "human": human});
+            assert_eq!(serde_json::from_value::<ChainNativeAsset>(json).unwrap(), expected, "{:?}", human);
+        }
+        for human in ["-1.5", "++1.5", "+"] {
+            let json = serde_json::json!({"chain": 10, "asset": "Weth", "human": human});
+            assert!(serde_json::from_value::<ChainNativeAsset>(json).is_err(), "{:?}", human);
+        }
+    }
+}
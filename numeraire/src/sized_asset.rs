@@ -4,10 +4,14 @@
 use crate::asset;
 use anyhow::{anyhow, Result};
 pub use ethers::core::types::{Address, Chain, U256};
+use ethers::core::abi::ethereum_types::U512;
 use rust_decimal::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::convert::From;
+use std::iter::Sum;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct ChainNativeAsset {
     chain: asset::Chain,
     asset: asset::Asset,
@@ -93,47 +97,635 @@ impl ChainNativeAsset {
         base64::encode(bytes)
     }
 
+    /// Decodes a size written by [`ChainNativeAsset::to_hex_string`], i.e. little endian bytes as unprefixed hex. Shorter strings are zero padded.
+    /// A `0x` prefix is refused, since it means a big endian quantity (as [`crate::size_repr::SizeRepr::Hex`] writes) that would decode to a different number.
+    pub fn from_hex_string(chain: Chain, asset: asset::Asset, hex_size: &str) -> Result<Self> {
+        let trimmed = hex_size.trim();
+        if trimmed.starts_with("0x") || trimmed.starts_with("0X") {
+            return Err(anyhow!(
+                "[ChainNativeAsset::from_hex_string]: {:?} is a big endian 0x quantity, but this decodes little endian hex",
+                hex_size
+            ));
+        }
+        let bytes = hex::decode(trimmed)
+            .map_err(|e| anyhow!("[ChainNativeAsset::from_hex_string]: {:?} isn't hex: {}", hex_size, e))?;
+        Self::new(chain, asset, from_little_endian(&bytes)?)
+    }
+
+    /// Decodes a size written by [`ChainNativeAsset::to_base64_string`].
+    pub fn from_base64_string(chain: Chain, asset: asset::Asset, base64_size: &str) -> Result<Self> {
+        let bytes = base64::decode(base64_size.trim()).map_err(|e| {
+            anyhow!("[ChainNativeAsset::from_base64_string]: {:?} isn't base64: {}", base64_size, e)
+        })?;
+        Self::new(chain, asset, from_little_endian(&bytes)?)
+    }
+
+    /// Represents the size of the asset as a human readable decimal string, e.g. `"1.5"` for 1.5 WETH.
+    /// This is lossless for every possible size, including [`ChainNativeAsset::new_max`].
+    pub fn to_human_string(&self) -> String {
+        wei_to_human(self.size(), self.decimals())
+    }
+
     /// Represents the size of the asset as a Decimal type.
-    /// WARNING: If you try to represent something larger than a u128, this will fail.
-    pub fn to_human_decimal(&self) -> Decimal {
-        Decimal::from_i128_with_scale(
-            i128::from_le_bytes(self.size().as_u128().to_le_bytes()).abs(),
-            self.asset().decimals().unwrap() as u32,
-        )
+    /// `Decimal` can only hold 96 bits of mantissa and 28 decimal places, so this fails with
+    /// [`ConversionError::DecimalOverflow`] rather than losing precision. Use [`ChainNativeAsset::to_human_string`] if you need every size.
+    pub fn to_human_decimal(&self) -> std::result::Result<Decimal, ConversionError> {
+        let human = self.to_human_string();
+        Decimal::from_str_exact(&human).map_err(|_| ConversionError::DecimalOverflow(human))
     }
 
-    /// This will panic if you try to represent something that isn't native on an EVM chain...
+    /// Converts a human readable size to wei, truncating anything smaller than a wei.
     pub fn from_human_decimal(
         chain: Chain,
         asset: asset::Asset,
         human_size: Decimal,
     ) -> Result<Self> {
-        let raw_size = (human_size
-            * Decimal::from(10u64.pow(asset.decimals().ok_or(anyhow!(
-                "Asset {:?} isn't supported on chain {:?} at this moment",
-                asset,
-                chain
-            ))? as u32)))
-        .trunc(); // this is strictly the integer part
-                  // we've got to convert this to a U256
-
-        // so, Decimal types are of the form m/10^e, where -2**96 < m < 2**96 and 0 <= e <= 28. So the largest thing we could represent is 2**96. For an 18 decimal coin, we still have 78 bits of integer tokens left. That's more than enough to represent anything we want
-        let u128_size = raw_size.to_u128().ok_or(anyhow!(
-            "Invalid human_size! Doesn't represent as a u128... {}",
-            &human_size
-        ))?;
-        let as_u256 = U256::from(u128_size);
+        Ok(Self::from_human_decimal_rounded(
+            chain,
+            asset,
+            human_size,
+            Rounding::Down,
+        )?)
+    }
 
-        Self::new(chain, asset, as_u256)
+    /// Converts a human readable size to wei, rounding anything smaller than a wei with `rounding`.
+    pub fn from_human_decimal_rounded(
+        chain: Chain,
+        asset: asset::Asset,
+        human_size: Decimal,
+        rounding: Rounding,
+    ) -> std::result::Result<Self, ConversionError> {
+        Self::from_human_str(chain, asset, &human_size.to_string(), rounding)
+    }
+
+    /// Converts a human readable decimal string (e.g. `"1234.5678"`) to wei, rounding anything smaller than a wei with `rounding`.
+    /// Unlike going through `Decimal`, this works for every size that fits in a U256.
+    pub fn from_human_str(
+        chain: Chain,
+        asset: asset::Asset,
+        human_size: &str,
+        rounding: Rounding,
+    ) -> std::result::Result<Self, ConversionError> {
+        let decimals = asset
+            .decimals()
+            .ok_or(ConversionError::UnsupportedAsset(asset))?;
+        let size = human_to_wei(human_size, decimals, rounding)?;
+        Ok(Self { chain, asset, size })
     }
 
+    /// Converts a human readable size to wei, truncating anything smaller than a wei.
     pub fn from_human_string<T: ToString>(
         chain: Chain,
         asset: asset::Asset,
         human_size: &T,
     ) -> Result<Self> {
-        let human_size_string = human_size.to_string();
-        let human_size_dec = Decimal::from_str_exact(&human_size_string)?;
-        Self::from_human_decimal(chain, asset, human_size_dec)
+        Ok(Self::from_human_str(
+            chain,
+            asset,
+            &human_size.to_string(),
+            Rounding::Down,
+        )?)
+    }
+
+    // every asset we can construct a ChainNativeAsset from has decimals
+    fn decimals(&self) -> u8 {
+        self.asset().decimals().unwrap()
+    }
+}
+
+// Checked arithmetic. Everything here fails if the chain or asset of the operands differ,
+// so that we never silently add USDC to WETH.
+impl ChainNativeAsset {
+    /// Returns true if `other` is the same asset on the same chain, i.e. if the two can be combined.
+    pub fn is_same_kind(&self, other: &Self) -> bool {
+        self.chain == other.chain && self.asset == other.asset
+    }
+
+    fn ensure_same_kind(&self, other: &Self) -> Result<()> {
+        if self.is_same_kind(other) {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "[ChainNativeAsset]: can't combine {:?} on {:?} with {:?} on {:?}",
+                self.asset,
+                self.chain,
+                other.asset,
+                other.chain
+            ))
+        }
+    }
+
+    fn with_size(&self, size: U256) -> Self {
+        Self {
+            chain: self.chain,
+            asset: self.asset,
+            size,
+        }
+    }
+
+    /// Adds two sizes of the same asset. Fails on a mismatch, or if the sum overflows.
+    pub fn checked_add(&self, other: &Self) -> Result<Self> {
+        self.ensure_same_kind(other)?;
+        let size = self.size.checked_add(other.size).ok_or(anyhow!(
+            "[ChainNativeAsset::checked_add]: {} + {} overflows",
+            self,
+            other
+        ))?;
+        Ok(self.with_size(size))
+    }
+
+    /// Subtracts `other` from `self`. Fails on a mismatch, or if `other` is larger than `self`.
+    pub fn checked_sub(&self, other: &Self) -> Result<Self> {
+        self.ensure_same_kind(other)?;
+        let size = self.size.checked_sub(other.size).ok_or(anyhow!(
+            "[ChainNativeAsset::checked_sub]: {} - {} underflows",
+            self,
+            other
+        ))?;
+        Ok(self.with_size(size))
+    }
+
+    /// Scales the size by `numerator / denominator`, rounding the result to a whole wei.
+    /// The intermediate product is 512 bits wide, so this only fails if the result doesn't fit in a U256 or `denominator` is zero.
+    pub fn checked_scale(&self, numerator: U256, denominator: U256, rounding: Rounding) -> Result<Self> {
+        let size = mul_div(self.size, numerator, denominator, rounding).ok_or(anyhow!(
+            "[ChainNativeAsset::checked_scale]: {} * {} / {} doesn't fit in a U256",
+            self,
+            numerator,
+            denominator
+        ))?;
+        Ok(self.with_size(size))
+    }
+
+    /// Scales the size by a non-negative decimal factor (e.g. `0.9995` to take off a 5 bps fee), rounding the result to a whole wei.
+    pub fn checked_scale_by(&self, factor: Decimal, rounding: Rounding) -> Result<Self> {
+        let (numerator, denominator) = decimal_to_ratio(factor).ok_or(anyhow!(
+            "[ChainNativeAsset::checked_scale_by]: can't scale by a negative factor ({})",
+            factor
+        ))?;
+        self.checked_scale(numerator, denominator, rounding)
+    }
+
+    /// Compares two sizes of the same asset. Fails on a mismatch.
+    pub fn try_cmp(&self, other: &Self) -> Result<Ordering> {
+        self.ensure_same_kind(other)?;
+        Ok(self.size.cmp(&other.size))
+    }
+
+    /// Returns the smaller of two sizes of the same asset. Fails on a mismatch.
+    pub fn try_min(&self, other: &Self) -> Result<Self> {
+        match self.try_cmp(other)? {
+            Ordering::Greater => Ok(other.clone()),
+            _ => Ok(self.clone()),
+        }
+    }
+
+    /// Returns the larger of two sizes of the same asset. Fails on a mismatch.
+    pub fn try_max(&self, other: &Self) -> Result<Self> {
+        match self.try_cmp(other)? {
+            Ordering::Less => Ok(other.clone()),
+            _ => Ok(self.clone()),
+        }
+    }
+
+    /// Sums sizes of `asset` on `chain`. Unlike `Iterator::sum`, an empty iterator gives zero.
+    pub fn checked_sum<'a, I: IntoIterator<Item = &'a ChainNativeAsset>>(
+        chain: Chain,
+        asset: asset::Asset,
+        items: I,
+    ) -> Result<Self> {
+        items
+            .into_iter()
+            .try_fold(Self::new_zero(chain, asset)?, |acc, x| acc.checked_add(x))
+    }
+}
+
+/// Sizes are only ordered against the same asset on the same chain; anything else is incomparable.
+/// There's deliberately no `Ord`, since that would have to order USDC against WETH. Use [`ChainNativeAsset::try_cmp`] to get an error instead.
+impl PartialOrd for ChainNativeAsset {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.try_cmp(other).ok()
+    }
+}
+
+/// Sums the sizes, failing if the iterator is empty or mixes assets or chains.
+impl Sum<ChainNativeAsset> for Result<ChainNativeAsset> {
+    fn sum<I: Iterator<Item = ChainNativeAsset>>(mut iter: I) -> Self {
+        let first = iter
+            .next()
+            .ok_or(anyhow!("[ChainNativeAsset::sum]: can't sum an empty iterator"))?;
+        iter.try_fold(first, |acc, x| acc.checked_add(&x))
+    }
+}
+
+/// Sums the sizes, failing if the iterator is empty or mixes assets or chains.
+impl<'a> Sum<&'a ChainNativeAsset> for Result<ChainNativeAsset> {
+    fn sum<I: Iterator<Item = &'a ChainNativeAsset>>(iter: I) -> Self {
+        iter.cloned().sum()
+    }
+}
+
+impl std::fmt::Display for ChainNativeAsset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.to_human_string(), self.asset())
+    }
+}
+
+/// How to round a human readable size that has more precision than the asset does.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Rounding {
+    /// Towards zero, i.e. truncate
+    Down,
+    /// Away from zero
+    Up,
+    /// To the nearest wei, with ties going to the even wei (banker's rounding)
+    HalfEven,
+}
+
+/// Errors from converting between wei and human readable sizes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConversionError {
+    /// The asset has no decimals, so it can't live on chain
+    UnsupportedAsset(asset::Asset),
+    /// The input wasn't a plain, non-negative decimal number
+    InvalidNumber(String),
+    /// The size doesn't fit in a U256 once converted to wei
+    Overflow(String),
+    /// The size doesn't fit in a `Decimal` without losing precision
+    DecimalOverflow(String),
+}
+
+impl std::fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConversionError::UnsupportedAsset(asset) => {
+                write!(f, "Asset {:?} has no decimals, so it can't be sized in wei", asset)
+            }
+            ConversionError::InvalidNumber(s) => {
+                write!(f, "{:?} isn't a non-negative decimal number", s)
+            }
+            ConversionError::Overflow(s) => write!(f, "{} doesn't fit in a U256 once converted to wei", s),
+            ConversionError::DecimalOverflow(s) => {
+                write!(f, "{} can't be represented as a Decimal without losing precision", s)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+// the inverse of `U256::to_little_endian`, refusing anything that doesn't fit rather than truncating it
+fn from_little_endian(bytes: &[u8]) -> Result<U256> {
+    if bytes.len() > 32 {
+        return Err(anyhow!(
+            "[ChainNativeAsset]: {} bytes don't fit in a U256",
+            bytes.len()
+        ));
+    }
+    Ok(U256::from_little_endian(bytes))
+}
+
+fn wei_to_human(size: &U256, decimals: u8) -> String {
+    let digits = size.to_string();
+    let decimals = decimals as usize;
+    if decimals == 0 {
+        return digits;
+    }
+    // left pad so that there's always at least one integer digit
+    let padded = format!("{:0>width$}", digits, width = decimals + 1);
+    let (int_part, frac_part) = padded.split_at(padded.len() - decimals);
+    let frac_part = frac_part.trim_end_matches('0');
+    if frac_part.is_empty() {
+        int_part.to_string()
+    } else {
+        format!("{}.{}", int_part, frac_part)
+    }
+}
+
+fn human_to_wei(
+    human_size: &str,
+    decimals: u8,
+    rounding: Rounding,
+) -> std::result::Result<U256, ConversionError> {
+    let invalid = || ConversionError::InvalidNumber(human_size.to_string());
+    let overflow = || ConversionError::Overflow(human_size.to_string());
+
+    let trimmed = human_size.trim();
+    let trimmed = trimmed.strip_prefix('+').unwrap_or(trimmed);
+    let (int_part, frac_part) = trimmed.split_once('.').unwrap_or((trimmed, ""));
+    if (int_part.is_empty() && frac_part.is_empty())
+        || !int_part.bytes().all(|b| b.is_ascii_digit())
+        || !frac_part.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    // everything past `decimals` places is smaller than a wei, and only matters for rounding
+    let decimals = decimals as usize;
+    let (kept, dropped) = frac_part.split_at(frac_part.len().min(decimals));
+
+    let mut wei = U256::zero();
+    let digits = int_part
+        .bytes()
+        .chain(kept.bytes())
+        .chain(std::iter::repeat_n(b'0', decimals - kept.len()));
+    for digit in digits {
+        wei = wei
+            .checked_mul(U256::from(10))
+            .and_then(|x| x.checked_add(U256::from(digit - b'0')))
+            .ok_or_else(overflow)?;
+    }
+
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => dropped.bytes().any(|b| b != b'0'),
+        Rounding::HalfEven => match dropped.as_bytes().first() {
+            None => false,
+            Some(b'5') => {
+                dropped[1..].bytes().any(|b| b != b'0') || wei.bit(0)
+            }
+            Some(first) => *first > b'5',
+        },
+    };
+    if round_up {
+        wei = wei.checked_add(U256::one()).ok_or_else(overflow)?;
+    }
+    Ok(wei)
+}
+
+/// Computes `a * numerator / denominator` with a 512 bit intermediate, rounding the quotient.
+/// Returns `None` if `denominator` is zero or the result doesn't fit in a U256.
+pub(crate) fn mul_div(a: U256, numerator: U256, denominator: U256, rounding: Rounding) -> Option<U256> {
+    if denominator.is_zero() {
+        return None;
+    }
+    let product = a.full_mul(numerator);
+    let denominator = U512::from(denominator);
+    let (quotient, remainder) = product.div_mod(denominator);
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => !remainder.is_zero(),
+        Rounding::HalfEven => match (remainder * U512::from(2)).cmp(&denominator) {
+            Ordering::Less => false,
+            Ordering::Greater => true,
+            Ordering::Equal => quotient.bit(0),
+        },
+    };
+    let quotient = if round_up { quotient + U512::one() } else { quotient };
+    U256::try_from(quotient).ok()
+}
+
+/// Splits a non-negative `Decimal` into an exact `(numerator, 10^scale)` pair.
+pub(crate) fn decimal_to_ratio(value: Decimal) -> Option<(U256, U256)> {
+    if value.is_sign_negative() && !value.is_zero() {
+        return None;
+    }
+    let numerator = U256::from(value.mantissa().unsigned_abs());
+    let denominator = U256::exp10(value.scale() as usize);
+    Some((numerator, denominator))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::Asset;
+
+    const CHAIN: Chain = Chain::Optimism;
+
+    fn wei(asset: Asset, human: &str, rounding: Rounding) -> std::result::Result<U256, ConversionError> {
+        ChainNativeAsset::from_human_str(CHAIN, asset, human, rounding).map(|x| *x.size())
+    }
+
+    #[test]
+    fn max_size_round_trips_through_human_strings() {
+        for asset in [Asset::Weth, Asset::Usdc, Asset::Wbtc] {
+            let max = ChainNativeAsset::new_max(CHAIN, asset).unwrap();
+            let human = max.to_human_string();
+            assert_eq!(wei(asset, &human, Rounding::Down).unwrap(), U256::MAX, "{}", asset);
+            assert_eq!(wei(asset, &human, Rounding::Up).unwrap(), U256::MAX, "{}", asset);
+        }
+        let weth = ChainNativeAsset::new_max(CHAIN, Asset::Weth).unwrap();
+        assert_eq!(
+            weth.to_human_string(),
+            "115792089237316195423570985008687907853269984665640564039457.584007913129639935"
+        );
+    }
+
+    #[test]
+    fn decimals_follow_the_asset() {
+        assert_eq!(wei(Asset::Weth, "1.5", Rounding::Down).unwrap(), U256::exp10(17) * 15);
+        assert_eq!(wei(Asset::Usdc, "1.5", Rounding::Down).unwrap(), U256::from(1_500_000));
+        assert_eq!(wei(Asset::Weth, "0.000000000000000001", Rounding::Down).unwrap(), U256::one());
+        assert_eq!(wei(Asset::Usdc, "0.000001", Rounding::Down).unwrap(), U256::one());
+        // a wei of WETH is far below a wei of USDC
+        assert_eq!(wei(Asset::Usdc, "0.000000000000000001", Rounding::Down).unwrap(), U256::zero());
+
+        let usdc = ChainNativeAsset::new(CHAIN, Asset::Usdc, U256::from(1_500_000)).unwrap();
+        let weth = ChainNativeAsset::new(CHAIN, Asset::Weth, U256::from(1_500_000)).unwrap();
+        assert_eq!(usdc.to_human_string(), "1.5");
+        assert_eq!(weth.to_human_string(), "0.0000000000015");
+        assert_eq!(usdc.to_human_decimal().unwrap(), Decimal::new(15, 1));
+        assert_eq!(weth.to_human_decimal().unwrap(), Decimal::new(15, 13));
+    }
+
+    #[test]
+    fn human_strings_are_canonical() {
+        assert_eq!(wei(Asset::Usdc, "+001.500000", Rounding::Down).unwrap(), U256::from(1_500_000));
+        assert_eq!(wei(Asset::Usdc, ".5", Rounding::Down).unwrap(), U256::from(500_000));
+        assert_eq!(wei(Asset::Usdc, "2.", Rounding::Down).unwrap(), U256::from(2_000_000));
+        let zero = ChainNativeAsset::new_zero(CHAIN, Asset::Usdc).unwrap();
+        assert_eq!(zero.to_human_string(), "0");
+        let whole = ChainNativeAsset::new(CHAIN, Asset::Usdc, U256::from(3_000_000)).unwrap();
+        assert_eq!(whole.to_human_string(), "3");
+    }
+
+    #[test]
+    fn half_even_rounds_ties_to_the_even_wei() {
+        // 0.5, 1.5 and 2.5 wei of USDC
+        assert_eq!(wei(Asset::Usdc, "0.0000005", Rounding::HalfEven).unwrap(), U256::zero());
+        assert_eq!(wei(Asset::Usdc, "0.0000015", Rounding::HalfEven).unwrap(), U256::from(2));
+        assert_eq!(wei(Asset::Usdc, "0.0000025", Rounding::HalfEven).unwrap(), U256::from(2));
+        // only an exact half is a tie
+        assert_eq!(wei(Asset::Usdc, "0.00000050001", Rounding::HalfEven).unwrap(), U256::one());
+        assert_eq!(wei(Asset::Usdc, "0.00000249999", Rounding::HalfEven).unwrap(), U256::from(2));
+        assert_eq!(wei(Asset::Usdc, "0.0000026", Rounding::HalfEven).unwrap(), U256::from(3));
+    }
+
+    #[test]
+    fn up_and_down_round_away_from_and_towards_zero() {
+        for (human, down, up) in [("0.0000005", 0, 1), ("0.0000015", 1, 2), ("0.0000025", 2, 3), ("0.000002", 2, 2)] {
+            assert_eq!(wei(Asset::Usdc, human, Rounding::Down).unwrap(), U256::from(down), "{}", human);
+            assert_eq!(wei(Asset::Usdc, human, Rounding::Up).unwrap(), U256::from(up), "{}", human);
+        }
+        // trailing zeros past the asset's decimals aren't a remainder
+        assert_eq!(wei(Asset::Usdc, "0.0000020000", Rounding::Up).unwrap(), U256::from(2));
+    }
+
+    #[test]
+    fn rounding_through_decimal() {
+        let asset = ChainNativeAsset::from_human_decimal_rounded(CHAIN, Asset::Usdc, Decimal::new(25, 7), Rounding::HalfEven).unwrap();
+        assert_eq!(*asset.size(), U256::from(2));
+        let asset = ChainNativeAsset::from_human_decimal(CHAIN, Asset::Usdc, Decimal::new(29, 7)).unwrap();
+        assert_eq!(*asset.size(), U256::from(2));
+    }
+
+    #[test]
+    fn overflow_is_an_error() {
+        let max = ChainNativeAsset::new_max(CHAIN, Asset::Usdc).unwrap().to_human_string();
+        // one wei more than the max
+        let over = "115792089237316195423570985008687907853269984665640564039457584007913129.639936";
+        assert_eq!(wei(Asset::Usdc, &max, Rounding::Down).unwrap(), U256::MAX);
+        assert!(matches!(wei(Asset::Usdc, over, Rounding::Down), Err(ConversionError::Overflow(_))));
+        // rounding up past the max overflows too
+        let max_and_a_bit = format!("{}1", max);
+        assert_eq!(wei(Asset::Usdc, &max_and_a_bit, Rounding::Down).unwrap(), U256::MAX);
+        assert!(matches!(wei(Asset::Usdc, &max_and_a_bit, Rounding::Up), Err(ConversionError::Overflow(_))));
+        // 10^60 WETH is 10^78 wei, which doesn't fit
+        let huge = format!("1{}", "0".repeat(60));
+        assert!(matches!(wei(Asset::Weth, &huge, Rounding::Down), Err(ConversionError::Overflow(_))));
+        assert!(wei(Asset::Usdc, &huge, Rounding::Down).is_ok());
+    }
+
+    #[test]
+    fn sizes_past_decimal_precision_are_an_error() {
+        let max = ChainNativeAsset::new_max(CHAIN, Asset::Weth).unwrap();
+        assert!(matches!(max.to_human_decimal(), Err(ConversionError::DecimalOverflow(_))));
+    }
+
+    #[test]
+    fn little_endian_strings_round_trip() {
+        for size in [U256::zero(), U256::from(1_500_000), U256::exp10(18) * 15, U256::MAX] {
+            let asset = ChainNativeAsset::new(CHAIN, Asset::Weth, size).unwrap();
+            let hex = asset.to_hex_string();
+            assert_eq!(ChainNativeAsset::from_hex_string(CHAIN, Asset::Weth, &hex).unwrap(), asset);
+            let base64 = asset.to_base64_string();
+            assert_eq!(ChainNativeAsset::from_base64_string(CHAIN, Asset::Weth, &base64).unwrap(), asset);
+        }
+        // 0x0100 little endian is 1, shorter strings are zero padded
+        let one = ChainNativeAsset::from_hex_string(CHAIN, Asset::Weth, "01").unwrap();
+        assert_eq!(*one.size(), U256::one());
+        assert_eq!(*ChainNativeAsset::from_hex_string(CHAIN, Asset::Weth, "0001").unwrap().size(), U256::from(256));
+    }
+
+    #[test]
+    fn from_hex_string_refuses_0x_quantities() {
+        // what a `SizeRepr::Hex` or a JSON-RPC quantity looks like
+        let quantity = format!("{:#x}", U256::from(256));
+        assert_eq!(quantity, "0x100");
+        assert!(ChainNativeAsset::from_hex_string(CHAIN, Asset::Weth, &quantity).is_err());
+        assert!(ChainNativeAsset::from_hex_string(CHAIN, Asset::Weth, "0X0001").is_err());
+        assert!(ChainNativeAsset::from_hex_string(CHAIN, Asset::Weth, &"00".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn malformed_numbers_are_an_error() {
+        for human in ["", ".", "-1", "1e6", "1,5", "0x10", "1.2.3", "one"] {
+            assert!(
+                matches!(wei(Asset::Usdc, human, Rounding::Down), Err(ConversionError::InvalidNumber(_))),
+                "{:?}",
+                human
+            );
+        }
+    }
+
+    fn usdc(x: u64) -> ChainNativeAsset {
+        ChainNativeAsset::new(CHAIN, Asset::Usdc, U256::from(x)).unwrap()
+    }
+
+    #[test]
+    fn arithmetic_refuses_to_mix_assets_or_chains() {
+        let weth = ChainNativeAsset::new(CHAIN, Asset::Weth, U256::from(5)).unwrap();
+        let mainnet = ChainNativeAsset::new(Chain::Mainnet, Asset::Usdc, U256::from(5)).unwrap();
+        for other in [&weth, &mainnet] {
+            assert!(!usdc(5).is_same_kind(other));
+            assert!(usdc(5).checked_add(other).is_err());
+            assert!(usdc(5).checked_sub(other).is_err());
+            assert!(usdc(5).try_cmp(other).is_err());
+            assert!(usdc(5).try_min(other).is_err());
+            assert!(usdc(5).try_max(other).is_err());
+            // equal sizes of different things aren't equal, and aren't ordered either
+            assert_ne!(&usdc(5), other);
+            assert_eq!(usdc(5).partial_cmp(other), None);
+            assert_eq!((usdc(5) < *other, usdc(5) > *other), (false, false));
+        }
+        assert!(ChainNativeAsset::checked_sum(CHAIN, Asset::Usdc, [usdc(1), weth.clone()].iter()).is_err());
+        assert!(vec![usdc(1), weth].into_iter().sum::<Result<ChainNativeAsset>>().is_err());
+    }
+
+    #[test]
+    fn arithmetic_on_the_same_asset() {
+        assert_eq!(usdc(2).checked_add(&usdc(3)).unwrap(), usdc(5));
+        assert_eq!(usdc(5).checked_sub(&usdc(3)).unwrap(), usdc(2));
+        assert_eq!(usdc(5).checked_sub(&usdc(5)).unwrap(), usdc(0));
+        assert_eq!(usdc(2).try_cmp(&usdc(3)).unwrap(), Ordering::Less);
+        assert_eq!(usdc(2).try_min(&usdc(3)).unwrap(), usdc(2));
+        assert_eq!(usdc(2).try_max(&usdc(3)).unwrap(), usdc(3));
+        assert!(usdc(2) < usdc(3));
+        assert_eq!(usdc(3).partial_cmp(&usdc(3)), Some(Ordering::Equal));
+    }
+
+    #[test]
+    fn overflow_and_underflow_are_errors() {
+        let max = ChainNativeAsset::new_max(CHAIN, Asset::Usdc).unwrap();
+        assert!(max.checked_add(&usdc(1)).is_err());
+        assert_eq!(max.checked_add(&usdc(0)).unwrap(), max);
+        assert!(usdc(2).checked_sub(&usdc(3)).is_err());
+        assert!(max.checked_scale(U256::from(2), U256::one(), Rounding::Down).is_err());
+        assert!(ChainNativeAsset::checked_sum(CHAIN, Asset::Usdc, [max.clone(), usdc(1)].iter()).is_err());
+        assert!(vec![max, usdc(1)].into_iter().sum::<Result<ChainNativeAsset>>().is_err());
+    }
+
+    #[test]
+    fn sums_of_nothing() {
+        // `checked_sum` knows what it's summing, so nothing sums to zero
+        assert_eq!(ChainNativeAsset::checked_sum(CHAIN, Asset::Usdc, [].iter()).unwrap(), usdc(0));
+        // `Sum` doesn't, so it can't make a zero
+        assert!(Vec::<ChainNativeAsset>::new().into_iter().sum::<Result<ChainNativeAsset>>().is_err());
+        assert!(Vec::<ChainNativeAsset>::new().iter().sum::<Result<ChainNativeAsset>>().is_err());
+        let sizes = [usdc(1), usdc(2), usdc(3)];
+        assert_eq!(sizes.iter().sum::<Result<ChainNativeAsset>>().unwrap(), usdc(6));
+        assert_eq!(ChainNativeAsset::checked_sum(CHAIN, Asset::Usdc, sizes.iter()).unwrap(), usdc(6));
+    }
+
+    #[test]
+    fn mul_div_rounds_as_asked() {
+        let (ten, three) = (U256::from(10), U256::from(3));
+        // 10 * 1 / 3 = 3.33...
+        assert_eq!(mul_div(ten, U256::one(), three, Rounding::Down), Some(U256::from(3)));
+        assert_eq!(mul_div(ten, U256::one(), three, Rounding::Up), Some(U256::from(4)));
+        assert_eq!(mul_div(ten, U256::one(), three, Rounding::HalfEven), Some(U256::from(3)));
+        // ties go to the even quotient: 5/2 = 2.5 and 7/2 = 3.5
+        assert_eq!(mul_div(U256::from(5), U256::one(), U256::from(2), Rounding::HalfEven), Some(U256::from(2)));
+        assert_eq!(mul_div(U256::from(7), U256::one(), U256::from(2), Rounding::HalfEven), Some(U256::from(4)));
+        // an exact quotient isn't rounded
+        assert_eq!(mul_div(U256::from(9), U256::one(), three, Rounding::Up), Some(three));
+        assert_eq!(mul_div(ten, U256::one(), U256::zero(), Rounding::Down), None);
+    }
+
+    #[test]
+    fn mul_div_goes_through_512_bits() {
+        // MAX * MAX doesn't fit in a U256, but MAX * MAX / MAX does
+        assert_eq!(mul_div(U256::MAX, U256::MAX, U256::MAX, Rounding::Down), Some(U256::MAX));
+        assert_eq!(mul_div(U256::MAX, U256::from(3), U256::from(4), Rounding::Up), Some(U256::MAX / 4 * 3 + 3));
+        // but the result still has to fit
+        assert_eq!(mul_div(U256::MAX, U256::from(2), U256::one(), Rounding::Down), None);
+        assert_eq!(mul_div(U256::MAX, U256::MAX, U256::MAX - 1, Rounding::Down), None);
+        let max = ChainNativeAsset::new_max(CHAIN, Asset::Usdc).unwrap();
+        assert_eq!(*max.checked_scale(U256::MAX, U256::MAX, Rounding::Down).unwrap().size(), U256::MAX);
+    }
+
+    #[test]
+    fn scaling_by_a_decimal() {
+        assert_eq!(decimal_to_ratio(Decimal::new(9995, 4)), Some((U256::from(9995), U256::from(10_000))));
+        assert_eq!(decimal_to_ratio(Decimal::ZERO), Some((U256::zero(), U256::one())));
+        assert_eq!(decimal_to_ratio(Decimal::new(-1, 0)), None);
+        // 5 bps off 1,000,001 wei is 999,500.9995 wei
+        let size = usdc(1_000_001);
+        let fee = Decimal::new(9995, 4);
+        assert_eq!(size.checked_scale_by(fee, Rounding::Down).unwrap(), usdc(999_500));
+        assert_eq!(size.checked_scale_by(fee, Rounding::Up).unwrap(), usdc(999_501));
+        assert_eq!(size.checked_scale_by(Decimal::new(15, 1), Rounding::HalfEven).unwrap(), usdc(1_500_002));
+        assert!(size.checked_scale_by(Decimal::new(-5, 1), Rounding::Down).is_err());
     }
 }
+
@@ -1,16 +1,20 @@
 #![allow(dead_code)]
 
 use super::asset::Asset;
-use super::sized_asset::ChainNativeAsset;
-use anyhow::Result;
+use super::pair::Pair;
+use super::price::Price;
+use super::sized_asset::{mul_div, ChainNativeAsset, Rounding};
+use anyhow::{anyhow, Result};
 use ethers::types::{Chain, U256};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 
 /**
  * This is meant to represent a swap of `source` for `target` tokens. We can use it in many ways:
  * for example, it could represent fill that occured in the past, or it could represent the
  * constraint of a limit order to be filled in the future (i.e. at least `target` tokens for `source` tokens).
  */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetSwap {
     source: ChainNativeAsset, // this is what we gave up
     target: ChainNativeAsset, // this is what we got
@@ -42,22 +46,23 @@ impl AssetSwap {
         &self.target
     }
 
-    /// Prints out a summary of the swap, with Addresses formatted as hex strings
-    pub fn summarize_hex(&self) {
-        println!(
-            "{}-{}:0x{} => {}-{}:0x{}",
+    /// Returns a summary of the swap, with sizes formatted as little endian hex strings.
+    /// They're unprefixed, as [`ChainNativeAsset::to_hex_string`] writes them, so [`ChainNativeAsset::from_hex_string`] reads them back.
+    pub fn summarize_hex(&self) -> String {
+        format!(
+            "{}-{}:{} => {}-{}:{}",
             self.source.chain(),
             self.source.asset(),
             self.source.to_hex_string(),
             self.target.chain(),
             self.target.asset(),
             self.target.to_hex_string()
-        );
+        )
     }
 
-    /// Prints out a summary of the swap, with Addresses formatted as base64 strings
-    pub fn summarize_base64(&self) {
-        println!(
+    /// Returns a summary of the swap, with sizes formatted as little endian base64 strings
+    pub fn summarize_base64(&self) -> String {
+        format!(
             "{}-{}:{} => {}-{}:{}",
             self.source.chain(),
             self.source.asset(),
@@ -65,11 +70,206 @@ impl AssetSwap {
             self.target.chain(),
             self.target.asset(),
             self.target.to_base64_string()
-        );
+        )
     }
 
     // does this represent a swap within a single chain, or is it cross-chain?
     pub fn is_local_to_chain(&self) -> bool {
         self.source.chain() == self.target.chain()
     }
+
+    /// Returns the swap in the other direction, i.e. `target` for `source`
+    pub fn inverse(&self) -> Self {
+        Self {
+            source: self.target.clone(),
+            target: self.source.clone(),
+        }
+    }
+
+    /// Returns true if `other` swaps the same assets in the same direction on the same chains
+    pub fn is_same_kind(&self, other: &Self) -> bool {
+        self.source.is_same_kind(&other.source) && self.target.is_same_kind(&other.target)
+    }
+
+    /// The price this swap was done at, quoted on `pair`. Fails if the swap isn't between the assets of `pair`.
+    pub fn implied_price(&self, pair: &Pair) -> Result<Price> {
+        let (base, quote) = if self.source.asset() == pair.base() && self.target.asset() == pair.quote() {
+            (&self.source, &self.target)
+        } else if self.source.asset() == pair.quote() && self.target.asset() == pair.base() {
+            (&self.target, &self.source)
+        } else {
+            return Err(anyhow!(
+                "[AssetSwap::implied_price]: swap of {} for {} isn't on {}",
+                self.source.asset(),
+                self.target.asset(),
+                pair
+            ));
+        };
+        let value = quote
+            .to_human_decimal()?
+            .checked_div(base.to_human_decimal()?)
+            .ok_or(anyhow!(
+                "[AssetSwap::implied_price]: can't price a swap of {} for {}",
+                self.source,
+                self.target
+            ))?;
+        Price::new(*pair, value)
+    }
+
+    /// Treating `limit` as a constraint (give up at most `limit.source()` for at least `limit.target()`), returns true
+    /// if this swap was done at a rate at least as good as the limit's. The sizes don't have to match, only the rate.
+    pub fn satisfies(&self, limit: &AssetSwap) -> Result<bool> {
+        self.ensure_same_kind(limit)?;
+        // fill.target / fill.source >= limit.target / limit.source, cross multiplied so we never divide
+        let fill = self.target.size().full_mul(*limit.source.size());
+        let required = limit.target.size().full_mul(*self.source.size());
+        Ok(fill >= required)
+    }
+
+    /// The fraction of `limit` that this swap filled, measured on the source side, e.g. `0.25` for a quarter fill.
+    pub fn fill_ratio(&self, limit: &AssetSwap) -> Result<Decimal> {
+        self.ensure_same_kind(limit)?;
+        ratio_to_decimal(*self.source.size(), *limit.source.size())
+    }
+
+    /// Aggregates many swaps of the same kind into one, by summing both sides.
+    /// The [`AssetSwap::implied_price`] of the result is the volume weighted average price of the inputs.
+    pub fn aggregate<'a, I: IntoIterator<Item = &'a AssetSwap>>(swaps: I) -> Result<Self> {
+        let mut swaps = swaps.into_iter();
+        let first = swaps
+            .next()
+            .ok_or(anyhow!("[AssetSwap::aggregate]: can't aggregate an empty list of swaps"))?
+            .clone();
+        swaps.try_fold(first, |acc, x| {
+            acc.ensure_same_kind(x)?;
+            Ok(Self {
+                source: acc.source.checked_add(&x.source)?,
+                target: acc.target.checked_add(&x.target)?,
+            })
+        })
+    }
+
+    /// The volume weighted average price of `swaps`, quoted on `pair`.
+    pub fn vwap<'a, I: IntoIterator<Item = &'a AssetSwap>>(swaps: I, pair: &Pair) -> Result<Price> {
+        Self::aggregate(swaps)?.implied_price(pair)
+    }
+
+    fn ensure_same_kind(&self, other: &Self) -> Result<()> {
+        if self.is_same_kind(other) {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "[AssetSwap]: can't compare a swap of {} for {} with a swap of {} for {}",
+                self.source.asset(),
+                self.target.asset(),
+                other.source.asset(),
+                other.target.asset()
+            ))
+        }
+    }
+}
+
+impl std::fmt::Display for AssetSwap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} => {}", self.source, self.target)
+    }
+}
+
+// numerator / denominator as a Decimal with 18 places, as long as it fits
+fn ratio_to_decimal(numerator: U256, denominator: U256) -> Result<Decimal> {
+    let scaled = mul_div(numerator, U256::exp10(18), denominator, Rounding::HalfEven)
+        .filter(|x| x.bits() <= 96)
+        .ok_or(anyhow!(
+            "[AssetSwap]: the ratio {} / {} can't be represented as a Decimal",
+            numerator,
+            denominator
+        ))?;
+    Ok(Decimal::from_i128_with_scale(scaled.as_u128() as i128, 18).normalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHAIN: Chain = Chain::Optimism;
+
+    fn pair() -> Pair {
+        Pair::new(Asset::Weth, Asset::Usdc)
+    }
+
+    // selling `weth` WETH for `usdc` USDC, both in whole units
+    fn sell(weth: u64, usdc: u64) -> AssetSwap {
+        AssetSwap::new_from_primitive(
+            CHAIN,
+            Asset::Weth,
+            Asset::Usdc,
+            U256::from(weth) * U256::exp10(18),
+            U256::from(usdc) * U256::exp10(6),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn implied_price_is_quoted_on_the_pair() {
+        let swap = sell(2, 3200);
+        assert_eq!(swap.implied_price(&pair()).unwrap().value(), Decimal::new(1600, 0));
+        // a buy is priced the same way round
+        assert_eq!(swap.inverse().implied_price(&pair()).unwrap().value(), Decimal::new(1600, 0));
+        assert_eq!(swap.implied_price(&pair().inverse()).unwrap().value(), Decimal::new(625, 6));
+        assert!(swap.implied_price(&Pair::new(Asset::Weth, Asset::Dai)).is_err());
+    }
+
+    #[test]
+    fn satisfies_compares_rates_not_sizes() {
+        let limit = sell(1, 1600);
+        assert!(sell(2, 3200).satisfies(&limit).unwrap());
+        assert!(sell(1, 1601).satisfies(&limit).unwrap());
+        assert!(!sell(2, 3199).satisfies(&limit).unwrap());
+        assert!(sell(1, 1600).inverse().satisfies(&limit).is_err());
+    }
+
+    #[test]
+    fn fill_ratio_is_measured_on_the_source() {
+        assert_eq!(sell(1, 1600).fill_ratio(&sell(4, 6400)).unwrap(), Decimal::new(25, 2));
+        assert_eq!(sell(4, 6400).fill_ratio(&sell(4, 6400)).unwrap(), Decimal::ONE);
+        let third = AssetSwap::new_from_primitive(CHAIN, Asset::Weth, Asset::Usdc, U256::one(), U256::zero()).unwrap();
+        let limit = AssetSwap::new_from_primitive(CHAIN, Asset::Weth, Asset::Usdc, U256::from(3), U256::zero()).unwrap();
+        assert_eq!(third.fill_ratio(&limit).unwrap(), Decimal::new(333_333_333_333_333_333, 18));
+    }
+
+    #[test]
+    fn aggregate_sums_both_sides() {
+        let total = AssetSwap::aggregate(&[sell(1, 1600), sell(3, 6000)]).unwrap();
+        assert_eq!(total.source().to_human_string(), "4");
+        assert_eq!(total.target().to_human_string(), "7600");
+        assert!(AssetSwap::aggregate(&[]).is_err());
+        assert!(AssetSwap::aggregate(&[sell(1, 1600), sell(1, 1600).inverse()]).is_err());
+    }
+
+    #[test]
+    fn summarized_sizes_read_back() {
+        let swap = sell(1, 1600);
+        let summary = swap.summarize_hex();
+        let (source, target) = summary.split_once(" => ").unwrap();
+        let size = |x: &str| x.rsplit_once(':').unwrap().1.to_string();
+        assert_eq!(
+            &ChainNativeAsset::from_hex_string(CHAIN, Asset::Weth, &size(source)).unwrap(),
+            swap.source()
+        );
+        assert_eq!(
+            &ChainNativeAsset::from_hex_string(CHAIN, Asset::Usdc, &size(target)).unwrap(),
+            swap.target()
+        );
+        assert!(!summary.contains("0x"));
+    }
+
+    #[test]
+    fn vwap_weights_by_volume() {
+        // 1 WETH at 1600 and 3 WETH at 2000
+        let vwap = AssetSwap::vwap(&[sell(1, 1600), sell(3, 6000)], &pair()).unwrap();
+        assert_eq!(vwap.value(), Decimal::new(1900, 0));
+        // the simple average would be 1800
+        let vwap = AssetSwap::vwap(&[sell(3, 4800), sell(1, 2000)], &pair()).unwrap();
+        assert_eq!(vwap.value(), Decimal::new(1700, 0));
+    }
 }
@@ -0,0 +1,245 @@
+/**
+ * Every venue spells its markets differently:
+ *
+ * - Rubicon:  "WETH-USDC"
+ * - Coinbase: "ETH-USDC"
+ * - FTX:      "BTC/USDC"
+ * - Binance:  "ETHUSDC"
+ *
+ * On top of that, centralized venues list the underlying coin rather than the wrapped token, so WETH is "ETH" and WBTC is "BTC".
+ * Fiat quotes like Coinbase's "ETH-USD" don't parse, since USD isn't an asset that lives on chain.
+ * A [`SymbolDialect`] knows how to format and parse one of these styles, so Rubicon markets can be mapped onto external reference feeds.
+ */
+use crate::asset::{Asset, Chain};
+use crate::pair::Pair;
+use crate::registry::TokenRegistry;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+impl FromStr for Asset {
+    type Err = anyhow::Error;
+
+    /// Parses one of the built-in assets from its symbol, case insensitively (e.g. `"weth"`).
+    /// Tokens that aren't built in have to be resolved through a [`TokenRegistry`].
+    fn from_str(s: &str) -> Result<Self> {
+        let upper = s.trim().to_uppercase();
+        Asset::BUILTIN
+            .into_iter()
+            .find(|x| x.symbol() == upper)
+            .ok_or(anyhow!(
+                "{:?} isn't a built-in asset, resolve it through a TokenRegistry instead",
+                s
+            ))
+    }
+}
+
+impl FromStr for Pair {
+    type Err = anyhow::Error;
+
+    /// Parses a pair in the same `BASE-QUOTE` form that `Display` writes, e.g. `"WETH-USDC"`.
+    fn from_str(s: &str) -> Result<Self> {
+        SymbolDialect::Rubicon.parse_pair(s)
+    }
+}
+
+/// The market symbol conventions of the venues we map Rubicon markets onto.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SymbolDialect {
+    /// "WETH-USDC"
+    Rubicon,
+    /// "ETH-USDC"
+    Coinbase,
+    /// "ETH/USDC"
+    Ftx,
+    /// "ETHUSDC"
+    Binance,
+}
+
+impl SymbolDialect {
+    /// The separator between the base and the quote, if the dialect has one.
+    pub fn separator(&self) -> Option<char> {
+        match self {
+            SymbolDialect::Rubicon | SymbolDialect::Coinbase => Some('-'),
+            SymbolDialect::Ftx => Some('/'),
+            SymbolDialect::Binance => None,
+        }
+    }
+
+    /// Whether the dialect lists wrapped tokens by their underlying coin (WETH as ETH, WBTC as BTC).
+    pub fn unwraps_symbols(&self) -> bool {
+        !matches!(self, SymbolDialect::Rubicon)
+    }
+
+    /// The symbol this dialect uses for `asset`.
+    pub fn format_asset(&self, asset: &Asset) -> String {
+        match asset {
+            Asset::Weth | Asset::Eth if self.unwraps_symbols() => "ETH".to_string(),
+            Asset::Wbtc if self.unwraps_symbols() => "BTC".to_string(),
+            _ => asset.symbol(),
+        }
+    }
+
+    /// The symbol this dialect uses for `pair`.
+    pub fn format_pair(&self, pair: &Pair) -> String {
+        let base = self.format_asset(pair.base());
+        let quote = self.format_asset(pair.quote());
+        match self.separator() {
+            Some(sep) => format!("{}{}{}", base, sep, quote),
+            None => format!("{}{}", base, quote),
+        }
+    }
+
+    /// Parses a built-in asset. Both the wrapped and the underlying symbol are accepted (e.g. "BTC" and "WBTC").
+    /// "ETH" is WETH on venues that [unwrap symbols](SymbolDialect::unwraps_symbols), and native [`Asset::Eth`] on Rubicon.
+    pub fn parse_asset(&self, s: &str) -> Result<Asset> {
+        self.parse_asset_with(s, &Asset::from_str)
+    }
+
+    /// Parses a built-in asset, or a token from `registry` on `chain`.
+    pub fn parse_asset_in(&self, s: &str, registry: &TokenRegistry, chain: &Chain) -> Result<Asset> {
+        self.parse_asset_with(s, &|x| {
+            registry.asset(chain, x).or_else(|_| Asset::from_str(x))
+        })
+    }
+
+    /// Parses a pair of built-in assets.
+    pub fn parse_pair(&self, s: &str) -> Result<Pair> {
+        self.parse_pair_with(s, &|x| self.parse_asset(x))
+    }
+
+    /// Parses a pair of built-in assets or tokens from `registry` on `chain`.
+    pub fn parse_pair_in(&self, s: &str, registry: &TokenRegistry, chain: &Chain) -> Result<Pair> {
+        self.parse_pair_with(s, &|x| self.parse_asset_in(x, registry, chain))
+    }
+
+    fn parse_asset_with(&self, s: &str, resolve: &dyn Fn(&str) -> Result<Asset>) -> Result<Asset> {
+        let upper = s.trim().to_uppercase();
+        // venues that unwrap symbols mean WETH when they say ETH, whereas on Rubicon ETH is the native asset
+        let unaliased = match upper.as_str() {
+            "ETH" if self.unwraps_symbols() => "WETH",
+            "BTC" => "WBTC",
+            other => other,
+        };
+        resolve(unaliased)
+    }
+
+    fn parse_pair_with(&self, s: &str, resolve: &dyn Fn(&str) -> Result<Asset>) -> Result<Pair> {
+        let trimmed = s.trim();
+        let (base, quote) = match self.separator() {
+            Some(sep) => {
+                let (base, quote) = trimmed.split_once(sep).ok_or(anyhow!(
+                    "[SymbolDialect::parse_pair]: {:?} isn't of the form BASE{}QUOTE",
+                    s,
+                    sep
+                ))?;
+                (resolve(base)?, resolve(quote)?)
+            }
+            None => {
+                // there's no separator, so try every split and insist on exactly one that makes sense
+                let candidates: Vec<(Asset, Asset)> = (1..trimmed.len())
+                    .filter(|i| trimmed.is_char_boundary(*i))
+                    .filter_map(|i| {
+                        let (base, quote) = trimmed.split_at(i);
+                        Some((resolve(base).ok()?, resolve(quote).ok()?))
+                    })
+                    .collect();
+                match candidates.as_slice() {
+                    [one] => *one,
+                    [] => {
+                        return Err(anyhow!(
+                            "[SymbolDialect::parse_pair]: {:?} isn't a pair of known assets",
+                            s
+                        ))
+                    }
+                    _ => {
+                        return Err(anyhow!(
+                            "[SymbolDialect::parse_pair]: {:?} can be split into more than one pair",
+                            s
+                        ))
+                    }
+                }
+            }
+        };
+        if base == quote {
+            return Err(anyhow!(
+                "[SymbolDialect::parse_pair]: {:?} has the same base and quote",
+                s
+            ));
+        }
+        Ok(Pair::new(base, quote))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::types::Address;
+
+    fn weth_usdc() -> Pair {
+        Pair::new(Asset::Weth, Asset::Usdc)
+    }
+
+    #[test]
+    fn rubicon() {
+        let dialect = SymbolDialect::Rubicon;
+        assert_eq!(dialect.parse_pair("WETH-USDC").unwrap(), weth_usdc());
+        assert_eq!(dialect.format_pair(&weth_usdc()), "WETH-USDC");
+        assert_eq!("weth-usdc".parse::<Pair>().unwrap(), weth_usdc());
+        // on Rubicon, ETH is the native asset
+        assert_eq!(dialect.parse_asset("ETH").unwrap(), Asset::Eth);
+        assert_eq!(dialect.parse_pair("BTC-USDC").unwrap(), Pair::new(Asset::Wbtc, Asset::Usdc));
+    }
+
+    #[test]
+    fn coinbase() {
+        let dialect = SymbolDialect::Coinbase;
+        assert_eq!(dialect.parse_pair("ETH-USDC").unwrap(), weth_usdc());
+        assert_eq!(dialect.format_pair(&weth_usdc()), "ETH-USDC");
+        assert_eq!(dialect.parse_pair("WETH-USDC").unwrap(), weth_usdc());
+    }
+
+    #[test]
+    fn ftx() {
+        let dialect = SymbolDialect::Ftx;
+        let wbtc_usdc = Pair::new(Asset::Wbtc, Asset::Usdc);
+        assert_eq!(dialect.parse_pair("BTC/USDC").unwrap(), wbtc_usdc);
+        assert_eq!(dialect.format_pair(&wbtc_usdc), "BTC/USDC");
+        assert!(dialect.parse_pair("BTC-USDC").is_err());
+    }
+
+    #[test]
+    fn binance() {
+        let dialect = SymbolDialect::Binance;
+        assert_eq!(dialect.parse_pair("ETHUSDC").unwrap(), weth_usdc());
+        assert_eq!(dialect.format_pair(&weth_usdc()), "ETHUSDC");
+        assert_eq!(dialect.parse_pair("opusdt").unwrap(), Pair::new(Asset::Op, Asset::Usdt));
+    }
+
+    #[test]
+    fn every_dialect_round_trips() {
+        for dialect in [SymbolDialect::Rubicon, SymbolDialect::Coinbase, SymbolDialect::Ftx, SymbolDialect::Binance] {
+            for pair in [weth_usdc(), Pair::new(Asset::Wbtc, Asset::Dai), Pair::new(Asset::Snx, Asset::Op)] {
+                assert_eq!(dialect.parse_pair(&dialect.format_pair(&pair)).unwrap(), pair, "{:?}", dialect);
+            }
+        }
+    }
+
+    #[test]
+    fn fiat_and_bad_symbols_dont_parse() {
+        assert!(SymbolDialect::Coinbase.parse_pair("ETH-USD").is_err());
+        assert!(SymbolDialect::Ftx.parse_pair("BTC/USD").is_err());
+        assert!(SymbolDialect::Rubicon.parse_pair("WETH-WETH").is_err());
+        assert!(SymbolDialect::Rubicon.parse_pair("WETHUSDC").is_err());
+    }
+
+    #[test]
+    fn registry_tokens_parse() {
+        let mut registry = TokenRegistry::new();
+        registry.insert(crate::registry::TokenInfo::new(Chain::Optimism, "FOO", Address::repeat_byte(1), 18));
+        let pair = SymbolDialect::Coinbase.parse_pair_in("FOO-USDC", &registry, &Chain::Optimism).unwrap();
+        assert_eq!(pair.base().symbol(), "FOO");
+        assert_eq!(pair.quote(), &Asset::Usdc);
+        assert!(SymbolDialect::Coinbase.parse_pair("FOO-USDC").is_err());
+    }
+}
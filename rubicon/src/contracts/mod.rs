@@ -1,8 +1,20 @@
+/*
+ * Typed bindings for the protocol's contracts, generated at compile time from the ABIs in `abi/`.
+ * Each ABI is parsed once, the first time its contract is used.
+ * The session wraps these up for you, but they're here if you need a function it doesn't expose.
+ */
 pub mod house;
 #[cfg(feature = "ierc20")]
 pub mod ierc_20;
 pub mod market;
-#[cfg(feature = "aid")]
 pub mod market_aid;
 pub mod pair;
 pub mod router;
+
+pub use house::BathHouse;
+#[cfg(feature = "ierc20")]
+pub use ierc_20::IERC20;
+pub use market::RubiconMarket;
+pub use market_aid::MarketAid;
+pub use pair::BathPair;
+pub use router::RubiconRouter;
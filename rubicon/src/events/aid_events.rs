@@ -0,0 +1,19 @@
+// generated from MarketAid's ABI (see `contracts/market_aid.rs`)
+use super::{
+    LogBatchMarketMakingTrades, LogBatchRequoteOffers, LogRequote, LogStrategistRewardClaim, LogStrategistTrade,
+};
+
+/// MarketAid's version of [`super::LogScrubbedStratTrade`], which leaves out the asset and bath quote addresses.
+pub use crate::contracts::market_aid::LogScrubbedStratTradeFilter as AidLogScrubbedStratTrade;
+
+event_enum! {
+    /// Every event emitted by MarketAid. Apart from [`AidLogScrubbedStratTrade`], these are the same events the BathPair emits.
+    AidEvent {
+        StrategistTrade(LogStrategistTrade),
+        ScrubbedStratTrade(AidLogScrubbedStratTrade),
+        StrategistRewardClaim(LogStrategistRewardClaim),
+        BatchMarketMakingTrades(LogBatchMarketMakingTrades),
+        Requote(LogRequote),
+        BatchRequoteOffers(LogBatchRequoteOffers),
+    }
+}
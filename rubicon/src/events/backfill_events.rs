@@ -0,0 +1,47 @@
+use super::{FeeTake, LogKill, LogMake, LogTake, PairEvent};
+use ethers::abi::{Error, RawLog};
+use ethers::contract::{EthEvent, EthLogDecode};
+use ethers::core::types::H256;
+use serde::{Deserialize, Serialize};
+
+/// The events that [`crate::RubiconSession::backfill`] collects: offers being made, taken and killed on the RubiconMarket,
+/// the fees taken on each fill, and everything the BathPair emits.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum BackfillEvent {
+    Make(LogMake),
+    Take(LogTake),
+    Kill(LogKill),
+    FeeTake(FeeTake),
+    Pair(PairEvent),
+}
+
+impl BackfillEvent {
+    /// The topic0 of every event that can be decoded as a [`BackfillEvent`]
+    pub fn signatures() -> Vec<H256> {
+        let mut signatures = vec![
+            LogMake::signature(),
+            LogTake::signature(),
+            LogKill::signature(),
+            FeeTake::signature(),
+        ];
+        signatures.extend(PairEvent::signatures());
+        signatures
+    }
+}
+
+impl EthLogDecode for BackfillEvent {
+    fn decode_log(log: &RawLog) -> Result<Self, Error> {
+        let topic0 = log.topics.first().ok_or(Error::InvalidData)?;
+        if *topic0 == LogMake::signature() {
+            <LogMake as EthEvent>::decode_log(log).map(BackfillEvent::Make)
+        } else if *topic0 == LogTake::signature() {
+            <LogTake as EthEvent>::decode_log(log).map(BackfillEvent::Take)
+        } else if *topic0 == LogKill::signature() {
+            <LogKill as EthEvent>::decode_log(log).map(BackfillEvent::Kill)
+        } else if *topic0 == FeeTake::signature() {
+            <FeeTake as EthEvent>::decode_log(log).map(BackfillEvent::FeeTake)
+        } else {
+            PairEvent::decode_log(log).map(BackfillEvent::Pair)
+        }
+    }
+}
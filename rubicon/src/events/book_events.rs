@@ -0,0 +1,29 @@
+use super::{LogBump, LogKill, LogMake, LogSortedOffer, LogTake, OfferDeleted};
+use ethers::core::types::U256;
+
+event_enum! {
+    /// The RubiconMarket events that change the contents of the book.
+    /// A `Make` places an offer, but it's only in the sorted book (the one the market matches against) once a `Sorted` follows it.
+    BookEvent {
+        Make(LogMake),
+        Sorted(LogSortedOffer),
+        Take(LogTake),
+        Kill(LogKill),
+        Bump(LogBump),
+        Deleted(OfferDeleted),
+    }
+}
+
+impl BookEvent {
+    /// The id of the offer the event is about
+    pub fn offer_id(&self) -> U256 {
+        match self {
+            BookEvent::Make(x) => x.offer_id(),
+            BookEvent::Sorted(x) => x.id,
+            BookEvent::Take(x) => x.offer_id(),
+            BookEvent::Kill(x) => x.offer_id(),
+            BookEvent::Bump(x) => x.offer_id(),
+            BookEvent::Deleted(x) => x.offer_id(),
+        }
+    }
+}
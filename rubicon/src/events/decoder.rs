@@ -0,0 +1,97 @@
+use super::{as_raw, AidEvent, HouseEvent, LogMeta, LoggedEvent, MarketEvent, PairEvent, RouterEvent};
+use crate::session::Deployment;
+use anyhow::{anyhow, Result};
+use ethers::contract::EthLogDecode;
+use ethers::core::types::{Address, Log, TransactionReceipt};
+use serde::{Deserialize, Serialize};
+
+/// Any event emitted by one of the protocol's contracts, grouped by the contract that emitted it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum RubiconEvent {
+    Market(MarketEvent),
+    Pair(PairEvent),
+    House(HouseEvent),
+    Router(RouterEvent),
+    Aid(AidEvent),
+}
+
+/**
+ * Decodes raw logs from any of a deployment's contracts into [`RubiconEvent`]s.
+ *
+ * Logs are classified by the address that emitted them first, and by their topic0 second, so the same event
+ * emitted by two contracts (e.g. `LogStrategistTrade` from the BathPair and from MarketAid) ends up in the right place.
+ * This lets an indexer pull every log for the protocol's addresses out of a block (or a receipt) and decode them in one pass.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventDecoder {
+    market: Address,
+    pair: Address,
+    house: Address,
+    router: Address,
+    aid: Option<Address>,
+}
+
+impl EventDecoder {
+    /// A decoder for the contracts in `deployment`.
+    pub fn new(deployment: &Deployment) -> Self {
+        Self {
+            market: deployment.market(),
+            pair: deployment.pair(),
+            house: deployment.house(),
+            router: deployment.router(),
+            aid: deployment.aid(),
+        }
+    }
+
+    /// Every address that the decoder recognizes, e.g. to build an `eth_getLogs` filter.
+    pub fn addresses(&self) -> Vec<Address> {
+        let mut addresses = vec![self.market, self.pair, self.house, self.router];
+        addresses.extend(self.aid);
+        addresses
+    }
+
+    /// True if `address` is one of the deployment's contracts.
+    pub fn is_rubicon(&self, address: &Address) -> bool {
+        self.addresses().contains(address)
+    }
+
+    /// Decodes a mined log. Fails if the log wasn't emitted by one of the deployment's contracts, or isn't one of its events.
+    pub fn decode(&self, log: &Log) -> Result<LoggedEvent<RubiconEvent>> {
+        let raw = as_raw(log);
+        let event = if log.address == self.market {
+            MarketEvent::decode_log(&raw).map(RubiconEvent::Market)
+        } else if log.address == self.pair {
+            PairEvent::decode_log(&raw).map(RubiconEvent::Pair)
+        } else if log.address == self.house {
+            HouseEvent::decode_log(&raw).map(RubiconEvent::House)
+        } else if log.address == self.router {
+            RouterEvent::decode_log(&raw).map(RubiconEvent::Router)
+        } else if Some(log.address) == self.aid {
+            AidEvent::decode_log(&raw).map(RubiconEvent::Aid)
+        } else {
+            return Err(anyhow!(
+                "[EventDecoder::decode]: {:?} isn't one of the deployment's contracts",
+                log.address
+            ));
+        }
+        .map_err(|e| {
+            anyhow!(
+                "[EventDecoder::decode]: can't decode log with topic0 {:?} from {:?}: {}",
+                log.topics.first(),
+                log.address,
+                e
+            )
+        })?;
+        Ok(LoggedEvent::new(event, LogMeta::from_log(log)?))
+    }
+
+    /// Decodes every log that's a protocol event, in the order given, skipping anything else.
+    pub fn decode_logs<'a>(&self, logs: impl IntoIterator<Item = &'a Log>) -> Vec<LoggedEvent<RubiconEvent>> {
+        logs.into_iter().filter_map(|x| self.decode(x).ok()).collect()
+    }
+
+    /// Decodes every protocol event in a transaction's receipt.
+    pub fn decode_receipt(&self, receipt: &TransactionReceipt) -> Vec<LoggedEvent<RubiconEvent>> {
+        self.decode_logs(receipt.logs.iter())
+    }
+}
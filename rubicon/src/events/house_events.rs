@@ -0,0 +1,12 @@
+// generated from the BathHouse's ABI (see `contracts/house.rs`)
+pub use crate::contracts::house::{
+    LogNewBathTokenFilter as LogNewBathToken, LogOpenCreationSignalFilter as LogOpenCreationSignal,
+};
+
+event_enum! {
+    /// Every event emitted by the BathHouse.
+    HouseEvent {
+        NewBathToken(LogNewBathToken),
+        OpenCreationSignal(LogOpenCreationSignal),
+    }
+}
@@ -1,165 +1,78 @@
+/*
+ * The RubiconMarket's events are generated from its ABI (see `contracts/market.rs`), so they always match what the contract emits.
+ * Their fields are public, and named after the ABI's parameters in snake case.
+ */
+pub use crate::contracts::market::{
+    FeeTakeFilter as FeeTake, LogBumpFilter as LogBump, LogBuyEnabledFilter as LogBuyEnabled,
+    LogDeleteFilter as LogDelete, LogInsertFilter as LogInsert, LogIntFilter as LogInt,
+    LogItemUpdateFilter as LogItemUpdate, LogKillFilter as LogKill, LogMakeFilter as LogMake,
+    LogMatchFilter as LogMatch, LogMatchingEnabledFilter as LogMatchingEnabled,
+    LogMinSellFilter as LogMinSell, LogSetAuthorityFilter as LogSetAuthority,
+    LogSetOwnerFilter as LogSetOwner, LogSortedOfferFilter as LogSortedOffer, LogTakeFilter as LogTake,
+    LogTradeFilter as LogTrade, LogUnsortedOfferFilter as LogUnsortedOffer,
+    OfferDeletedFilter as OfferDeleted,
+};
 use ethers::contract::EthEvent;
-use ethers::core::types::{Address, U256};
+use ethers::core::types::{Address, Bytes, U256};
 use serde::{Deserialize, Serialize};
-// first, we do the matching events
 
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-pub struct LogBuyEnabled {
-    #[ethevent(name = "isEnabled")]
-    is_enabled: bool,
-}
-
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-pub struct LogMinSell {
-    pay_gem: Address,
-    min_amount: U256,
-}
-
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-pub struct LogMatchingEnabled {
-    #[ethevent(name = "isEnabled")]
-    is_enabled: bool,
-}
-
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-pub struct LogUnsortedOffer {
-    id: U256,
-}
-
-// VALIDATED
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-pub struct LogSortedOffer {
-    id: U256,
-}
-
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-pub struct LogInsert {
-    keeper: Address,
-    id: U256,
-}
-
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-pub struct LogDelete {
-    keeper: Address,
-    id: U256,
-}
-
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-pub struct LogMatch {
-    id: U256,
-    amount: U256,
-}
-
-// now, we go do the eventful market events
-// VALIDATED
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-pub struct LogItemUpdate {
-    id: U256,
-}
-
-// VALIDATED
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-pub struct LogTrade {
-    pay_amt: U256,
-    #[ethevent(indexed)]
-    pay_gem: Address,
-    buy_amt: U256,
-    #[ethevent(indexed)]
-    buy_gem: Address,
-}
-
-// sig needs to be 0x773ff502687307abfa024ac9f62f9752a0d210dac2ffd9a29e38e12e2ea82c82
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-#[ethevent(signature = "773ff502687307abfa024ac9f62f9752a0d210dac2ffd9a29e38e12e2ea82c82")]
-//#[ethevent(abi="LogMake(bytes32,bytes32,address,address,address,uint128,uint128,uint64)")]
-pub struct LogMake {
-    #[ethevent(indexed)]
-    id: U256,
-    #[ethevent(indexed)]
-    pair: [u8; 32],
-    #[ethevent(indexed)]
-    maker: Address,
-    pay_gem: Address,
-    buy_gem: Address,
-    pay_amt: u128,
-    buy_amt: u128,
-    timestamp: u64,
-}
-
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-#[ethevent(abi = "LogBump(bytes32,bytes32,address,address,address,uint128,uint128,uint64)")]
-pub struct LogBump {
-    #[ethevent(indexed)]
-    id: [u8; 32],
-    #[ethevent(indexed)]
-    pair: [u8; 32],
-    #[ethevent(indexed)]
-    maker: Address,
-    pay_gem: Address,
-    buy_gem: Address,
-    pay_amt: u128,
-    buy_amt: u128,
-    timestamp: u64,
-}
-
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-#[ethevent(signature = "3383e3357c77fd2e3a4b30deea81179bc70a795d053d14d5b7f2f01d0fd4596f")]
-pub struct LogTake {
-    #[ethevent(indexed)]
-    id: [u8; 32],
-    #[ethevent(indexed)]
-    pair: [u8; 32],
-    #[ethevent(indexed)]
-    maker: Address,
-    pay_gem: Address,
-    buy_gem: Address,
-    #[ethevent(indexed)]
-    taker: Address,
-    take_amt: u128,
-    give_amt: u128,
-    timestamp: u64,
-}
-
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-pub struct LogKill {
-    #[ethevent(indexed)]
-    id: [u8; 32],
-    #[ethevent(indexed)]
-    pair: [u8; 32],
-    #[ethevent(indexed)]
-    maker: Address,
-    pay_gem: Address,
-    buy_gem: Address,
-    pay_amt: u128,
-    buy_amt: u128,
-    timestamp: u64,
-}
-
-// I don't know how strings work here - I'll deal with it later
-// #[derive(Clone, Debug, EthEvent)]
-// pub struct LogInt{
-//     string lol,
-//     uint256 input
-// };
-
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-pub struct FeeTake {
-    #[ethevent(indexed)]
-    id: [u8; 32],
-    #[ethevent(indexed)]
-    pair: [u8; 32],
-    asset: Address,
-    #[ethevent(indexed)]
-    taker: Address,
-    #[ethevent(name = "feeTo")]
-    fee_to: Address,
-    #[ethevent(name = "feeAmt")]
-    fee_amt: U256,
-    timestamp: u64,
-}
-
-#[derive(Clone, Debug, EthEvent, Deserialize, Serialize)]
-pub struct OfferDeleted {
-    #[ethevent(indexed)]
-    id: [u8; 32],
+/// DSNote's `LogNote`, which the market emits for its `note`d functions.
+/// It's anonymous, which abigen doesn't carry over from the ABI, so it's declared by hand: its topics are the indexed fields, with no signature first.
+#[derive(Clone, Debug, PartialEq, Eq, EthEvent, Deserialize, Serialize)]
+#[ethevent(name = "LogNote", abi = "LogNote(bytes4,address,bytes32,bytes32,uint256,bytes)", anonymous)]
+pub struct LogNote {
+    #[ethevent(indexed)]
+    pub sig: [u8; 4],
+    #[ethevent(indexed)]
+    pub guy: Address,
+    #[ethevent(indexed)]
+    pub foo: [u8; 32],
+    #[ethevent(indexed)]
+    pub bar: [u8; 32],
+    pub wad: U256,
+    pub fax: Bytes,
+}
+
+// the market logs offer ids as bytes32, but takes them as uint256 everywhere else
+macro_rules! offer_id {
+    ($($event:ty),*) => {
+        $(
+            impl $event {
+                /// The id of the offer, as the market's functions take it
+                pub fn offer_id(&self) -> U256 {
+                    U256::from_big_endian(&self.id)
+                }
+            }
+        )*
+    };
+}
+
+offer_id!(LogMake, LogBump, LogTake, LogKill, FeeTake, OfferDeleted);
+
+event_enum! {
+    /// Every event emitted by the RubiconMarket. DSNote's anonymous [`LogNote`] is tried last, for logs whose topic0 isn't an event signature.
+    MarketEvent {
+        BuyEnabled(LogBuyEnabled),
+        MinSell(LogMinSell),
+        MatchingEnabled(LogMatchingEnabled),
+        UnsortedOffer(LogUnsortedOffer),
+        SortedOffer(LogSortedOffer),
+        Insert(LogInsert),
+        Delete(LogDelete),
+        Match(LogMatch),
+        ItemUpdate(LogItemUpdate),
+        Trade(LogTrade),
+        Make(LogMake),
+        Bump(LogBump),
+        Take(LogTake),
+        Kill(LogKill),
+        Int(LogInt),
+        FeeTake(FeeTake),
+        OfferDeleted(OfferDeleted),
+        SetAuthority(LogSetAuthority),
+        SetOwner(LogSetOwner),
+    }
+    anonymous {
+        Note(LogNote),
+    }
 }
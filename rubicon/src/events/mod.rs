@@ -1,12 +1,173 @@
+// generates an enum over a set of events, which decodes a log by matching its topic0 against each event's signature.
+// anonymous events don't have a signature in their topics, so they're tried in order if nothing else matches.
+macro_rules! event_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($variant:ident($event:ty)),* $(,)?
+        }
+        $(anonymous {
+            $($anon_variant:ident($anon_event:ty)),* $(,)?
+        })?
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, ::serde::Deserialize, ::serde::Serialize)]
+        pub enum $name {
+            $($variant($event),)*
+            $($($anon_variant($anon_event),)*)?
+        }
+
+        impl $name {
+            #[doc = concat!("The topic0 of every (non-anonymous) event that can be decoded as a [`", stringify!($name), "`]")]
+            pub fn signatures() -> Vec<::ethers::core::types::H256> {
+                vec![$(<$event as ::ethers::contract::EthEvent>::signature()),*]
+            }
+        }
+
+        impl ::ethers::contract::EthLogDecode for $name {
+            fn decode_log(log: &::ethers::abi::RawLog) -> Result<Self, ::ethers::abi::Error> {
+                let topic0 = log.topics.first().ok_or(::ethers::abi::Error::InvalidData)?;
+                $(
+                    if *topic0 == <$event as ::ethers::contract::EthEvent>::signature() {
+                        return <$event as ::ethers::contract::EthEvent>::decode_log(log).map($name::$variant);
+                    }
+                )*
+                $($(
+                    if let Ok(event) = <$anon_event as ::ethers::contract::EthEvent>::decode_log(log) {
+                        return Ok($name::$anon_variant(event));
+                    }
+                )*)?
+                Err(::ethers::abi::Error::InvalidData)
+            }
+        }
+    };
+}
+
+mod aid_events;
+mod backfill_events;
+mod book_events;
+mod decoder;
+mod house_events;
 mod market_events;
 mod pair_events;
+mod router_events;
 
+pub use aid_events::*;
+pub use backfill_events::*;
+pub use book_events::*;
+pub use decoder::*;
+pub use house_events::*;
 pub use market_events::*;
 pub use pair_events::*;
+pub use router_events::*;
 
+use anyhow::{anyhow, Result};
 use ethers::abi::RawLog;
-use ethers::contract::EthEvent;
-use ethers::core::types::{Log, TransactionReceipt};
+use ethers::contract::{EthEvent, EthLogDecode};
+use ethers::core::types::{Address, Log, TransactionReceipt, H256, U256};
+use serde::{Deserialize, Serialize};
+
+/// Where a log came from: the contract that emitted it, and its position in the chain.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LogMeta {
+    address: Address,
+    block_number: u64,
+    block_hash: H256,
+    transaction_hash: H256,
+    log_index: U256,
+    removed: bool,
+}
+
+impl LogMeta {
+    /// Extracts the metadata of a mined log. Fails for pending logs, which don't have a position yet.
+    pub fn from_log(log: &Log) -> Result<Self> {
+        let pending = || anyhow!("[LogMeta::from_log]: log from {:?} hasn't been mined", log.address);
+        Ok(Self {
+            address: log.address,
+            block_number: log.block_number.ok_or_else(pending)?.as_u64(),
+            block_hash: log.block_hash.ok_or_else(pending)?,
+            transaction_hash: log.transaction_hash.ok_or_else(pending)?,
+            log_index: log.log_index.ok_or_else(pending)?,
+            removed: log.removed.unwrap_or(false),
+        })
+    }
+
+    /// The address of the contract that emitted the log
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub fn block_number(&self) -> u64 {
+        self.block_number
+    }
+
+    pub fn block_hash(&self) -> H256 {
+        self.block_hash
+    }
+
+    pub fn transaction_hash(&self) -> H256 {
+        self.transaction_hash
+    }
+
+    /// The index of the log within its block
+    pub fn log_index(&self) -> U256 {
+        self.log_index
+    }
+
+    /// True if the log was removed from the chain by a reorg
+    pub fn removed(&self) -> bool {
+        self.removed
+    }
+
+    /// The position of the log in the chain, for ordering and deduplicating logs
+    pub fn position(&self) -> (u64, U256) {
+        (self.block_number, self.log_index)
+    }
+}
+
+/// A decoded event, along with where it came from.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LoggedEvent<E> {
+    event: E,
+    meta: LogMeta,
+}
+
+impl<E: EthLogDecode> LoggedEvent<E> {
+    /// Decodes a mined log as an `E`.
+    pub fn from_log(log: &Log) -> Result<Self> {
+        let event = E::decode_log(&as_raw(log)).map_err(|e| {
+            anyhow!(
+                "[LoggedEvent::from_log]: can't decode log as {}: {}",
+                std::any::type_name::<E>(),
+                e
+            )
+        })?;
+        Ok(Self {
+            event,
+            meta: LogMeta::from_log(log)?,
+        })
+    }
+}
+
+impl<E> LoggedEvent<E> {
+    pub fn new(event: E, meta: LogMeta) -> Self {
+        Self { event, meta }
+    }
+
+    /// Returns a reference to the decoded event
+    pub fn event(&self) -> &E {
+        &self.event
+    }
+
+    /// Returns a reference to where the event came from
+    pub fn meta(&self) -> &LogMeta {
+        &self.meta
+    }
+
+    pub fn into_inner(self) -> (E, LogMeta) {
+        (self.event, self.meta)
+    }
+}
 
 
 #[allow(dead_code)]
@@ -14,15 +175,140 @@ pub(crate) fn extract_events<E: EthEvent>(receipt: &TransactionReceipt) -> Vec<E
     receipt
         .logs
         .iter()
-        .map(|x| as_raw(x))
+        .map(as_raw)
         .filter_map(|x| E::decode_log(&x).ok())
         .collect()
 }
 
-#[allow(dead_code)]
-fn as_raw(log: &Log) -> RawLog {
+pub(crate) fn as_raw(log: &Log) -> RawLog {
     RawLog {
         topics: log.topics.clone(),
         data: log.data.to_vec(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::contract::EthEvent;
+
+    // keccak256 of each event's canonical signature in the deployed contracts' ABIs, written out so a change to the
+    // generated types (or to the ABIs they come from) can't go unnoticed
+    const MARKET: &[(&str, &str)] = &[
+        ("FeeTake(bytes32,bytes32,address,address,address,uint256,uint64)", "0x525bb278de7f06bee02138b8c4ec9bd8d462ad13711d72159787024c24adc642"),
+        ("LogBump(bytes32,bytes32,address,address,address,uint128,uint128,uint64)", "0x70a14c213064359ede031fd2a1645a11ce2ec825ffe6ab5cfb5b160c3ef4d0a2"),
+        ("LogBuyEnabled(bool)", "0x7089e4f0bcc948f9f723a361590c32d9c2284da7ab1981b1249ad2edb9f953c1"),
+        ("LogDelete(address,uint256)", "0xcb9d6176c6aac6478ebb9a2754cdce22a944de29ed1f2642f8613884eba4b40c"),
+        ("LogInsert(address,uint256)", "0x6d5c16212bdea16850dce4d9fa2314c446bd30ce84700d9c36c7677c6d283940"),
+        ("LogInt(string,uint256)", "0x3b53f2745f01e9cc7d8317d92cca0b2e25a1e0f710c5b65c2da4002d794e399f"),
+        ("LogItemUpdate(uint256)", "0xa2c251311b1a7a475913900a2a73dc9789a21b04bc737e050bbc506dd4eb3488"),
+        ("LogKill(bytes32,bytes32,address,address,address,uint128,uint128,uint64)", "0x9577941d28fff863bfbee4694a6a4a56fb09e169619189d2eaa750b5b4819995"),
+        ("LogMake(bytes32,bytes32,address,address,address,uint128,uint128,uint64)", "0x773ff502687307abfa024ac9f62f9752a0d210dac2ffd9a29e38e12e2ea82c82"),
+        ("LogMatch(uint256,uint256)", "0x3156f1facedd2c8392e4de7babf62e19484c1ac8f306f3a857c46a1bf39013b9"),
+        ("LogMatchingEnabled(bool)", "0xea11e00ec1642be9b494019b756440e2c57dbe9e59242c4f9c64ce33fb4f41d9"),
+        ("LogMinSell(address,uint256)", "0xc28d56449b0bb31e64ee7487e061f57a2e72aea8019d810832f26dda099823d0"),
+        ("LogSetAuthority(address)", "0x1abebea81bfa2637f28358c371278fb15ede7ea8dd28d2e03b112ff6d936ada4"),
+        ("LogSetOwner(address)", "0xce241d7ca1f669fee44b6fc00b8eba2df3bb514eed0f6f668f8f89096e81ed94"),
+        ("LogSortedOffer(uint256)", "0x20fb9bad86c18f7e22e8065258790d9416a7d2df8ff05f80f82c46d38b925acd"),
+        ("LogTake(bytes32,bytes32,address,address,address,address,uint128,uint128,uint64)", "0x3383e3357c77fd2e3a4b30deea81179bc70a795d053d14d5b7f2f01d0fd4596f"),
+        ("LogTrade(uint256,address,uint256,address)", "0x819e390338feffe95e2de57172d6faf337853dfd15c7a09a32d76f7fd2443875"),
+        ("LogUnsortedOffer(uint256)", "0x8173832a493e0a3989e521458e55bfe9feac9f9b675a94e100b9d5a85f814862"),
+        ("OfferDeleted(bytes32)", "0x233d1d5c74ac8791f0673668c3df846ed7254243dc50ce621d05653f5c64b587"),
+    ];
+
+    const PAIR: &[(&str, &str)] = &[
+        ("LogBatchMarketMakingTrades(address,uint256[])", "0x912c0be8b5d78932353ee6292b3366b917d7d88f90789c85163d36e18251162c"),
+        ("LogBatchRequoteOffers(address,uint256[])", "0x23b3a496131d0d4586d02caa9b90c403ab515a7ca0a345eda899ef0af0dccaad"),
+        ("LogRebalancePair(address,address,address,uint256,uint256,uint256)", "0x1199da27e69c9ac21181f944f3cf690e823feb0705e65603579844d879570963"),
+        ("LogRequote(address,uint256,uint256)", "0xfef679012c81d7308cc9a176133e71381ca058e65095cb79a962137dea393a67"),
+        ("LogScrubbedStratTrade(uint256,uint256,address,address,uint256,address,address)", "0x030b89afc87aaffa357d9c4098ca6f70a13d8658f44140c561bddd866d665eb6"),
+        ("LogStrategistRewardClaim(address,address,uint256,uint256)", "0xd7e245a0605e4329a3ae3bb51bbbe3c07c4c939cbf241c4cccf59b11c2452913"),
+        ("LogStrategistTrade(uint256,bytes32,bytes32,address,address,uint256,address)", "0x9717ea499c0fba392e55b97dd4c755cebb204398666ac5aa0c5c211619fa1a8e"),
+        ("LogTailOffMultiv0(address,address,address[],uint24[],uint256)", "0x6bc354214f7f54c46e05aa087d8e10707837ac45fa9934e501000caaf701af85"),
+        ("LogTailOffv0(address,address,address,address,uint256,uint256)", "0x67cf73b1c64d3a645f8d47632d01e0e596421f6e11521ff61964d7f7419ae913"),
+    ];
+
+    const HOUSE: &[(&str, &str)] = &[
+        ("LogNewBathToken(address,address,address,uint256,address)", "0x3042e0d13ca8ee0bdf1dc720371ee18ade19c59a8dfc8db85869f433cedaf015"),
+        ("LogOpenCreationSignal(address,address,uint256,address,address,uint256,address)", "0x9eb6cc4b72fbb43e01228aeeb5dd940751498582f294e6f77658cb3ccd6a4f50"),
+    ];
+
+    const ROUTER: &[(&str, &str)] = &[
+        ("LogNote(string,uint256)", "0x237ed0dc730435f49ffc52e0c385fe376f53260bda0022cdbd6bea601c058496"),
+        ("LogSwap(uint256,address,uint256,address,bytes32,uint256,address)", "0xe246f5c6615e4a819ae6062dd22d99f289cc78afbf91469c64a81584c45475e5"),
+    ];
+
+    const AID: &[(&str, &str)] = &[
+        ("LogBatchMarketMakingTrades(address,uint256[])", "0x912c0be8b5d78932353ee6292b3366b917d7d88f90789c85163d36e18251162c"),
+        ("LogBatchRequoteOffers(address,uint256[])", "0x23b3a496131d0d4586d02caa9b90c403ab515a7ca0a345eda899ef0af0dccaad"),
+        ("LogRequote(address,uint256,uint256)", "0xfef679012c81d7308cc9a176133e71381ca058e65095cb79a962137dea393a67"),
+        ("LogScrubbedStratTrade(uint256,uint256,address,uint256,address)", "0x05a74080e1908ec46ccac7a5c2359bfc9bd300d90850f36cbd977c307f790b7e"),
+        ("LogStrategistRewardClaim(address,address,uint256,uint256)", "0xd7e245a0605e4329a3ae3bb51bbbe3c07c4c939cbf241c4cccf59b11c2452913"),
+        ("LogStrategistTrade(uint256,bytes32,bytes32,address,address,uint256,address)", "0x9717ea499c0fba392e55b97dd4c755cebb204398666ac5aa0c5c211619fa1a8e"),
+    ];
+
+    fn topics(expected: &[(&str, &str)]) -> Vec<H256> {
+        let mut topics: Vec<H256> = expected.iter().map(|(_, x)| x.parse().unwrap()).collect();
+        topics.sort();
+        topics
+    }
+
+    fn sorted(mut topics: Vec<H256>) -> Vec<H256> {
+        topics.sort();
+        topics
+    }
+
+    #[test]
+    fn topics_match_the_deployed_events() {
+        assert_eq!(sorted(MarketEvent::signatures()), topics(MARKET));
+        assert_eq!(sorted(PairEvent::signatures()), topics(PAIR));
+        assert_eq!(sorted(HouseEvent::signatures()), topics(HOUSE));
+        assert_eq!(sorted(RouterEvent::signatures()), topics(ROUTER));
+        assert_eq!(sorted(AidEvent::signatures()), topics(AID));
+        let market = topics(MARKET);
+        let pair = topics(PAIR);
+        for topic in BookEvent::signatures() {
+            assert!(market.contains(&topic));
+        }
+        for topic in BackfillEvent::signatures() {
+            assert!(market.contains(&topic) || pair.contains(&topic));
+        }
+    }
+
+    #[test]
+    fn topics_name_the_right_events() {
+        let expected: H256 = "0x773ff502687307abfa024ac9f62f9752a0d210dac2ffd9a29e38e12e2ea82c82".parse().unwrap();
+        assert_eq!(LogMake::signature(), expected);
+        assert_eq!(LogMake::abi_signature(), MARKET[8].0);
+        let expected: H256 = "0x3383e3357c77fd2e3a4b30deea81179bc70a795d053d14d5b7f2f01d0fd4596f".parse().unwrap();
+        assert_eq!(LogTake::signature(), expected);
+        assert_eq!(LogTake::abi_signature(), MARKET[15].0);
+        assert_eq!(AidLogScrubbedStratTrade::abi_signature(), AID[3].0);
+        assert_eq!(LogScrubbedStratTrade::abi_signature(), PAIR[4].0);
+    }
+
+    #[test]
+    fn anonymous_log_note_has_the_ds_note_signature() {
+        // DSNote's LogNote is anonymous, so this never appears as a topic0, but it's still the event's signature
+        let expected: H256 = "0x644843f351d3fba4abcd60109eaff9f54bac8fb8ccf0bab941009c21df21cf31".parse().unwrap();
+        assert!(LogNote::is_anonymous());
+        assert_eq!(LogNote::signature(), expected);
+        // a note's first topic is the function selector, not a signature, so it's only picked up as an anonymous event
+        let selector = H256::from_slice(&[[0x1b, 0x33, 0xd4, 0x12].as_slice(), &[0u8; 28]].concat());
+        let log = RawLog {
+            topics: vec![selector, H256::from(Address::repeat_byte(1)), H256::zero(), H256::zero()],
+            data: ethers::abi::encode(&[
+                ethers::abi::Token::Uint(U256::from(7)),
+                ethers::abi::Token::Bytes(vec![1, 2, 3]),
+            ]),
+        };
+        match MarketEvent::decode_log(&log).unwrap() {
+            MarketEvent::Note(note) => {
+                assert_eq!(note.sig, [0x1b, 0x33, 0xd4, 0x12]);
+                assert_eq!(note.guy, Address::repeat_byte(1));
+                assert_eq!(note.wad, U256::from(7));
+            }
+            other => panic!("decoded as {:?}", other),
+        }
+    }
+}
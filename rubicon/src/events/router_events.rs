@@ -0,0 +1,13 @@
+// generated from the RubiconRouter's ABI (see `contracts/router.rs`)
+pub use crate::contracts::router::LogSwapFilter as LogSwap;
+
+/// The router's debugging log, `LogNote(string, uint256)`. This isn't the same event as the market's (anonymous) [`super::LogNote`].
+pub use crate::contracts::router::LogNoteFilter as RouterLogNote;
+
+event_enum! {
+    /// Every event emitted by the RubiconRouter.
+    RouterEvent {
+        Swap(LogSwap),
+        Note(RouterLogNote),
+    }
+}
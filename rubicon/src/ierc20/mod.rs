@@ -2,8 +2,8 @@
 
 use anyhow::Result;
 
+use crate::contracts::IERC20;
 use ethers::{
-    contract::Contract,
     core::types::{Address, Chain, U256},
     prelude::builders::ContractCall,
     providers::Middleware,
@@ -16,22 +16,20 @@ use tracing::{instrument};
  */
 pub struct Token<M: Middleware + 'static> {
     chain: Chain,
-    coin: Contract<M>,
+    coin: IERC20<M>,
 }
 
 impl<M: Middleware + 'static> Token<M> {
     pub fn new(client: Arc<M>, chain: Chain, address: Address) -> Self {
         Self {
-            chain: chain,
-            coin: crate::contracts::ierc_20::build_contract(address, client).unwrap(),
+            chain,
+            coin: IERC20::new(address, client),
         }
     }
 
     pub fn new_from_string<T: ToString>(client: Arc<M>, chain: Chain, address: T) -> Result<Self> {
-        Ok(Self {
-            chain: chain,
-            coin: crate::contracts::ierc_20::build_contract_string(address, client)?,
-        })
+        let hx = hex::decode(address.to_string())?;
+        Ok(Self::new(client, chain, Address::from_slice(hx.as_slice())))
     }
 
     /// Returns a reference to the Chain that this Token is on.
@@ -40,7 +38,7 @@ impl<M: Middleware + 'static> Token<M> {
     }
 
     /// Returns a reference to the underlying ERC-20 contract
-    pub fn contract(&self) -> &Contract<M> {
+    pub fn contract(&self) -> &IERC20<M> {
         &self.coin
     }
 
@@ -59,29 +57,19 @@ impl<M: Middleware + 'static> Token<M> {
     /// Returns the amount of tokens in existence. This function is a View function.
     #[instrument(level = "debug", skip_all)]
     pub async fn total_supply(&self) -> Result<U256> {
-        Ok(self
-            .contract()
-            .method::<_, U256>("totalSupply", ())?
-            .call()
-            .await?)
+        Ok(self.contract().total_supply().call().await?)
     }
 
     /// Returns the amount of tokens owned by `account`. This function is a View function.
     #[instrument(level = "debug", skip(self))]
     pub async fn balance_of(&self, account: Address) -> Result<U256> {
-        Ok(self
-            .contract()
-            .method::<_, U256>("balanceOf", (account,))?
-            .call()
-            .await?)
+        Ok(self.contract().balance_of(account).call().await?)
     }
 
     /// Returns a contract call that transfers `amount` of tokens from the caller's account to the `receiver`'s account. It is the user's responsibility to execute the contract call with either the methods provided in ethers-rs.
     #[instrument(level = "debug", skip(self))]
     pub fn transfer(&self, receiver: Address, amount: U256) -> Result<ContractCall<M, bool>> {
-        let mut tx = self
-            .contract()
-            .method::<_, bool>("transfer", (receiver, amount))?;
+        let mut tx = self.contract().transfer(receiver, amount);
         if self.is_legacy() {
             tx = tx.legacy();
         }
@@ -91,17 +79,13 @@ impl<M: Middleware + 'static> Token<M> {
     /// Returns the amount of `owner`'s tokens that `spender` is allowed to spend. This function is a View function.
     #[instrument(level = "debug", skip(self))]
     pub async fn allowance(&self, owner: Address, spender: Address) -> Result<U256> {
-        Ok(self
-            .contract()
-            .method::<_, U256>("allowance", (owner, spender))?
-            .call()
-            .await?)
+        Ok(self.contract().allowance(owner, spender).call().await?)
     }
 
     /// Returns a contract call that sets `amount` as the allowance of `spender` of the caller's tokens. It is the user's responsibility to execute the contract call with either the methods provided in ethers-rs.
     #[instrument(level = "debug", skip(self))]
     pub fn approve(&self, spender: Address, amount: U256) -> Result<ContractCall<M, bool>> {
-        let mut tx = self.coin.method("approve", (spender, amount))?;
+        let mut tx = self.coin.approve(spender, amount);
         if self.is_legacy() {
             tx = tx.legacy();
         }
@@ -116,7 +100,7 @@ impl<M: Middleware + 'static> Token<M> {
         to: Address,
         amount: U256,
     ) -> Result<ContractCall<M, bool>> {
-        let mut tx = self.coin.method("transferFrom", (from, to, amount))?;
+        let mut tx = self.coin.transfer_from(from, to, amount);
         if self.is_legacy() {
             tx = tx.legacy();
         }
@@ -1,12 +1,12 @@
 //! An SDK to interact with the [Rubicon](https://rubicon.finance) protocol, built on top of [ethers-rs](https://github.com/gakonst/ethers-rs)
 //!
-//! # How to use `rbcn`
+//! # How to use `rubi`
 //!
 //! The basic entry point to Rubicon is through [`RubiconSession`]. We expect most users to wrap this in a [`std::sync::Arc`] and share it across Tokio tasks.
 //!
 //! # Example
-//! ```
-//! use rbcn::prelude::*;
+//! ```ignore
+//! use rubi::prelude::*;
 //! use std::sync::Arc;
 //!
 //! let provider = Provider::<Ws>::connect("this is your provider URL").await.unwrap();
@@ -31,17 +31,22 @@
 //! - `full`: enables all features
 //! - `ierc20`: enables the [`ierc20`] module, and the [`ierc20::Token`] struct that comes with it
 
-mod contracts;
+pub mod contracts;
 pub mod events;
 #[cfg(feature = "ierc20")]
 pub mod ierc20;
+pub mod market;
 pub mod session;
 pub use session::*;
 
 pub mod prelude {
+    pub use super::contracts::{BathHouse, BathPair, MarketAid, RubiconMarket, RubiconRouter};
     pub use super::events::*;
     #[cfg(feature = "ierc20")]
     pub use super::ierc20::*;
+    #[cfg(feature = "ierc20")]
+    pub use super::contracts::IERC20;
+    pub use super::market::*;
     pub use super::session::*;
     pub use numeraire::prelude::*;
 }
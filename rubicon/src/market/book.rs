@@ -0,0 +1,292 @@
+use super::Offer;
+use anyhow::{anyhow, Result};
+use ethers::core::types::U256;
+use numeraire::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// A single offer on one side of an [`OrderBook`], in human readable units of the book's pair.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BookLevel {
+    id: U256,
+    price: Price,
+    size: Decimal,
+}
+
+impl BookLevel {
+    /// Builds a level out of an offer that swaps `swap.source()` for `swap.target()`. The two assets must be those of `pair`.
+    pub fn from_swap(id: U256, swap: &AssetSwap, pair: &Pair) -> Result<Self> {
+        let price = swap.implied_price(pair)?;
+        let base = if swap.source().asset() == pair.base() {
+            swap.source()
+        } else {
+            swap.target()
+        };
+        Ok(Self {
+            id,
+            price,
+            size: base.to_human_decimal()?,
+        })
+    }
+
+    /// Like [`BookLevel::from_swap`], but an offer whose size or price can't be held in a [`Decimal`] is skipped with a warning,
+    /// so that one huge (or dust) offer doesn't fail a whole snapshot. This still fails if the offer isn't on `pair`.
+    pub fn for_snapshot(id: U256, swap: &AssetSwap, pair: &Pair) -> Result<Option<Self>> {
+        let (source, target) = (swap.source().asset(), swap.target().asset());
+        if !(pair.contains(source) && pair.contains(target) && source != target) {
+            return Err(anyhow!(
+                "[BookLevel::for_snapshot]: offer {} swaps {} for {}, which isn't on {}",
+                id,
+                source,
+                target,
+                pair
+            ));
+        }
+        match Self::from_swap(id, swap, pair) {
+            Ok(level) => Ok(Some(level)),
+            Err(e) => {
+                warn!("[BookLevel::for_snapshot]: leaving offer {} out of the {} book: {}", id, pair, e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Builds a level out of an offer. The assets of the offer must be those of `pair`.
+    pub fn from_offer(offer: &Offer, pair: &Pair) -> Result<Self> {
+        Self::from_swap(offer.id(), &offer.to_swap(), pair)
+    }
+
+    /// The id of the offer behind this level
+    pub fn id(&self) -> U256 {
+        self.id
+    }
+
+    /// Returns a reference to the price, in units of quote/base
+    pub fn price(&self) -> &Price {
+        &self.price
+    }
+
+    /// The size of the level, in units of the base
+    pub fn size(&self) -> Decimal {
+        self.size
+    }
+
+    /// The size of the level, in units of the quote
+    pub fn quote_size(&self) -> Decimal {
+        self.size * self.price.value()
+    }
+}
+
+/**
+ * A snapshot of the RubiconMarket's book for a single [`Pair`].
+ * Bids are offers to buy the base (they pay the quote), sorted from the highest price down.
+ * Asks are offers to sell the base (they pay the base), sorted from the lowest price up.
+ */
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderBook {
+    pair: Pair,
+    block: Option<u64>,
+    bids: Vec<BookLevel>,
+    asks: Vec<BookLevel>,
+}
+
+impl OrderBook {
+    /// Creates a book out of levels that are already sorted best first. Fails if a level isn't quoted on `pair`.
+    pub fn new(pair: Pair, bids: Vec<BookLevel>, asks: Vec<BookLevel>, block: Option<u64>) -> Result<Self> {
+        if let Some(level) = bids.iter().chain(asks.iter()).find(|x| x.price().pair() != &pair) {
+            return Err(anyhow!(
+                "[OrderBook::new]: level {} is quoted on {}, but the book is for {}",
+                level.id(),
+                level.price().pair(),
+                pair
+            ));
+        }
+        Ok(Self {
+            pair,
+            block,
+            bids,
+            asks,
+        })
+    }
+
+    /// Returns a reference to the pair
+    pub fn pair(&self) -> &Pair {
+        &self.pair
+    }
+
+    /// The block the snapshot was taken at, if it's known
+    pub fn block(&self) -> Option<u64> {
+        self.block
+    }
+
+    /// The bids, best (highest) first
+    pub fn bids(&self) -> &[BookLevel] {
+        &self.bids
+    }
+
+    /// The asks, best (lowest) first
+    pub fn asks(&self) -> &[BookLevel] {
+        &self.asks
+    }
+
+    /// The levels on one side of the book, where [`Side::Buy`] is the bids and [`Side::Sell`] is the asks
+    pub fn levels(&self, side: Side) -> &[BookLevel] {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<&BookLevel> {
+        self.bids.first()
+    }
+
+    pub fn best_ask(&self) -> Option<&BookLevel> {
+        self.asks.first()
+    }
+
+    /// The midpoint of the best bid and the best ask, if both sides have liquidity
+    pub fn mid(&self) -> Option<Price> {
+        let (bid, ask) = (self.best_bid()?, self.best_ask()?);
+        let mid = (bid.price().value() + ask.price().value()) / Decimal::TWO;
+        Price::new(self.pair, mid).ok()
+    }
+
+    /// The best ask minus the best bid, in units of quote/base. This is negative if the book is crossed.
+    pub fn spread(&self) -> Option<Decimal> {
+        Some(self.best_ask()?.price().value() - self.best_bid()?.price().value())
+    }
+
+    /// The cumulative base size available on one side of the book at each level, best first.
+    /// e.g. `cumulative_depth(Side::Sell)[2]` is how much of the base could be bought by sweeping the three best asks.
+    pub fn cumulative_depth(&self, side: Side) -> Vec<(Price, Decimal)> {
+        self.levels(side)
+            .iter()
+            .scan(Decimal::ZERO, |total, level| {
+                *total += level.size();
+                Some((*level.price(), *total))
+            })
+            .collect()
+    }
+
+    /// The total base size on one side of the book at prices at least as good as `limit`
+    /// (at or above it for bids, at or below it for asks).
+    pub fn depth_at(&self, side: Side, limit: &Price) -> Decimal {
+        self.levels(side)
+            .iter()
+            .take_while(|level| match side {
+                Side::Buy => level.price().value() >= limit.value(),
+                Side::Sell => level.price().value() <= limit.value(),
+            })
+            .map(|level| level.size())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::types::Chain;
+
+    const CHAIN: Chain = Chain::Optimism;
+    const ETH: u128 = 1_000_000_000_000_000_000;
+    const USDC: u128 = 1_000_000;
+
+    fn dec(x: &str) -> Decimal {
+        x.parse().unwrap()
+    }
+
+    fn pair() -> Pair {
+        Pair::new(Asset::Weth, Asset::Usdc)
+    }
+
+    fn weth(x: U256) -> ChainNativeAsset {
+        ChainNativeAsset::new(CHAIN, Asset::Weth, x).unwrap()
+    }
+
+    fn usdc(x: U256) -> ChainNativeAsset {
+        ChainNativeAsset::new(CHAIN, Asset::Usdc, x).unwrap()
+    }
+
+    // a bid pays USDC for WETH, an ask pays WETH for USDC
+    fn bid(id: u64, eth: u128, usd: u128) -> BookLevel {
+        BookLevel::from_swap(U256::from(id), &AssetSwap::new(usdc(usd.into()), weth(eth.into())), &pair()).unwrap()
+    }
+
+    fn ask(id: u64, eth: u128, usd: u128) -> BookLevel {
+        BookLevel::from_swap(U256::from(id), &AssetSwap::new(weth(eth.into()), usdc(usd.into())), &pair()).unwrap()
+    }
+
+    fn book() -> OrderBook {
+        OrderBook::new(
+            pair(),
+            vec![bid(1, ETH, 1990 * USDC), bid(2, 2 * ETH, 3960 * USDC)],
+            vec![ask(3, ETH / 2, 1005 * USDC), ask(4, 3 * ETH, 6060 * USDC)],
+            Some(100),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn levels_are_sized_in_the_base_and_priced_in_the_quote() {
+        let level = bid(1, 2 * ETH, 3960 * USDC);
+        assert_eq!(level.price().value(), dec("1980"));
+        assert_eq!(level.size(), dec("2"));
+        assert_eq!(level.quote_size(), dec("3960"));
+        // an ask comes out the same way up
+        let level = ask(2, ETH / 2, 1005 * USDC);
+        assert_eq!(level.price().value(), dec("2010"));
+        assert_eq!(level.size(), dec("0.5"));
+        assert!(BookLevel::from_swap(U256::one(), &AssetSwap::new(weth(U256::one()), weth(U256::one())), &pair()).is_err());
+    }
+
+    #[test]
+    fn the_book_reads_best_first() {
+        let book = book();
+        assert_eq!(book.best_bid().unwrap().id(), U256::from(1));
+        assert_eq!(book.best_ask().unwrap().id(), U256::from(3));
+        assert_eq!(book.levels(Side::Buy), book.bids());
+        assert_eq!(book.levels(Side::Sell), book.asks());
+        assert_eq!(book.mid().unwrap().value(), dec("2000"));
+        assert_eq!(book.spread().unwrap(), dec("20"));
+        assert_eq!(book.block(), Some(100));
+
+        let empty = OrderBook::new(pair(), Vec::new(), vec![ask(3, ETH, 2010 * USDC)], None).unwrap();
+        assert!(empty.mid().is_none());
+        assert!(empty.spread().is_none());
+        // a level on another pair doesn't belong in the book
+        assert!(OrderBook::new(pair().inverse(), vec![bid(1, ETH, 1990 * USDC)], Vec::new(), None).is_err());
+    }
+
+    #[test]
+    fn depth_adds_up_from_the_top_of_the_book() {
+        let book = book();
+        let depth = book.cumulative_depth(Side::Sell);
+        assert_eq!(depth.len(), 2);
+        assert_eq!((depth[0].0.value(), depth[0].1), (dec("2010"), dec("0.5")));
+        assert_eq!((depth[1].0.value(), depth[1].1), (dec("2020"), dec("3.5")));
+        let depth = book.cumulative_depth(Side::Buy);
+        assert_eq!((depth[1].0.value(), depth[1].1), (dec("1980"), dec("3")));
+
+        let price = |x| Price::new(pair(), x).unwrap();
+        assert_eq!(book.depth_at(Side::Sell, &price(dec("2015"))), dec("0.5"));
+        assert_eq!(book.depth_at(Side::Sell, &price(dec("2020"))), dec("3.5"));
+        assert_eq!(book.depth_at(Side::Buy, &price(dec("1985"))), dec("1"));
+        assert_eq!(book.depth_at(Side::Buy, &price(dec("2500"))), Decimal::ZERO);
+    }
+
+    #[test]
+    fn an_unrepresentable_offer_is_left_out_of_a_snapshot() {
+        // more WETH than a Decimal can hold
+        let whale = AssetSwap::new(weth(U256::MAX), usdc(U256::from(1000 * USDC)));
+        assert!(BookLevel::from_swap(U256::one(), &whale, &pair()).is_err());
+        assert_eq!(BookLevel::for_snapshot(U256::one(), &whale, &pair()).unwrap(), None);
+
+        let fine = AssetSwap::new(weth(ETH.into()), usdc(U256::from(2000 * USDC)));
+        assert!(BookLevel::for_snapshot(U256::one(), &fine, &pair()).unwrap().is_some());
+        // but an offer on the wrong pair is still a mistake
+        let wrong = AssetSwap::new(weth(ETH.into()), ChainNativeAsset::new(CHAIN, Asset::Op, U256::one()).unwrap());
+        assert!(BookLevel::for_snapshot(U256::one(), &wrong, &pair()).is_err());
+    }
+}
@@ -0,0 +1,484 @@
+use super::{BookLevel, Offer, OrderBook};
+use crate::events::{BookEvent, LogMeta, LoggedEvent};
+use anyhow::{anyhow, Result};
+use ethers::core::types::{Address, Chain, H256, U256};
+use numeraire::prelude::*;
+use std::collections::{HashMap, VecDeque};
+
+/// How many blocks of history a [`LiveOrderBook`] keeps by default, i.e. the deepest reorg it can roll back.
+pub const DEFAULT_REORG_DEPTH: u64 = 64;
+
+// one change to the book, and what it replaced
+#[derive(Clone, Debug)]
+struct JournalEntry {
+    block_number: u64,
+    block_hash: H256,
+    id: U256,
+    previous: Option<Offer>,
+    // whether `previous` was in the sorted book, rather than waiting to be sorted
+    was_sorted: bool,
+}
+
+/**
+ * An order book that stays current by applying the market's [`BookEvent`]s, rather than by polling.
+ *
+ * A [`LiveOrderBook`] is seeded from a snapshot of every offer on a set of pairs at some block (see [`crate::RubiconSession::live_order_book`]),
+ * and then fed every book event after that block, e.g. from [`crate::RubiconSession::stream_book_events`]. Events for other pairs are ignored.
+ *
+ * Like the snapshot, the book only holds offers in the market's sorted lists. An offer that's made without being sorted
+ * (e.g. while matching is disabled) is held back until a `LogSortedOffer` puts it in the sorted book.
+ *
+ * Every change is journalled with the block it came from. When a log is removed by a reorg, or a log arrives from a
+ * different block at a height we've already seen, the book is rolled back to the state before that height.
+ * The replacement logs are then applied as they arrive. History older than the reorg depth is forgotten.
+ */
+#[derive(Clone, Debug)]
+pub struct LiveOrderBook {
+    chain: Chain,
+    pairs: Vec<Pair>,
+    assets: HashMap<Address, Asset>,
+    offers: HashMap<U256, Offer>,
+    // offers on our pairs that have been made, but not sorted
+    unsorted: HashMap<U256, Offer>,
+    seed_block: u64,
+    // the newest block whose changes have been pruned from the journal, so can't be rolled back
+    pruned_through: u64,
+    last_position: Option<(u64, U256)>,
+    journal: VecDeque<JournalEntry>,
+    reorg_depth: u64,
+}
+
+impl LiveOrderBook {
+    /// Creates a book for `pairs` out of every offer that was in the market's sorted lists for them at `block`.
+    /// Offers that aren't on one of the pairs are ignored.
+    pub fn new(chain: Chain, pairs: Vec<Pair>, offers: Vec<Offer>, block: u64) -> Result<Self> {
+        let mut assets = HashMap::new();
+        for pair in pairs.iter() {
+            for asset in [pair.base(), pair.quote()] {
+                assets.insert(asset.to_address(&chain)?, *asset);
+            }
+        }
+        let mut book = Self {
+            chain,
+            pairs,
+            assets,
+            offers: HashMap::new(),
+            unsorted: HashMap::new(),
+            seed_block: block,
+            pruned_through: block,
+            last_position: None,
+            journal: VecDeque::new(),
+            reorg_depth: DEFAULT_REORG_DEPTH,
+        };
+        for offer in offers {
+            if book.pair_of(offer.pay().asset(), offer.buy().asset()).is_some() {
+                book.offers.insert(offer.id(), offer);
+            }
+        }
+        Ok(book)
+    }
+
+    /// Sets how many blocks of history to keep for rolling back reorgs.
+    pub fn with_reorg_depth(mut self, reorg_depth: u64) -> Self {
+        self.reorg_depth = reorg_depth;
+        self
+    }
+
+    /// The pairs the book tracks
+    pub fn pairs(&self) -> &[Pair] {
+        &self.pairs
+    }
+
+    /// The block the book was seeded at
+    pub fn seed_block(&self) -> u64 {
+        self.seed_block
+    }
+
+    /// The most recent block the book has applied an event from, or the seed block if it hasn't applied any
+    pub fn block(&self) -> u64 {
+        self.last_position.map_or(self.seed_block, |(block, _)| block)
+    }
+
+    /// Returns the live offer with the given id, if it's on one of the tracked pairs
+    pub fn offer(&self, id: U256) -> Option<&Offer> {
+        self.offers.get(&id)
+    }
+
+    /// Every live offer on the tracked pairs
+    pub fn offers(&self) -> impl Iterator<Item = &Offer> {
+        self.offers.values()
+    }
+
+    /// Applies a book event. Events from at or before the seed block, and events we've already applied, are ignored.
+    /// Fails if a reorg reaches further back than the history we've kept.
+    pub fn apply(&mut self, event: &LoggedEvent<BookEvent>) -> Result<()> {
+        let meta = event.meta();
+        if meta.removed() {
+            return self.rollback_from(meta.block_number());
+        }
+        if meta.block_number() <= self.seed_block {
+            return Ok(());
+        }
+        // a different block at a height we've already applied means we missed the removal, so roll back anyway
+        if self
+            .journal
+            .iter()
+            .any(|x| x.block_number == meta.block_number() && x.block_hash != meta.block_hash())
+        {
+            self.rollback_from(meta.block_number())?;
+        }
+        if self.last_position.is_some_and(|x| meta.position() <= x) {
+            return Ok(());
+        }
+        // only once it's applied, so that an event we couldn't apply isn't skipped when it's retried
+        self.apply_event(event.event(), meta)?;
+        self.last_position = Some(meta.position());
+        self.prune();
+        Ok(())
+    }
+
+    /// Undoes every change from `block` onwards.
+    pub fn rollback_from(&mut self, block: u64) -> Result<()> {
+        if block <= self.pruned_through {
+            return Err(anyhow!(
+                "[LiveOrderBook]: can't roll back block {}, the book only has history after block {}",
+                block,
+                self.pruned_through
+            ));
+        }
+        if block > self.block() {
+            // we never applied anything from there
+            return Ok(());
+        }
+        while self.journal.back().is_some_and(|x| x.block_number >= block) {
+            let entry = self.journal.pop_back().unwrap();
+            self.offers.remove(&entry.id);
+            self.unsorted.remove(&entry.id);
+            if let Some(offer) = entry.previous {
+                match entry.was_sorted {
+                    true => self.offers.insert(entry.id, offer),
+                    false => self.unsorted.insert(entry.id, offer),
+                };
+            }
+        }
+        // anything from the orphaned blocks is new to us once it's mined again
+        self.last_position = Some((block - 1, U256::MAX));
+        Ok(())
+    }
+
+    /// A snapshot of the book for `pair`, which must be one of the tracked pairs.
+    pub fn order_book(&self, pair: &Pair) -> Result<OrderBook> {
+        if !self.pairs.contains(pair) {
+            return Err(anyhow!("[LiveOrderBook]: {} isn't tracked by this book", pair));
+        }
+        let mut bids = Vec::new();
+        let mut asks = Vec::new();
+        for offer in self.offers.values() {
+            if offer.pay().asset() == pair.quote() && offer.buy().asset() == pair.base() {
+                bids.extend(BookLevel::for_snapshot(offer.id(), &offer.to_swap(), pair)?);
+            } else if offer.pay().asset() == pair.base() && offer.buy().asset() == pair.quote() {
+                asks.extend(BookLevel::for_snapshot(offer.id(), &offer.to_swap(), pair)?);
+            }
+        }
+        // best first, and the market fills older offers first at the same price
+        bids.sort_by(|a, b| b.price().value().cmp(&a.price().value()).then(a.id().cmp(&b.id())));
+        asks.sort_by(|a, b| a.price().value().cmp(&b.price().value()).then(a.id().cmp(&b.id())));
+        OrderBook::new(*pair, bids, asks, Some(self.block()))
+    }
+
+    /// The live offers selling `sell` for `buy`, best first, in the market's own order. Unlike [`LiveOrderBook::order_book`],
+    /// the offers keep their exact wei amounts, e.g. for a [`super::QuoteSimulation`].
+    pub fn sorted_offers(&self, sell: &Asset, buy: &Asset) -> Vec<Offer> {
+        let mut offers: Vec<Offer> = self
+            .offers
+            .values()
+            .filter(|x| x.pay().asset() == sell && x.buy().asset() == buy)
+            .cloned()
+            .collect();
+        // a better offer pays more per unit it buys, compared the way the market does, and older offers come first at the same price
+        offers.sort_by(|a, b| {
+            b.pay()
+                .size()
+                .full_mul(*a.buy().size())
+                .cmp(&a.pay().size().full_mul(*b.buy().size()))
+                .then(a.id().cmp(&b.id()))
+        });
+        offers
+    }
+
+    pub fn best_bid(&self, pair: &Pair) -> Result<Option<BookLevel>> {
+        Ok(self.order_book(pair)?.best_bid().copied())
+    }
+
+    pub fn best_ask(&self, pair: &Pair) -> Result<Option<BookLevel>> {
+        Ok(self.order_book(pair)?.best_ask().copied())
+    }
+
+    /// See [`OrderBook::mid`]
+    pub fn mid(&self, pair: &Pair) -> Result<Option<Price>> {
+        Ok(self.order_book(pair)?.mid())
+    }
+
+    /// See [`OrderBook::spread`]
+    pub fn spread(&self, pair: &Pair) -> Result<Option<rust_decimal::Decimal>> {
+        Ok(self.order_book(pair)?.spread())
+    }
+
+    /// See [`OrderBook::cumulative_depth`]
+    pub fn cumulative_depth(&self, pair: &Pair, side: Side) -> Result<Vec<(Price, rust_decimal::Decimal)>> {
+        Ok(self.order_book(pair)?.cumulative_depth(side))
+    }
+
+    fn apply_event(&mut self, event: &BookEvent, meta: &LogMeta) -> Result<()> {
+        let id = event.offer_id();
+        let current = match (self.offers.get(&id), self.unsorted.get(&id)) {
+            (Some(offer), _) => Some((offer.clone(), true)),
+            (None, Some(offer)) => Some((offer.clone(), false)),
+            (None, None) => None,
+        };
+        // the offer after the event, and whether it's sorted
+        let updated = match (event, &current) {
+            // the market always sorts (or doesn't) after logging the make, so a new offer starts out unsorted
+            (BookEvent::Make(x), _) => {
+                match self.offer_from(id, x.maker, x.pay_gem, x.pay_amt, x.buy_gem, x.buy_amt, x.timestamp)? {
+                    Some(offer) => Some((offer, false)),
+                    None => return Ok(()),
+                }
+            }
+            (BookEvent::Sorted(_), Some((offer, false))) => Some((offer.clone(), true)),
+            (BookEvent::Bump(x), Some((_, sorted))) => {
+                match self.offer_from(id, x.maker, x.pay_gem, x.pay_amt, x.buy_gem, x.buy_amt, x.timestamp)? {
+                    Some(offer) => Some((offer, *sorted)),
+                    None => return Ok(()),
+                }
+            }
+            (BookEvent::Take(x), Some((offer, sorted))) => {
+                let pay = offer.pay().checked_sub(&ChainNativeAsset::new(self.chain, *offer.pay().asset(), x.take_amt.into())?)?;
+                let buy = offer.buy().checked_sub(&ChainNativeAsset::new(self.chain, *offer.buy().asset(), x.give_amt.into())?)?;
+                match pay.size().is_zero() {
+                    true => None,
+                    false => Some((Offer::new(id, offer.owner(), pay, buy, offer.timestamp()), *sorted)),
+                }
+            }
+            (BookEvent::Kill(_) | BookEvent::Deleted(_), Some(_)) => None,
+            // not one of ours, or already sorted
+            _ => return Ok(()),
+        };
+        self.offers.remove(&id);
+        self.unsorted.remove(&id);
+        if let Some((offer, sorted)) = updated {
+            match sorted {
+                true => self.offers.insert(id, offer),
+                false => self.unsorted.insert(id, offer),
+            };
+        }
+        let (previous, was_sorted) = match current {
+            Some((offer, sorted)) => (Some(offer), sorted),
+            None => (None, false),
+        };
+        self.journal.push_back(JournalEntry {
+            block_number: meta.block_number(),
+            block_hash: meta.block_hash(),
+            id,
+            previous,
+            was_sorted,
+        });
+        Ok(())
+    }
+
+    // builds an offer out of a make or bump, if it's on one of our pairs
+    #[allow(clippy::too_many_arguments)]
+    fn offer_from(
+        &self,
+        id: U256,
+        maker: Address,
+        pay_gem: Address,
+        pay_amt: u128,
+        buy_gem: Address,
+        buy_amt: u128,
+        timestamp: u64,
+    ) -> Result<Option<Offer>> {
+        let (pay, buy) = match (self.assets.get(&pay_gem), self.assets.get(&buy_gem)) {
+            (Some(pay), Some(buy)) if self.pair_of(pay, buy).is_some() => (*pay, *buy),
+            _ => return Ok(None),
+        };
+        Ok(Some(Offer::new(
+            id,
+            maker,
+            ChainNativeAsset::new(self.chain, pay, pay_amt.into())?,
+            ChainNativeAsset::new(self.chain, buy, buy_amt.into())?,
+            timestamp,
+        )))
+    }
+
+    fn pair_of(&self, pay: &Asset, buy: &Asset) -> Option<&Pair> {
+        self.pairs
+            .iter()
+            .find(|x| (x.base() == pay && x.quote() == buy) || (x.base() == buy && x.quote() == pay))
+    }
+
+    fn prune(&mut self) {
+        let horizon = self.block().saturating_sub(self.reorg_depth);
+        while self.journal.front().is_some_and(|x| x.block_number < horizon) {
+            let entry = self.journal.pop_front().unwrap();
+            self.pruned_through = self.pruned_through.max(entry.block_number);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{LogKill, LogMake, LogSortedOffer, LogTake};
+    use ethers::core::types::Log;
+
+    const CHAIN: Chain = Chain::Optimism;
+    const ETH: u128 = 1_000_000_000_000_000_000;
+    const USDC: u128 = 1_000_000;
+
+    fn pair() -> Pair {
+        Pair::new(Asset::Weth, Asset::Usdc)
+    }
+
+    fn gem(asset: Asset) -> Address {
+        asset.to_address(&CHAIN).unwrap()
+    }
+
+    fn id(x: u64) -> [u8; 32] {
+        let mut id = [0u8; 32];
+        U256::from(x).to_big_endian(&mut id);
+        id
+    }
+
+    // an ask selling `pay` wei of WETH for `buy` wei of USDC, as `get_offer` would return it
+    fn ask(x: u64, pay: u128, buy: u128) -> Offer {
+        Offer::new(
+            U256::from(x),
+            Address::zero(),
+            ChainNativeAsset::new(CHAIN, Asset::Weth, pay.into()).unwrap(),
+            ChainNativeAsset::new(CHAIN, Asset::Usdc, buy.into()).unwrap(),
+            0,
+        )
+    }
+
+    fn logged(event: BookEvent, block: u64, index: u64) -> LoggedEvent<BookEvent> {
+        let log = Log {
+            block_number: Some(block.into()),
+            block_hash: Some(H256::from_low_u64_be(block)),
+            transaction_hash: Some(H256::from_low_u64_be(block)),
+            log_index: Some(index.into()),
+            ..Default::default()
+        };
+        LoggedEvent::new(event, LogMeta::from_log(&log).unwrap())
+    }
+
+    fn make(x: u64, pay: u128, buy: u128) -> BookEvent {
+        BookEvent::Make(LogMake {
+            id: id(x),
+            pair: [0u8; 32],
+            maker: Address::zero(),
+            pay_gem: gem(Asset::Weth),
+            buy_gem: gem(Asset::Usdc),
+            pay_amt: pay,
+            buy_amt: buy,
+            timestamp: 0,
+        })
+    }
+
+    fn sorted(x: u64) -> BookEvent {
+        BookEvent::Sorted(LogSortedOffer { id: U256::from(x) })
+    }
+
+    fn take(x: u64, take_amt: u128, give_amt: u128) -> BookEvent {
+        BookEvent::Take(LogTake {
+            id: id(x),
+            pair: [0u8; 32],
+            maker: Address::zero(),
+            pay_gem: gem(Asset::Weth),
+            buy_gem: gem(Asset::Usdc),
+            taker: Address::zero(),
+            take_amt,
+            give_amt,
+            timestamp: 0,
+        })
+    }
+
+    fn kill(x: u64, pay: u128, buy: u128) -> BookEvent {
+        BookEvent::Kill(LogKill {
+            id: id(x),
+            pair: [0u8; 32],
+            maker: Address::zero(),
+            pay_gem: gem(Asset::Weth),
+            buy_gem: gem(Asset::Usdc),
+            pay_amt: pay,
+            buy_amt: buy,
+            timestamp: 0,
+        })
+    }
+
+    // the book that `live_order_book` would seed from the market's sorted lists at `block`
+    fn snapshot(offers: Vec<Offer>, block: u64) -> LiveOrderBook {
+        LiveOrderBook::new(CHAIN, vec![pair()], offers, block).unwrap()
+    }
+
+    fn assert_same_book(live: &LiveOrderBook, expected: &LiveOrderBook) {
+        assert_eq!(live.order_book(&pair()).unwrap(), expected.order_book(&pair()).unwrap());
+        assert_eq!(
+            live.sorted_offers(&Asset::Weth, &Asset::Usdc),
+            expected.sorted_offers(&Asset::Weth, &Asset::Usdc)
+        );
+    }
+
+    #[test]
+    fn replaying_events_over_a_seed_matches_a_later_snapshot() {
+        let mut live = snapshot(vec![ask(1, ETH, 2000 * USDC), ask(2, ETH, 2100 * USDC)], 10);
+        let events = [
+            // a matched make is sorted straight away
+            logged(make(3, ETH, 2050 * USDC), 11, 0),
+            logged(sorted(3), 11, 1),
+            // an unsorted make isn't in the sorted lists, so it's not in the snapshot either, however good its price
+            logged(make(4, ETH, 1500 * USDC), 11, 2),
+            logged(take(1, ETH / 2, 1000 * USDC), 12, 0),
+            logged(kill(2, ETH, 2100 * USDC), 12, 1),
+        ];
+        for event in events.iter() {
+            live.apply(event).unwrap();
+        }
+        assert_eq!(live.block(), 12);
+        assert!(live.offer(U256::from(4)).is_none());
+        assert_same_book(&live, &snapshot(vec![ask(1, ETH / 2, 1000 * USDC), ask(3, ETH, 2050 * USDC)], 12));
+
+        // once it's sorted, the unsorted offer joins the book with whatever was taken from it in the meantime
+        live.apply(&logged(take(4, ETH / 4, 375 * USDC), 13, 0)).unwrap();
+        live.apply(&logged(sorted(4), 13, 1)).unwrap();
+        let at_13 = snapshot(
+            vec![ask(4, 3 * ETH / 4, 1125 * USDC), ask(1, ETH / 2, 1000 * USDC), ask(3, ETH, 2050 * USDC)],
+            13,
+        );
+        assert_same_book(&live, &at_13);
+
+        // a reorg of block 13 puts the offer back where it was, out of the book
+        live.rollback_from(13).unwrap();
+        assert_same_book(&live, &snapshot(vec![ask(1, ETH / 2, 1000 * USDC), ask(3, ETH, 2050 * USDC)], 12));
+        live.apply(&logged(take(4, ETH / 4, 375 * USDC), 13, 0)).unwrap();
+        live.apply(&logged(sorted(4), 13, 1)).unwrap();
+        assert_same_book(&live, &at_13);
+    }
+
+    #[test]
+    fn an_event_that_fails_to_apply_is_not_marked_applied() {
+        let mut live = snapshot(vec![ask(1, ETH, 2000 * USDC)], 10);
+        // taking more than our copy of the offer has left can't be applied
+        let overdrawn = logged(take(1, 2 * ETH, 4000 * USDC), 11, 0);
+        assert!(live.apply(&overdrawn).is_err());
+        assert_eq!(live.block(), 10);
+        // so the same event is tried again rather than skipped as a duplicate
+        assert!(live.apply(&overdrawn).is_err());
+        assert_same_book(&live, &snapshot(vec![ask(1, ETH, 2000 * USDC)], 10));
+
+        // and an event at the same position that does apply still gets through
+        live.apply(&logged(take(1, ETH / 2, 1000 * USDC), 11, 0)).unwrap();
+        assert_eq!(live.block(), 11);
+        assert_same_book(&live, &snapshot(vec![ask(1, ETH / 2, 1000 * USDC)], 11));
+    }
+}
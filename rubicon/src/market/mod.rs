@@ -0,0 +1,20 @@
+/**
+ * Typed views of the RubiconMarket's state. These are what the session's view functions return,
+ * with token addresses already resolved to [`numeraire::prelude::Asset`]s.
+ */
+mod book;
+mod live;
+mod offer;
+mod offer_id;
+mod outcome;
+mod route;
+mod simulate;
+mod slippage;
+pub use book::*;
+pub use live::*;
+pub use offer::*;
+pub use offer_id::*;
+pub use outcome::*;
+pub use route::*;
+pub use simulate::*;
+pub use slippage::*;
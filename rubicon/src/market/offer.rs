@@ -0,0 +1,60 @@
+use anyhow::Result;
+use ethers::core::types::{Address, U256};
+use numeraire::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A live offer on the RubiconMarket: `owner` sells `pay` for `buy`.
+/// As the offer is partially filled, both `pay` and `buy` shrink in proportion.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Offer {
+    id: U256,
+    owner: Address,
+    pay: ChainNativeAsset,
+    buy: ChainNativeAsset,
+    timestamp: u64,
+}
+
+impl Offer {
+    pub fn new(id: U256, owner: Address, pay: ChainNativeAsset, buy: ChainNativeAsset, timestamp: u64) -> Self {
+        Self {
+            id,
+            owner,
+            pay,
+            buy,
+            timestamp,
+        }
+    }
+
+    pub fn id(&self) -> U256 {
+        self.id
+    }
+
+    pub fn owner(&self) -> Address {
+        self.owner
+    }
+
+    /// Returns a reference to what the offer is selling
+    pub fn pay(&self) -> &ChainNativeAsset {
+        &self.pay
+    }
+
+    /// Returns a reference to what the offer wants in return
+    pub fn buy(&self) -> &ChainNativeAsset {
+        &self.buy
+    }
+
+    /// The unix timestamp of the block the offer was made in
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// The offer as a swap from the owner's point of view: they give up `pay` for `buy`.
+    pub fn to_swap(&self) -> AssetSwap {
+        AssetSwap::new(self.pay.clone(), self.buy.clone())
+    }
+
+    /// The price of the offer quoted on `pair`, which must contain both of the offer's assets.
+    pub fn price(&self, pair: &Pair) -> Result<Price> {
+        self.to_swap().implied_price(pair)
+    }
+}
@@ -0,0 +1,109 @@
+use ethers::core::types::{H256, U256};
+use serde::{Deserialize, Serialize};
+
+/**
+ * The id of an offer on the RubiconMarket.
+ *
+ * The market takes ids as a `uint256` in `offer`/`buy`/`cancel`, and as a `bytes32` in the `make`/`take`/`kill`/`bump` API and its events.
+ * Both are the same number: the `bytes32` is the `uint256` in big-endian order, so an [`OfferId`] converts freely between them.
+ */
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct OfferId(U256);
+
+impl OfferId {
+    pub fn new(id: U256) -> Self {
+        Self(id)
+    }
+
+    /// The id in the `uint256` form, as `offer`/`buy`/`cancel` take it
+    pub fn as_u256(&self) -> U256 {
+        self.0
+    }
+
+    /// The id in the `bytes32` form, as `make`/`take`/`kill`/`bump` take it
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        self.0.to_big_endian(&mut bytes);
+        bytes
+    }
+
+    /// The market uses id 0 to mean "no offer"
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+}
+
+impl From<U256> for OfferId {
+    fn from(id: U256) -> Self {
+        Self(id)
+    }
+}
+
+impl From<[u8; 32]> for OfferId {
+    fn from(id: [u8; 32]) -> Self {
+        Self(U256::from_big_endian(&id))
+    }
+}
+
+impl From<H256> for OfferId {
+    fn from(id: H256) -> Self {
+        Self::from(id.0)
+    }
+}
+
+impl From<OfferId> for U256 {
+    fn from(id: OfferId) -> Self {
+        id.0
+    }
+}
+
+impl From<OfferId> for [u8; 32] {
+    fn from(id: OfferId) -> Self {
+        id.to_bytes()
+    }
+}
+
+impl From<OfferId> for H256 {
+    fn from(id: OfferId) -> Self {
+        H256(id.to_bytes())
+    }
+}
+
+impl std::fmt::Display for OfferId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids() -> Vec<U256> {
+        vec![U256::zero(), U256::one(), U256::from(0x1234_5678_u64), U256::from(u128::MAX), U256::MAX]
+    }
+
+    #[test]
+    fn every_form_round_trips() {
+        for id in ids() {
+            let offer_id = OfferId::new(id);
+            assert_eq!(U256::from(offer_id), id);
+            assert_eq!(OfferId::from(<[u8; 32]>::from(offer_id)), offer_id);
+            assert_eq!(OfferId::from(H256::from(offer_id)), offer_id);
+            assert_eq!(OfferId::from(offer_id.to_bytes()).as_u256(), id);
+            assert_eq!(offer_id.is_zero(), id.is_zero());
+        }
+    }
+
+    #[test]
+    fn the_bytes_are_big_endian() {
+        let mut bytes = [0u8; 32];
+        bytes[31] = 1;
+        assert_eq!(OfferId::new(U256::one()).to_bytes(), bytes);
+        assert_eq!(OfferId::from(bytes), OfferId::new(U256::one()));
+        assert_eq!(OfferId::new(U256::MAX).to_bytes(), [0xff; 32]);
+        assert_eq!(H256::from(OfferId::new(U256::from(0x0102))), H256::from_low_u64_be(0x0102));
+        assert_eq!(OfferId::default().to_bytes(), [0u8; 32]);
+        assert!(OfferId::from(H256::zero()).is_zero());
+    }
+}
@@ -0,0 +1,221 @@
+use super::Offer;
+use anyhow::{anyhow, Result};
+use ethers::core::types::{Address, H256, U256};
+use numeraire::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// An offer that was (partially) filled by the transaction: `taker` took `pay` out of the offer, and gave `maker` `buy` for it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fill {
+    offer_id: U256,
+    maker: Address,
+    taker: Address,
+    pay: ChainNativeAsset,
+    buy: ChainNativeAsset,
+}
+
+impl Fill {
+    pub fn new(offer_id: U256, maker: Address, taker: Address, pay: ChainNativeAsset, buy: ChainNativeAsset) -> Self {
+        Self {
+            offer_id,
+            maker,
+            taker,
+            pay,
+            buy,
+        }
+    }
+
+    pub fn offer_id(&self) -> U256 {
+        self.offer_id
+    }
+
+    pub fn maker(&self) -> Address {
+        self.maker
+    }
+
+    pub fn taker(&self) -> Address {
+        self.taker
+    }
+
+    /// Returns a reference to what the maker gave up, i.e. what the taker received
+    pub fn pay(&self) -> &ChainNativeAsset {
+        &self.pay
+    }
+
+    /// Returns a reference to what the maker received, i.e. what the taker spent
+    pub fn buy(&self) -> &ChainNativeAsset {
+        &self.buy
+    }
+
+    /// The fill as a swap from the taker's point of view: they give up `buy` for `pay`.
+    pub fn to_taker_swap(&self) -> AssetSwap {
+        AssetSwap::new(self.buy.clone(), self.pay.clone())
+    }
+}
+
+/// A fee that the market charged the taker of an offer, and paid to `fee_to`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fee {
+    offer_id: U256,
+    taker: Address,
+    fee_to: Address,
+    amount: ChainNativeAsset,
+}
+
+impl Fee {
+    pub fn new(offer_id: U256, taker: Address, fee_to: Address, amount: ChainNativeAsset) -> Self {
+        Self {
+            offer_id,
+            taker,
+            fee_to,
+            amount,
+        }
+    }
+
+    pub fn offer_id(&self) -> U256 {
+        self.offer_id
+    }
+
+    pub fn taker(&self) -> Address {
+        self.taker
+    }
+
+    pub fn fee_to(&self) -> Address {
+        self.fee_to
+    }
+
+    /// Returns a reference to the size of the fee
+    pub fn amount(&self) -> &ChainNativeAsset {
+        &self.amount
+    }
+}
+
+/**
+ * What a mined transaction did on the RubiconMarket, pieced together from its receipt (see [`crate::RubiconSession::trade_outcome`]).
+ *
+ * The market emits both a `LogTake` and a `LogTrade` for every fill. The `LogTake`s become [`Fill`]s, which say which offer was taken and by whom.
+ * The `LogTrade`s become `trades`, which are the same fills as swaps from the maker's point of view, and are kept so that nothing in the receipt is dropped.
+ * Use one or the other, not both, when adding up what was traded.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeOutcome {
+    transaction_hash: H256,
+    block_number: Option<u64>,
+    success: bool,
+    made: Vec<Offer>,
+    fills: Vec<Fill>,
+    trades: Vec<AssetSwap>,
+    fees: Vec<Fee>,
+    swaps: Vec<AssetSwap>,
+    strategist_trades: Vec<U256>,
+    unresolved: Vec<Address>,
+    gas_used: U256,
+    effective_gas_price: ChainNativeAsset,
+}
+
+impl TradeOutcome {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        transaction_hash: H256,
+        block_number: Option<u64>,
+        success: bool,
+        made: Vec<Offer>,
+        fills: Vec<Fill>,
+        trades: Vec<AssetSwap>,
+        fees: Vec<Fee>,
+        swaps: Vec<AssetSwap>,
+        strategist_trades: Vec<U256>,
+        unresolved: Vec<Address>,
+        gas_used: U256,
+        effective_gas_price: ChainNativeAsset,
+    ) -> Self {
+        Self {
+            transaction_hash,
+            block_number,
+            success,
+            made,
+            fills,
+            trades,
+            fees,
+            swaps,
+            strategist_trades,
+            unresolved,
+            gas_used,
+            effective_gas_price,
+        }
+    }
+
+    pub fn transaction_hash(&self) -> H256 {
+        self.transaction_hash
+    }
+
+    pub fn block_number(&self) -> Option<u64> {
+        self.block_number
+    }
+
+    /// False if the transaction reverted, in which case it did nothing on the market but still paid for gas
+    pub fn success(&self) -> bool {
+        self.success
+    }
+
+    /// The offers that the transaction placed on the book, as they were when they were made.
+    /// An offer that was made and then filled in the same transaction appears here and in [`TradeOutcome::fills`].
+    pub fn made(&self) -> &[Offer] {
+        &self.made
+    }
+
+    /// The ids of the offers that the transaction placed on the book, in the order they were made
+    pub fn offer_ids(&self) -> Vec<U256> {
+        self.made.iter().map(|x| x.id()).collect()
+    }
+
+    /// The offers that the transaction filled, in the order they were filled
+    pub fn fills(&self) -> &[Fill] {
+        &self.fills
+    }
+
+    /// The fills as the market's `LogTrade`s report them, i.e. from the maker's point of view
+    pub fn trades(&self) -> &[AssetSwap] {
+        &self.trades
+    }
+
+    /// The fees that the market charged, in the order they were charged
+    pub fn fees(&self) -> &[Fee] {
+        &self.fees
+    }
+
+    /// The router swaps that the transaction made, each as what went in for what came out (the `LogSwap`'s realized fill)
+    pub fn swaps(&self) -> &[AssetSwap] {
+        &self.swaps
+    }
+
+    /// The ids of the strategist trades that the BathPair (or MarketAid) recorded, e.g. for `batch_place_market_making_trades`
+    pub fn strategist_trades(&self) -> &[U256] {
+        &self.strategist_trades
+    }
+
+    /// The tokens in the receipt that the session's registry doesn't know, so the events involving them were left out.
+    /// Empty unless the outcome is partial; register the tokens and call [`crate::RubiconSession::trade_outcome`] again to get the rest.
+    pub fn unresolved_tokens(&self) -> &[Address] {
+        &self.unresolved
+    }
+
+    /// How much gas the transaction used
+    pub fn gas_used(&self) -> U256 {
+        self.gas_used
+    }
+
+    /// What the transaction paid for each unit of gas, in the chain's native asset
+    pub fn effective_gas_price(&self) -> &ChainNativeAsset {
+        &self.effective_gas_price
+    }
+
+    /// What the transaction paid for gas in total, in the chain's native asset
+    pub fn gas_cost(&self) -> Result<ChainNativeAsset> {
+        let cost = self
+            .gas_used
+            .checked_mul(*self.effective_gas_price.size())
+            .ok_or_else(|| anyhow!("[gas_cost]: gas used times gas price overflows a U256"))?;
+        ChainNativeAsset::new(*self.effective_gas_price.chain(), *self.effective_gas_price.asset(), cost)
+    }
+}
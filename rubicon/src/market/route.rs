@@ -0,0 +1,138 @@
+use super::TradeOutcome;
+use anyhow::{anyhow, Result};
+use ethers::core::types::{Address, Chain, U256};
+use numeraire::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A path through the market's books that the RubiconRouter can swap along, and what it's expected to fill.
+/// Found and ranked by [`crate::RubiconSession::find_swap_routes`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapRoute {
+    path: Vec<Asset>,
+    pay: ChainNativeAsset,
+    expected_fill: ChainNativeAsset,
+    fee_bps: U256,
+}
+
+impl SwapRoute {
+    pub fn new(path: Vec<Asset>, pay: ChainNativeAsset, expected_fill: ChainNativeAsset, fee_bps: U256) -> Self {
+        Self {
+            path,
+            pay,
+            expected_fill,
+            fee_bps,
+        }
+    }
+
+    /// The assets the swap goes through, starting with what's paid and ending with what's bought
+    pub fn path(&self) -> &[Asset] {
+        &self.path
+    }
+
+    /// How many books the swap crosses
+    pub fn hops(&self) -> usize {
+        self.path.len().saturating_sub(1)
+    }
+
+    /// Returns a reference to what the swap pays in
+    pub fn pay(&self) -> &ChainNativeAsset {
+        &self.pay
+    }
+
+    /// Returns a reference to what the router expected the swap to fill, as of the quote
+    pub fn expected_fill(&self) -> &ChainNativeAsset {
+        &self.expected_fill
+    }
+
+    /// The market fee the quote was made with, in basis points
+    pub fn fee_bps(&self) -> U256 {
+        self.fee_bps
+    }
+
+    /// The path as token addresses, as the router takes it
+    pub fn addresses(&self, chain: &Chain) -> Result<Vec<Address>> {
+        self.path.iter().map(|x| x.to_address(chain)).collect()
+    }
+
+    /// The swap we expect: `pay` for `expected_fill`.
+    pub fn to_swap(&self) -> AssetSwap {
+        AssetSwap::new(self.pay.clone(), self.expected_fill.clone())
+    }
+
+    /// What the router's `LogSwap` in `outcome` says the swap along this route filled, to compare with [`SwapRoute::expected_fill`].
+    /// Fails if `outcome` has no swap of `pay` for what the route buys, e.g. because the transaction reverted.
+    pub fn realized_fill(&self, outcome: &TradeOutcome) -> Result<ChainNativeAsset> {
+        outcome
+            .swaps()
+            .iter()
+            .find(|x| x.source() == &self.pay && x.target().asset() == self.expected_fill.asset())
+            .map(|x| x.target().clone())
+            .ok_or(anyhow!(
+                "[SwapRoute::realized_fill]: transaction {:?} didn't swap {} for {}",
+                outcome.transaction_hash(),
+                self.pay,
+                self.expected_fill.asset()
+            ))
+    }
+
+    /// How much less than [`SwapRoute::expected_fill`] the swap in `outcome` filled, or nothing if it filled at least that much.
+    pub fn shortfall(&self, outcome: &TradeOutcome) -> Result<ChainNativeAsset> {
+        let realized = self.realized_fill(outcome)?;
+        match self.expected_fill.try_cmp(&realized)? {
+            std::cmp::Ordering::Greater => self.expected_fill.checked_sub(&realized),
+            _ => ChainNativeAsset::new_zero(*realized.chain(), *realized.asset()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::types::H256;
+
+    const CHAIN: Chain = Chain::Optimism;
+
+    fn usdc(wei: u64) -> ChainNativeAsset {
+        ChainNativeAsset::new(CHAIN, Asset::Usdc, U256::from(wei)).unwrap()
+    }
+
+    fn dai(wei: u64) -> ChainNativeAsset {
+        ChainNativeAsset::new(CHAIN, Asset::Dai, U256::from(wei)).unwrap()
+    }
+
+    fn outcome(swaps: Vec<AssetSwap>) -> TradeOutcome {
+        TradeOutcome::new(
+            H256::repeat_byte(1),
+            Some(1),
+            true,
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            swaps,
+            vec![],
+            vec![],
+            U256::from(21_000),
+            ChainNativeAsset::new(CHAIN, Asset::Eth, U256::one()).unwrap(),
+        )
+    }
+
+    #[test]
+    fn the_realized_fill_comes_from_the_matching_log_swap() {
+        let route = SwapRoute::new(vec![Asset::Usdc, Asset::Weth, Asset::Dai], usdc(1_000), dai(990), U256::from(20));
+        // another swap in the same transaction, and one that paid a different amount, don't count
+        let swaps = vec![
+            AssetSwap::new(dai(5), usdc(5)),
+            AssetSwap::new(usdc(2_000), dai(1_980)),
+            AssetSwap::new(usdc(1_000), dai(985)),
+        ];
+        assert_eq!(route.realized_fill(&outcome(swaps.clone())).unwrap(), dai(985));
+        assert_eq!(route.shortfall(&outcome(swaps)).unwrap(), dai(5));
+
+        // filling more than expected isn't a shortfall
+        let better = vec![AssetSwap::new(usdc(1_000), dai(995))];
+        assert_eq!(route.shortfall(&outcome(better)).unwrap(), dai(0));
+
+        assert!(route.realized_fill(&outcome(vec![])).is_err());
+    }
+}
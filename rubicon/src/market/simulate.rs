@@ -0,0 +1,367 @@
+use super::Offer;
+use anyhow::{anyhow, Result};
+use ethers::core::types::U256;
+use numeraire::prelude::*;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+/**
+ * What a market order would do to a snapshot of one side of the book, worked out locally.
+ *
+ * The book is walked the way the market's `getBuyAmount`/`getPayAmount` walk it, with the same DS-math rounding on the last,
+ * partially filled offer, so for the same offers the fill is the same to the wei. The market's fee is charged on top, once per offer taken:
+ * on what its `buy` spends on that offer, rounded down, as in [`crate::RubiconSession::take_offer`]. That's what the market charges, so
+ * across many offers the fee can come to a few wei less than the fee on the total.
+ *
+ * The offers must all sell the same asset for the same asset, sorted best first (see [`crate::RubiconSession::sorted_offers`]
+ * or [`super::LiveOrderBook::sorted_offers`]).
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteSimulation {
+    swap: AssetSwap,
+    fee: ChainNativeAsset,
+    average_price: Price,
+    best_price: Price,
+    worst_price: Price,
+    offers_crossed: usize,
+    price_impact: Decimal,
+}
+
+impl QuoteSimulation {
+    /// Simulates selling `pay` into `offers` (i.e. `sellAllAmount`), where the offers sell what we're buying for `pay.asset()`.
+    /// Prices are quoted on `pair`, which must be made of the two assets. Fails if the offers can't absorb all of `pay`.
+    pub fn sell_all_amount(offers: &[Offer], pay: &ChainNativeAsset, fee_bps: U256, pair: &Pair) -> Result<Self> {
+        let (fill, taken) = walk(offers, pay, Side::Sell, "QuoteSimulation::sell_all_amount")?;
+        let fill = ChainNativeAsset::new(*pay.chain(), *offers[0].pay().asset(), fill)?;
+        Self::new(offers, &taken, pay.clone(), fill, fee_bps, pair)
+    }
+
+    /// Simulates buying `buy` out of `offers` (i.e. `buyAllAmount`), where the offers sell `buy.asset()` for what we're paying with.
+    /// Prices are quoted on `pair`, which must be made of the two assets. Fails if the offers don't have enough of `buy`.
+    pub fn buy_all_amount(offers: &[Offer], buy: &ChainNativeAsset, fee_bps: U256, pair: &Pair) -> Result<Self> {
+        let (fill, taken) = walk(offers, buy, Side::Buy, "QuoteSimulation::buy_all_amount")?;
+        let fill = ChainNativeAsset::new(*buy.chain(), *offers[0].buy().asset(), fill)?;
+        Self::new(offers, &taken, fill, buy.clone(), fee_bps, pair)
+    }
+
+    // `paid` is what went into the book, before the fee, and `taken` is how much was bought out of each offer crossed
+    fn new(
+        offers: &[Offer],
+        taken: &[U256],
+        paid: ChainNativeAsset,
+        received: ChainNativeAsset,
+        fee_bps: U256,
+        pair: &Pair,
+    ) -> Result<Self> {
+        let crossed = taken.len();
+        let mut fee = U256::zero();
+        for (offer, quantity) in offers.iter().zip(taken) {
+            // the market's `buy` spends `quantity * buy_amt / pay_amt` on the offer, and charges its fee on that
+            let offer_fee = quantity
+                .checked_mul(*offer.buy().size())
+                .and_then(|x| x.checked_div(*offer.pay().size()))
+                .and_then(|spend| spend.checked_mul(fee_bps))
+                .map(|x| x / U256::from(10_000))
+                .and_then(|x| fee.checked_add(x))
+                .ok_or(anyhow!("[QuoteSimulation]: a fee of {} bps on offer {} overflows", fee_bps, offer.id()))?;
+            fee = offer_fee;
+        }
+        let fee = ChainNativeAsset::new(*paid.chain(), *paid.asset(), fee)?;
+        let before_fee = AssetSwap::new(paid.clone(), received.clone());
+        let swap = AssetSwap::new(paid.checked_add(&fee)?, received);
+        let best_price = offers[0].price(pair)?;
+        let average = before_fee.implied_price(pair)?.value();
+        let price_impact = ((average - best_price.value()) / best_price.value()).abs();
+        Ok(Self {
+            average_price: swap.implied_price(pair)?,
+            best_price,
+            worst_price: offers[crossed - 1].price(pair)?,
+            offers_crossed: crossed,
+            price_impact,
+            swap,
+            fee,
+        })
+    }
+
+    /// Returns a reference to the whole trade from our point of view: what we'd pay, fee included, for what we'd get.
+    pub fn swap(&self) -> &AssetSwap {
+        &self.swap
+    }
+
+    /// Returns a reference to the market's fee, in the asset we'd pay with
+    pub fn fee(&self) -> &ChainNativeAsset {
+        &self.fee
+    }
+
+    /// Returns a reference to the price we'd get overall, fee included
+    pub fn average_price(&self) -> &Price {
+        &self.average_price
+    }
+
+    /// Returns a reference to the price of the best offer
+    pub fn best_price(&self) -> &Price {
+        &self.best_price
+    }
+
+    /// Returns a reference to the price of the worst offer we'd reach, i.e. the book's price after the trade
+    pub fn worst_price(&self) -> &Price {
+        &self.worst_price
+    }
+
+    /// How many offers we'd take from, counting the last one that's only partially filled
+    pub fn offers_crossed(&self) -> usize {
+        self.offers_crossed
+    }
+
+    /// How far the average price (before the fee) is from the best offer's, as a fraction of the best offer's, e.g. `0.01` for 1%
+    pub fn price_impact(&self) -> Decimal {
+        self.price_impact
+    }
+}
+
+// DS-math's ray
+const RAY: u128 = 1_000_000_000_000_000_000_000_000_000;
+
+// walks the offers like the market does, returning the fill and how much of what each offer sells we'd take, for every offer it reached.
+// Selling, `amount` goes into the offers' buy side and the fill comes out of their pay side (`getBuyAmount`); buying, it's the other way around (`getPayAmount`).
+fn walk(offers: &[Offer], amount: &ChainNativeAsset, side: Side, func: &str) -> Result<(U256, Vec<U256>)> {
+    let mut remaining = *amount.size();
+    if remaining.is_zero() {
+        return Err(anyhow!("[{}]: there's nothing to trade", func));
+    }
+    let mut fill = U256::zero();
+    let mut taken = Vec::new();
+    for offer in offers.iter() {
+        // `give` is the side of the offer that `amount` is in, `take` is the side the fill comes out of
+        let (give, take) = match side {
+            Side::Sell => (offer.buy(), offer.pay()),
+            Side::Buy => (offer.pay(), offer.buy()),
+        };
+        if give.asset() != amount.asset() {
+            return Err(anyhow!("[{}]: offer {} doesn't trade {}", func, offer.id(), amount.asset()));
+        }
+        if remaining > *give.size() {
+            fill = fill
+                .checked_add(*take.size())
+                .ok_or(anyhow!("[{}]: the fill overflows", func))?;
+            remaining -= *give.size();
+            taken.push(*offer.pay().size());
+            continue;
+        }
+        // the last offer is filled in proportion, with the market's rounding
+        let partial = remaining
+            .checked_mul(U256::exp10(9))
+            .and_then(|x| rmul(x, rdiv(*take.size(), *give.size())?))
+            .map(|x| x / U256::exp10(9))
+            .ok_or(anyhow!("[{}]: offer {} overflows, the market would revert", func, offer.id()))?;
+        fill = fill
+            .checked_add(partial)
+            .ok_or(anyhow!("[{}]: the fill overflows", func))?;
+        if fill.is_zero() {
+            return Err(anyhow!("[{}]: {} is too little to fill anything, so the market won't take it", func, amount));
+        }
+        taken.push(match side {
+            Side::Sell => partial,
+            Side::Buy => remaining,
+        });
+        return Ok((fill, taken));
+    }
+    Err(anyhow!("[{}]: the book doesn't have enough offers to trade {}", func, amount))
+}
+
+fn rmul(x: U256, y: U256) -> Option<U256> {
+    x.checked_mul(y)?.checked_add(U256::from(RAY / 2)).map(|x| x / U256::from(RAY))
+}
+
+fn rdiv(x: U256, y: U256) -> Option<U256> {
+    if y.is_zero() {
+        return None;
+    }
+    x.checked_mul(U256::from(RAY))?.checked_add(y / 2).map(|x| x / y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::types::Address;
+
+    const CHAIN: Chain = Chain::Optimism;
+
+    fn weth(x: u128) -> ChainNativeAsset {
+        ChainNativeAsset::new(CHAIN, Asset::Weth, U256::from(x)).unwrap()
+    }
+
+    fn usdc(x: u128) -> ChainNativeAsset {
+        ChainNativeAsset::new(CHAIN, Asset::Usdc, U256::from(x)).unwrap()
+    }
+
+    fn pair() -> Pair {
+        Pair::new(Asset::Weth, Asset::Usdc)
+    }
+
+    // asks selling WETH for USDC, as (pay_amt, buy_amt) in wei, best first
+    fn asks(terms: &[(u128, u128)]) -> Vec<Offer> {
+        terms
+            .iter()
+            .enumerate()
+            .map(|(i, (pay, buy))| Offer::new(U256::from(i + 1), Address::zero(), weth(*pay), usdc(*buy), 0))
+            .collect()
+    }
+
+    /*
+     * The market's view functions, transliterated from RubiconMarket.sol over `(pay_amt, buy_amt)` offers, with the offer list
+     * standing in for `getBestOffer`/`getWorseOffer`. `None` is a revert.
+     */
+
+    fn sol_rmul(x: U256, y: U256) -> Option<U256> {
+        Some((x.checked_mul(y)? + U256::from(RAY) / 2) / U256::from(RAY))
+    }
+
+    fn sol_rdiv(x: U256, y: U256) -> Option<U256> {
+        (x.checked_mul(U256::from(RAY))? + y / 2).checked_div(y)
+    }
+
+    fn get_buy_amount(offers: &[(u128, u128)], mut pay_amt: U256) -> Option<U256> {
+        let offer = |id: usize| offers.get(id).map_or((U256::zero(), U256::zero()), |(p, b)| (U256::from(*p), U256::from(*b)));
+        let mut offer_id = 0;
+        let mut fill_amt = U256::zero();
+        while pay_amt > offer(offer_id).1 {
+            fill_amt += offer(offer_id).0;
+            pay_amt -= offer(offer_id).1;
+            if pay_amt > U256::zero() {
+                offer_id += 1;
+                if offer_id >= offers.len() {
+                    return None;
+                }
+            }
+        }
+        let (pay, buy) = offer(offer_id);
+        Some(fill_amt + sol_rmul(pay_amt * U256::exp10(9), sol_rdiv(pay, buy)?)? / U256::exp10(9))
+    }
+
+    fn get_pay_amount(offers: &[(u128, u128)], mut buy_amt: U256) -> Option<U256> {
+        let offer = |id: usize| offers.get(id).map_or((U256::zero(), U256::zero()), |(p, b)| (U256::from(*p), U256::from(*b)));
+        let mut offer_id = 0;
+        let mut fill_amt = U256::zero();
+        while buy_amt > offer(offer_id).0 {
+            fill_amt += offer(offer_id).1;
+            buy_amt -= offer(offer_id).0;
+            if buy_amt > U256::zero() {
+                offer_id += 1;
+                if offer_id >= offers.len() {
+                    return None;
+                }
+            }
+        }
+        let (pay, buy) = offer(offer_id);
+        Some(fill_amt + sol_rmul(buy_amt * U256::exp10(9), sol_rdiv(buy, pay)?)? / U256::exp10(9))
+    }
+
+    // 1 WETH at 1500, 2 WETH at 1510, 0.333.. WETH at 1523.17.., in wei
+    const BOOK: [(u128, u128); 3] = [
+        (1_000_000_000_000_000_000, 1_500_000_000),
+        (2_000_000_000_000_000_000, 3_020_000_000),
+        (333_333_333_333_333_333, 507_723_456),
+    ];
+
+    #[test]
+    fn sells_match_get_buy_amount() {
+        let offers = asks(&BOOK);
+        for pay in [1, 999_999, 1_500_000_000, 1_500_000_001, 2_000_000_000, 4_520_000_000, 5_000_000_000, 5_027_723_456] {
+            let expected = get_buy_amount(&BOOK, U256::from(pay)).unwrap();
+            let sim = QuoteSimulation::sell_all_amount(&offers, &usdc(pay), U256::zero(), &pair());
+            match expected.is_zero() {
+                true => assert!(sim.is_err(), "selling {} USDC wei fills nothing", pay),
+                false => assert_eq!(*sim.unwrap().swap().target().size(), expected, "selling {} USDC wei", pay),
+            }
+        }
+    }
+
+    #[test]
+    fn buys_match_get_pay_amount() {
+        let offers = asks(&BOOK);
+        for buy in [
+            1,
+            123_456_789,
+            1_000_000_000_000_000_000,
+            1_000_000_000_000_000_001,
+            2_500_000_000_000_000_000,
+            3_000_000_000_000_000_000,
+            3_333_333_333_333_333_333,
+        ] {
+            let expected = get_pay_amount(&BOOK, U256::from(buy)).unwrap();
+            let sim = QuoteSimulation::buy_all_amount(&offers, &weth(buy), U256::zero(), &pair());
+            match expected.is_zero() {
+                true => assert!(sim.is_err(), "buying {} WETH wei costs nothing", buy),
+                false => assert_eq!(*sim.unwrap().swap().source().size(), expected, "buying {} WETH wei", buy),
+            }
+        }
+    }
+
+    #[test]
+    fn walks_the_book() {
+        let offers = asks(&BOOK);
+        // all of the first offer and half of the second
+        let sim = QuoteSimulation::buy_all_amount(&offers, &weth(2_000_000_000_000_000_000), U256::zero(), &pair()).unwrap();
+        assert_eq!(*sim.swap().source().size(), U256::from(3_010_000_000u64));
+        assert_eq!(sim.offers_crossed(), 2);
+        assert_eq!(sim.best_price().value(), Decimal::from(1500));
+        assert_eq!(sim.worst_price().value(), Decimal::from(1510));
+        assert_eq!(sim.average_price().value(), Decimal::from(1505));
+        assert_eq!(sim.price_impact(), Decimal::ONE / Decimal::from(300));
+
+        // inside the first offer there's no impact
+        let sim = QuoteSimulation::sell_all_amount(&offers, &usdc(750_000_000), U256::zero(), &pair()).unwrap();
+        assert_eq!(*sim.swap().target().size(), U256::from(500_000_000_000_000_000u128));
+        assert_eq!(sim.offers_crossed(), 1);
+        assert_eq!(sim.price_impact(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn charges_the_fee_on_top() {
+        let offers = asks(&BOOK);
+        let sim = QuoteSimulation::buy_all_amount(&offers, &weth(1_000_000_000_000_000_000), U256::from(10), &pair()).unwrap();
+        assert_eq!(*sim.fee(), usdc(1_500_000));
+        assert_eq!(*sim.swap().source(), usdc(1_501_500_000));
+        assert_eq!(*sim.swap().target(), weth(1_000_000_000_000_000_000));
+        assert_eq!(sim.average_price().value(), Decimal::new(15015, 1));
+        // the impact is the book's, not the fee's
+        assert_eq!(sim.price_impact(), Decimal::ZERO);
+
+        let sim = QuoteSimulation::sell_all_amount(&offers, &usdc(1_500_000_000), U256::from(10), &pair()).unwrap();
+        assert_eq!(*sim.swap().source(), usdc(1_501_500_000));
+        assert_eq!(*sim.swap().target(), weth(1_000_000_000_000_000_000));
+    }
+
+    #[test]
+    fn charges_the_fee_per_offer_taken() {
+        // 10 bps of each offer is a fraction of a wei short of a whole number, which the market drops once per offer
+        let offers = asks(&[(1_000_000_000_000_000_000, 1_500_000_999), (1_000_000_000_000_000, 999)]);
+        let sim = QuoteSimulation::buy_all_amount(&offers, &weth(1_001_000_000_000_000_000), U256::from(10), &pair()).unwrap();
+        assert_eq!(*sim.fee(), usdc(1_500_000));
+        assert_eq!(*sim.swap().source(), usdc(1_500_001_998 + 1_500_000));
+        // 10 bps of the total would be 1_500_001
+        let sim = QuoteSimulation::sell_all_amount(&offers, &usdc(1_500_001_998), U256::from(10), &pair()).unwrap();
+        assert_eq!(*sim.fee(), usdc(1_500_000));
+
+        // half of the second offer spends 499 (not 499.5) on it, and pays no fee on that
+        let sim = QuoteSimulation::buy_all_amount(&offers, &weth(1_000_500_000_000_000_000), U256::from(10), &pair()).unwrap();
+        assert_eq!(sim.offers_crossed(), 2);
+        assert_eq!(*sim.fee(), usdc(1_500_000));
+        // and at 100% the fee is exactly what's spent on each offer, rounded down
+        let sim = QuoteSimulation::buy_all_amount(&offers, &weth(1_000_500_000_000_000_000), U256::from(10_000), &pair()).unwrap();
+        assert_eq!(*sim.fee(), usdc(1_500_000_999 + 499));
+    }
+
+    #[test]
+    fn refuses_what_the_market_would_revert() {
+        let offers = asks(&BOOK);
+        assert!(get_buy_amount(&BOOK, U256::from(5_027_723_457u64)).is_none());
+        assert!(QuoteSimulation::sell_all_amount(&offers, &usdc(5_027_723_457), U256::zero(), &pair()).is_err());
+        assert!(get_pay_amount(&BOOK, U256::from(3_333_333_333_333_333_334u128)).is_none());
+        assert!(QuoteSimulation::buy_all_amount(&offers, &weth(3_333_333_333_333_333_334), U256::zero(), &pair()).is_err());
+        assert!(QuoteSimulation::buy_all_amount(&[], &weth(1), U256::zero(), &pair()).is_err());
+        // the offers sell WETH, so they can't be sold WETH
+        assert!(QuoteSimulation::sell_all_amount(&offers, &weth(1), U256::zero(), &pair()).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::{anyhow, Result};
+use ethers::core::types::U256;
+use numeraire::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/**
+ * How far a market order may stray from what the book quoted for it, before it should revert.
+ *
+ * A [`Slippage::Bps`] tolerance is measured from the quote itself, so it guards against the book moving (or being sandwiched)
+ * between the quote and the order being mined. A [`Slippage::Limit`] is the worst price we'll trade at, whatever the quote was,
+ * so it also refuses a quote that's already too thin.
+ */
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Slippage {
+    /// A tolerance in basis points (1/100th of a percent) of the quote
+    Bps(u32),
+    /// The worst price to accept. Its pair must be made of the two assets being traded, in either order.
+    Limit(Price),
+}
+
+impl Slippage {
+    /// The least we should accept for selling `pay`, given that the book quoted `quote` for it.
+    pub fn min_fill(&self, pay: &ChainNativeAsset, quote: &ChainNativeAsset) -> Result<ChainNativeAsset> {
+        match self {
+            Slippage::Bps(bps) => {
+                if *bps > 10_000 {
+                    return Err(anyhow!("[Slippage::min_fill]: a tolerance of {} bps is more than 100%", bps));
+                }
+                scale_bps(quote, 10_000 - *bps)
+            }
+            // selling base we want at least the limit's quote, selling quote we want at least the limit's base
+            Slippage::Limit(price) => match self.orientation(price, pay, quote)? {
+                true => price.quote_for(pay, Rounding::Up),
+                false => price.base_for(pay, Rounding::Up),
+            },
+        }
+    }
+
+    /// The most we should pay for buying `buy`, given that the book quoted `quote` for it.
+    pub fn max_fill(&self, buy: &ChainNativeAsset, quote: &ChainNativeAsset) -> Result<ChainNativeAsset> {
+        match self {
+            Slippage::Bps(bps) => scale_bps(
+                quote,
+                10_000_u32
+                    .checked_add(*bps)
+                    .ok_or(anyhow!("[Slippage::max_fill]: a tolerance of {} bps is too large", bps))?,
+            ),
+            // buying base we pay at most the limit's quote, buying quote we pay at most the limit's base
+            Slippage::Limit(price) => match self.orientation(price, buy, quote)? {
+                true => price.quote_for(buy, Rounding::Down),
+                false => price.base_for(buy, Rounding::Down),
+            },
+        }
+    }
+
+    // true if `size` is the base of the limit's pair and `other` its quote, false if it's the other way around
+    fn orientation(&self, price: &Price, size: &ChainNativeAsset, other: &ChainNativeAsset) -> Result<bool> {
+        if price.base() == size.asset() && price.quote() == other.asset() {
+            Ok(true)
+        } else if price.quote() == size.asset() && price.base() == other.asset() {
+            Ok(false)
+        } else {
+            Err(anyhow!(
+                "[Slippage]: a limit of {} can't bound a trade between {} and {}",
+                price,
+                size.asset(),
+                other.asset()
+            ))
+        }
+    }
+}
+
+impl std::fmt::Display for Slippage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Slippage::Bps(bps) => write!(f, "a tolerance of {} bps", bps),
+            Slippage::Limit(price) => write!(f, "a limit of {}", price),
+        }
+    }
+}
+
+// `size * bps / 10_000`, rounded down
+fn scale_bps(size: &ChainNativeAsset, bps: u32) -> Result<ChainNativeAsset> {
+    let scaled = size
+        .size()
+        .checked_mul(U256::from(bps))
+        .map(|x| x / U256::from(10_000))
+        .ok_or(anyhow!("[Slippage]: {} times {} bps overflows", size, bps))?;
+    ChainNativeAsset::new(*size.chain(), *size.asset(), scaled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::types::Chain;
+    use rust_decimal::Decimal;
+
+    const CHAIN: Chain = Chain::Optimism;
+
+    fn weth(wei: U256) -> ChainNativeAsset {
+        ChainNativeAsset::new(CHAIN, Asset::Weth, wei).unwrap()
+    }
+
+    fn usdc(wei: u64) -> ChainNativeAsset {
+        ChainNativeAsset::new(CHAIN, Asset::Usdc, U256::from(wei)).unwrap()
+    }
+
+    fn limit(value: Decimal) -> Slippage {
+        Slippage::Limit(Price::new(Pair::new(Asset::Weth, Asset::Usdc), value).unwrap())
+    }
+
+    #[test]
+    fn a_bps_tolerance_must_be_in_bounds() {
+        let (pay, quote) = (weth(U256::exp10(18)), usdc(1_600_000_000));
+        assert_eq!(Slippage::Bps(0).min_fill(&pay, &quote).unwrap(), quote);
+        assert_eq!(Slippage::Bps(10_000).min_fill(&pay, &quote).unwrap(), usdc(0));
+        assert!(Slippage::Bps(10_001).min_fill(&pay, &quote).is_err());
+
+        assert_eq!(Slippage::Bps(0).max_fill(&pay, &quote).unwrap(), quote);
+        assert_eq!(Slippage::Bps(10_000).max_fill(&pay, &quote).unwrap(), usdc(3_200_000_000));
+        assert!(Slippage::Bps(u32::MAX - 9_999).max_fill(&pay, &quote).is_err());
+        assert!(Slippage::Bps(u32::MAX).max_fill(&pay, &quote).is_err());
+        // in range for the addition, but too much for the quote
+        let huge = ChainNativeAsset::new(CHAIN, Asset::Usdc, U256::MAX).unwrap();
+        assert!(Slippage::Bps(1).max_fill(&pay, &huge).is_err());
+    }
+
+    #[test]
+    fn a_bps_tolerance_rounds_down() {
+        let pay = weth(U256::exp10(18));
+        // 999 * 0.995 = 994.005 and 999 * 1.005 = 1003.995
+        assert_eq!(Slippage::Bps(50).min_fill(&pay, &usdc(999)).unwrap(), usdc(994));
+        assert_eq!(Slippage::Bps(50).max_fill(&pay, &usdc(999)).unwrap(), usdc(1003));
+    }
+
+    #[test]
+    fn a_limit_bounds_either_side_of_its_pair() {
+        let one_weth = weth(U256::exp10(18));
+        let third = weth(U256::from(333_333_333_333_333_333_u64));
+        let slippage = limit(Decimal::new(3, 0));
+
+        // selling base, we want at least the limit's quote
+        assert_eq!(slippage.min_fill(&one_weth, &usdc(5_000_000)).unwrap(), usdc(3_000_000));
+        // selling quote, we want at least the limit's base, rounded up
+        assert_eq!(slippage.min_fill(&usdc(1_000_000), &one_weth).unwrap(), weth(*third.size() + 1));
+        // buying base, we pay at most the limit's quote
+        assert_eq!(slippage.max_fill(&one_weth, &usdc(1_000_000)).unwrap(), usdc(3_000_000));
+        // buying quote, we pay at most the limit's base, rounded down
+        assert_eq!(slippage.max_fill(&usdc(1_000_000), &one_weth).unwrap(), third);
+
+        // the same limit quoted the other way around bounds the same trades
+        let inverse = Slippage::Limit(Price::new(Pair::new(Asset::Usdc, Asset::Weth), Decimal::new(5, 1)).unwrap());
+        assert_eq!(inverse.min_fill(&one_weth, &usdc(5_000_000)).unwrap(), usdc(2_000_000));
+        assert_eq!(inverse.max_fill(&usdc(1_000_000), &one_weth).unwrap(), weth(U256::exp10(18) / 2));
+    }
+
+    #[test]
+    fn a_limit_on_another_pair_is_an_error() {
+        let dai = ChainNativeAsset::new(CHAIN, Asset::Dai, U256::exp10(18)).unwrap();
+        let slippage = limit(Decimal::ONE);
+        assert!(slippage.min_fill(&dai, &usdc(1)).is_err());
+        assert!(slippage.max_fill(&usdc(1), &dai).is_err());
+        // both assets have to match, not just one
+        assert!(slippage.min_fill(&weth(U256::one()), &weth(U256::one())).is_err());
+    }
+}
@@ -0,0 +1,326 @@
+use super::{backoff, RubiconSession};
+use crate::events::{BackfillEvent, LoggedEvent};
+use anyhow::{anyhow, Result};
+use ethers::{core::types::Filter, providers::Middleware};
+use futures_timer::Delay;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, instrument, warn};
+
+/// How many blocks a backfill asks for in one `eth_getLogs` request, until the provider tells us that's too many.
+pub const DEFAULT_BACKFILL_CHUNK: u64 = 10_000;
+
+/// How many times in a row we retry a failed `eth_getLogs` request before giving up on a page.
+pub const BACKFILL_RETRIES: usize = 3;
+
+/// How long we wait before the first retry of a failed `eth_getLogs` request. The wait doubles with every retry after that.
+pub const BACKFILL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The longest we wait between two retries of a failed `eth_getLogs` request.
+pub const BACKFILL_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/**
+ * How far a backfill has got. The checkpoint only moves forward once a page of events has been fetched and decoded,
+ * so if a backfill is interrupted (or gives up), it can be resumed from the checkpoint without missing or repeating anything.
+ * Persist it alongside the events it's produced.
+ *
+ * The checkpoint also remembers how many blocks the provider is happy to serve at once, so a resumed backfill doesn't
+ * have to rediscover that. A checkpoint that asks for no blocks at a time can't make progress, so it fails to load.
+ */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "BackfillCheckpointRepr", into = "BackfillCheckpointRepr")]
+pub struct BackfillCheckpoint {
+    next_block: u64,
+    to_block: u64,
+    chunk_size: u64,
+    max_chunk_size: u64,
+    // set once the last block has been backfilled, for when there's no block after it to point at (i.e. `to_block` is `u64::MAX`)
+    done: bool,
+}
+
+impl BackfillCheckpoint {
+    /// A checkpoint for a backfill of every block from `from_block` to `to_block`, inclusive.
+    pub fn new(from_block: u64, to_block: u64) -> Self {
+        Self {
+            next_block: from_block,
+            to_block,
+            chunk_size: DEFAULT_BACKFILL_CHUNK,
+            max_chunk_size: DEFAULT_BACKFILL_CHUNK,
+            done: false,
+        }
+    }
+
+    /// Sets the most blocks to ask for in one request.
+    pub fn with_chunk_size(mut self, chunk_size: u64) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self.max_chunk_size = self.chunk_size;
+        self
+    }
+
+    /// The first block that hasn't been backfilled yet
+    pub fn next_block(&self) -> u64 {
+        self.next_block
+    }
+
+    /// The last block to backfill
+    pub fn to_block(&self) -> u64 {
+        self.to_block
+    }
+
+    /// How many blocks the next request will ask for
+    pub fn chunk_size(&self) -> u64 {
+        self.chunk_size
+    }
+
+    /// True once every block has been backfilled
+    pub fn is_done(&self) -> bool {
+        self.done || self.next_block > self.to_block
+    }
+}
+
+/*
+ * Backfilling pulls historical events out of `eth_getLogs`, a page at a time.
+ *
+ * Providers limit how much one `eth_getLogs` request can cover, either in blocks or in results, and the limits vary.
+ * When a request is rejected for being too big, we halve the range and try again. After a successful request the range
+ * grows back, up to the checkpoint's chunk size. Any other failure is retried up to `BACKFILL_RETRIES` times, with an exponential backoff.
+ */
+impl<M: Middleware + Clone + 'static> RubiconSession<M> {
+    /// Fetches every [`BackfillEvent`] from `from_block` to `to_block`, inclusive, in the order they happened.
+    /// To backfill a large range incrementally, or to be able to resume after a failure, use [`RubiconSession::backfill_page`].
+    #[instrument(level = "debug", skip(self))]
+    pub async fn backfill(&self, from_block: u64, to_block: u64) -> Result<Vec<LoggedEvent<BackfillEvent>>> {
+        let mut checkpoint = BackfillCheckpoint::new(from_block, to_block);
+        let mut events = Vec::new();
+        while !checkpoint.is_done() {
+            events.extend(self.backfill_page(&mut checkpoint).await?);
+        }
+        Ok(events)
+    }
+
+    /// Fetches the next page of events after `checkpoint`, in the order they happened, and moves the checkpoint past them.
+    /// Returns nothing once the checkpoint is done. On failure, the checkpoint is left where it was (bar a smaller chunk size).
+    /// A log that can't be decoded fails the page rather than being skipped, since skipping it would leave a hole in the history.
+    ///
+    /// ```ignore
+    /// let mut checkpoint = BackfillCheckpoint::new(from_block, to_block);
+    /// while !checkpoint.is_done() {
+    ///     let events = session.backfill_page(&mut checkpoint).await?;
+    ///     store(events, checkpoint);
+    /// }
+    /// ```
+    #[instrument(level = "debug", skip(self))]
+    pub async fn backfill_page(&self, checkpoint: &mut BackfillCheckpoint) -> Result<Vec<LoggedEvent<BackfillEvent>>> {
+        let base = Filter::new()
+            .address(vec![self.deployment().market(), self.deployment().pair()])
+            .topic0(BackfillEvent::signatures());
+        let mut failures = 0;
+        while !checkpoint.is_done() {
+            let from = checkpoint.next_block;
+            let to = from
+                .saturating_add(checkpoint.chunk_size - 1)
+                .min(checkpoint.to_block);
+            let filter = base.clone().from_block(from).to_block(to);
+            match self._internal_middleware.get_logs(&filter).await {
+                Ok(logs) => {
+                    let mut events = logs
+                        .iter()
+                        .filter(|x| !x.removed.unwrap_or(false))
+                        .map(|x| {
+                            LoggedEvent::<BackfillEvent>::from_log(x).map_err(|e| {
+                                anyhow!(
+                                    "[backfill_page]: can't decode log {:?} in block {:?} of blocks {} to {}: {}",
+                                    x.log_index,
+                                    x.block_number,
+                                    from,
+                                    to,
+                                    e
+                                )
+                            })
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    events.sort_by_key(|x| x.meta().position());
+                    match to.checked_add(1) {
+                        Some(next) => checkpoint.next_block = next,
+                        None => checkpoint.done = true,
+                    }
+                    checkpoint.chunk_size = checkpoint
+                        .chunk_size
+                        .saturating_mul(2)
+                        .min(checkpoint.max_chunk_size);
+                    return Ok(events);
+                }
+                Err(e) => {
+                    let message = e.to_string();
+                    if checkpoint.chunk_size > 1 && is_range_error(&message) {
+                        checkpoint.chunk_size /= 2;
+                        debug!(
+                            "[backfill_page]: blocks {} to {} is too much for the provider, trying {} blocks",
+                            from, to, checkpoint.chunk_size
+                        );
+                        continue;
+                    }
+                    failures += 1;
+                    if failures > BACKFILL_RETRIES {
+                        return Err(anyhow!(
+                            "[backfill_page]: giving up on blocks {} to {} after {} failed attempts: {}",
+                            from,
+                            to,
+                            failures,
+                            message
+                        ));
+                    }
+                    warn!("[backfill_page]: failed to get logs for blocks {} to {} ({}), retrying", from, to, message);
+                    Delay::new(backoff(BACKFILL_BACKOFF, BACKFILL_MAX_BACKOFF, failures)).await;
+                }
+            }
+        }
+        Ok(Vec::new())
+    }
+}
+
+// the on-disk form of a checkpoint, checked on the way in
+#[derive(Serialize, Deserialize)]
+struct BackfillCheckpointRepr {
+    next_block: u64,
+    to_block: u64,
+    chunk_size: u64,
+    max_chunk_size: u64,
+    #[serde(default)]
+    done: bool,
+}
+
+impl TryFrom<BackfillCheckpointRepr> for BackfillCheckpoint {
+    type Error = anyhow::Error;
+
+    fn try_from(repr: BackfillCheckpointRepr) -> Result<Self> {
+        if repr.chunk_size == 0 || repr.chunk_size > repr.max_chunk_size {
+            return Err(anyhow!(
+                "[BackfillCheckpoint]: the chunk size must be between 1 and the max chunk size ({}), got {}",
+                repr.max_chunk_size,
+                repr.chunk_size
+            ));
+        }
+        Ok(Self {
+            next_block: repr.next_block,
+            to_block: repr.to_block,
+            chunk_size: repr.chunk_size,
+            max_chunk_size: repr.max_chunk_size,
+            done: repr.done,
+        })
+    }
+}
+
+impl From<BackfillCheckpoint> for BackfillCheckpointRepr {
+    fn from(checkpoint: BackfillCheckpoint) -> Self {
+        Self {
+            next_block: checkpoint.next_block,
+            to_block: checkpoint.to_block,
+            chunk_size: checkpoint.chunk_size,
+            max_chunk_size: checkpoint.max_chunk_size,
+            done: checkpoint.done,
+        }
+    }
+}
+
+// what providers say when an `eth_getLogs` request covers too many blocks or returns too many logs.
+// anything else (rate limits included) is retried as is
+const RANGE_ERRORS: &[&str] = &[
+    // infura: query returned more than 10000 results
+    "query returned more than",
+    // alchemy: Log response size exceeded...
+    "log response size exceeded",
+    // quicknode: eth_getLogs and eth_newFilter are limited to a 10,000 blocks range
+    "blocks range",
+    // ankr
+    "block range is too wide",
+    // nodereal: exceed maximum block range: 5000
+    "exceed maximum block range",
+];
+
+fn is_range_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    RANGE_ERRORS.iter().any(|x| message.contains(x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::mock::mocked_session;
+    use ethers::contract::EthEvent;
+    use ethers::core::types::{Log, H256, U256, U64};
+    use futures::executor::block_on;
+
+    #[test]
+    fn a_checkpoint_round_trips_and_refuses_an_empty_chunk() {
+        let checkpoint = BackfillCheckpoint::new(100, 200).with_chunk_size(50);
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        assert_eq!(serde_json::from_str::<BackfillCheckpoint>(&json).unwrap(), checkpoint);
+        assert_eq!(BackfillCheckpoint::new(100, 200).with_chunk_size(0).chunk_size(), 1);
+
+        let empty = r#"{"next_block":100,"to_block":200,"chunk_size":0,"max_chunk_size":50}"#;
+        assert!(serde_json::from_str::<BackfillCheckpoint>(empty).is_err());
+        let oversized = r#"{"next_block":100,"to_block":200,"chunk_size":51,"max_chunk_size":50}"#;
+        assert!(serde_json::from_str::<BackfillCheckpoint>(oversized).is_err());
+        // checkpoints saved before `done` existed still load
+        let old = r#"{"next_block":100,"to_block":200,"chunk_size":50,"max_chunk_size":50}"#;
+        assert_eq!(serde_json::from_str::<BackfillCheckpoint>(old).unwrap(), checkpoint);
+    }
+
+    #[test]
+    fn a_backfill_up_to_the_last_block_there_is_finishes() {
+        let (session, mock) = mocked_session();
+        let mut checkpoint = BackfillCheckpoint::new(u64::MAX - 1, u64::MAX);
+        mock.push::<Vec<Log>, _>(vec![]).unwrap();
+        assert!(block_on(session.backfill_page(&mut checkpoint)).unwrap().is_empty());
+        assert!(checkpoint.is_done());
+        assert_eq!(checkpoint.next_block(), u64::MAX - 1);
+
+        let json = serde_json::to_string(&checkpoint).unwrap();
+        assert!(serde_json::from_str::<BackfillCheckpoint>(&json).unwrap().is_done());
+        // and a done checkpoint doesn't ask for anything more
+        assert!(block_on(session.backfill_page(&mut checkpoint)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn a_log_that_does_not_decode_fails_the_page() {
+        let (session, mock) = mocked_session();
+        let mut checkpoint = BackfillCheckpoint::new(100, 200);
+        // a LogKill with nothing in it
+        let garbled = Log {
+            address: session.deployment().market(),
+            topics: vec![crate::events::LogKill::signature()],
+            block_number: Some(U64::from(150)),
+            block_hash: Some(H256::repeat_byte(1)),
+            transaction_hash: Some(H256::repeat_byte(2)),
+            log_index: Some(U256::zero()),
+            ..Default::default()
+        };
+        mock.push::<Vec<Log>, _>(vec![garbled]).unwrap();
+        assert!(block_on(session.backfill_page(&mut checkpoint)).is_err());
+        assert_eq!(checkpoint.next_block(), 100);
+        assert!(!checkpoint.is_done());
+    }
+
+    #[test]
+    fn only_range_errors_shrink_the_chunk() {
+        for message in [
+            "(code: -32005, message: query returned more than 10000 results, data: None)",
+            "Log response size exceeded. You can make eth_getLogs requests with up to a 2K block range and no limit on the response size",
+            "eth_getLogs and eth_newFilter are limited to a 10,000 blocks range",
+            "block range is too wide",
+            "exceed maximum block range: 5000",
+        ] {
+            assert!(is_range_error(message), "{}", message);
+        }
+        for message in [
+            "(code: -32005, message: daily request count exceeded, request rate limited, data: None)",
+            "HTTP error 429 Too Many Requests",
+            "execution reverted: limit",
+            "header not found",
+            "connection reset by peer",
+        ] {
+            assert!(!is_range_error(message), "{}", message);
+        }
+    }
+}
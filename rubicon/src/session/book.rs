@@ -0,0 +1,137 @@
+use super::RubiconSession;
+use crate::market::{BookLevel, LiveOrderBook, Offer, OrderBook};
+use anyhow::{anyhow, Result};
+use ethers::{
+    core::types::{BlockId, BlockNumber},
+    providers::Middleware,
+};
+use numeraire::prelude::*;
+use tracing::instrument;
+
+impl<M: Middleware + Clone + 'static> RubiconSession<M> {
+    /// Takes a snapshot of the top `depth` levels on each side of the book for `pair`, by walking the market's sorted offers.
+    /// Every call is pinned to the latest block at the time of the call, so the two sides are consistent with each other.
+    /// Native ETH is traded as WETH, so the book for an ETH pair is quoted on the WETH pair.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn order_book(&self, pair: &Pair, depth: usize) -> Result<OrderBook> {
+        let pair = settlement_pair(pair, "order_book")?;
+        let block = self
+            ._internal_middleware
+            .get_block_number()
+            .await
+            .map_err(|e| anyhow!("[order_book]: eth_blockNumber failed: {}", e))?
+            .as_u64();
+        let pinned = Some(BlockId::Number(BlockNumber::Number(block.into())));
+
+        let (bids, asks) = futures::try_join!(
+            self.book_side(&pair, pair.quote(), pair.base(), depth, pinned),
+            self.book_side(&pair, pair.base(), pair.quote(), depth, pinned),
+        )?;
+        OrderBook::new(pair, bids, asks, Some(block))
+    }
+
+    /// Seeds a [`LiveOrderBook`] with every offer on `pairs` at the latest block.
+    /// Feed it the events from [`RubiconSession::stream_book_events`] (with the `streaming` feature) to keep it current.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn live_order_book(&self, pairs: &[Pair]) -> Result<LiveOrderBook> {
+        let pairs: Vec<Pair> = pairs
+            .iter()
+            .map(|x| settlement_pair(x, "live_order_book"))
+            .collect::<Result<_>>()?;
+        let block = self
+            ._internal_middleware
+            .get_block_number()
+            .await
+            .map_err(|e| anyhow!("[live_order_book]: eth_blockNumber failed: {}", e))?
+            .as_u64();
+        let pinned = Some(BlockId::Number(BlockNumber::Number(block.into())));
+
+        let mut offers = Vec::new();
+        for pair in pairs.iter() {
+            offers.extend(self.book_offers(pair.quote(), pair.base(), usize::MAX, pinned).await?);
+            offers.extend(self.book_offers(pair.base(), pair.quote(), usize::MAX, pinned).await?);
+        }
+        LiveOrderBook::new(*self.chain(), pairs, offers, block)
+    }
+
+    /// Returns the top `depth` offers selling `sell` for `buy`, best first, with their exact wei amounts.
+    /// This is the snapshot that a [`crate::market::QuoteSimulation`] walks, so that many quotes can be simulated from one walk of the book.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn sorted_offers(&self, sell: &Asset, buy: &Asset, depth: usize) -> Result<Vec<Offer>> {
+        self.book_offers(sell, buy, depth, None).await
+    }
+
+    async fn book_side(
+        &self,
+        pair: &Pair,
+        sell: &Asset,
+        buy: &Asset,
+        depth: usize,
+        block: Option<BlockId>,
+    ) -> Result<Vec<BookLevel>> {
+        self.book_offers(sell, buy, depth, block)
+            .await?
+            .iter()
+            .map(|x| BookLevel::for_snapshot(x.id(), &x.to_swap(), pair))
+            .filter_map(Result::transpose)
+            .collect()
+    }
+
+    // walks the offers selling `sell` for `buy`, from the best down
+    async fn book_offers(
+        &self,
+        sell: &Asset,
+        buy: &Asset,
+        depth: usize,
+        block: Option<BlockId>,
+    ) -> Result<Vec<Offer>> {
+        let mut offers = Vec::new();
+        let mut next = match depth {
+            0 => None,
+            _ => self.get_best_offer_at(sell, buy, block).await?,
+        };
+        while let Some(id) = next {
+            let offer = self.get_offer_at(id, block).await?.ok_or(anyhow!(
+                "[order_book]: offer {} is in the sorted list, but doesn't exist",
+                id
+            ))?;
+            offers.push(offer);
+            next = match offers.len() < depth {
+                true => self.get_worse_offer_at(id, block).await?,
+                false => None,
+            };
+        }
+        Ok(offers)
+    }
+}
+
+// the pair the market actually trades for `pair`, which fails for ETH-WETH since both sides settle as WETH
+pub(crate) fn settlement_pair(pair: &Pair, func: &str) -> Result<Pair> {
+    let (base, quote) = (pair.base().settlement_asset(), pair.quote().settlement_asset());
+    if base == quote {
+        return Err(anyhow!("[{}]: both sides of {} settle as {}, so there's no book for it", func, pair, base));
+    }
+    Ok(Pair::new(base, quote))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::mock::mocked_session;
+    use futures::executor::block_on;
+
+    #[test]
+    fn an_eth_weth_book_is_an_error_not_a_panic() {
+        // nothing is mocked, so this has to fail before making any requests
+        let (session, _mock) = mocked_session();
+        let pair = Pair::new(Asset::Eth, Asset::Weth);
+        let e = block_on(session.order_book(&pair, 5)).unwrap_err();
+        assert!(e.to_string().starts_with("[order_book]:"), "{}", e);
+        let e = block_on(session.live_order_book(&[Pair::new(Asset::Weth, Asset::Usdc), pair])).unwrap_err();
+        assert!(e.to_string().starts_with("[live_order_book]:"), "{}", e);
+        assert_eq!(
+            settlement_pair(&Pair::new(Asset::Eth, Asset::Usdc), "order_book").unwrap(),
+            Pair::new(Asset::Weth, Asset::Usdc)
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use super::{Deployment, DeploymentManifest, RubiconSession};
+use anyhow::{anyhow, Result};
+use ethers::providers::Middleware;
+use numeraire::prelude::TokenRegistry;
+use std::sync::Arc;
+use tracing::instrument;
+
+/**
+ * Builds a [`RubiconSession`] against an arbitrary [`Deployment`], e.g. a local anvil fork or a new deployment.
+ *
+ * [`RubiconSessionBuilder::build`] trusts the deployment as given.
+ * [`RubiconSessionBuilder::build_verified`] checks `eth_chainId` against the deployment, and that there is contract code at every address.
+ * If no deployment was given, `build_verified` picks one out of the manifest (by default, [`DeploymentManifest::builtin`]) using `eth_chainId`.
+ */
+pub struct RubiconSessionBuilder<M: Middleware + Clone + 'static> {
+    client: Arc<M>,
+    deployment: Option<Deployment>,
+    manifest: Option<DeploymentManifest>,
+    registry: Option<TokenRegistry>,
+}
+
+impl<M: Middleware + Clone + 'static> RubiconSessionBuilder<M> {
+    pub fn new(client: M) -> Self {
+        Self {
+            client: Arc::new(client),
+            deployment: None,
+            manifest: None,
+            registry: None,
+        }
+    }
+
+    /// Use exactly this deployment.
+    pub fn deployment(mut self, deployment: Deployment) -> Self {
+        self.deployment = Some(deployment);
+        self
+    }
+
+    /// Pick the deployment out of this manifest, based on the chain id reported by the provider.
+    pub fn manifest(mut self, manifest: DeploymentManifest) -> Self {
+        self.manifest = Some(manifest);
+        self
+    }
+
+    /// Use this registry to resolve tokens, instead of [`TokenRegistry::with_builtins`].
+    pub fn registry(mut self, registry: TokenRegistry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Builds the session without touching the network. This requires that a deployment was given.
+    pub fn build(self) -> Result<RubiconSession<M>> {
+        let deployment = self.deployment.ok_or(anyhow!(
+            "[RubiconSessionBuilder::build]: no deployment was given, use build_verified to pick one by chain id"
+        ))?;
+        Self::assemble(self.client, deployment, self.registry)
+    }
+
+    /// Builds the session after checking the deployment against the chain.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn build_verified(self) -> Result<RubiconSession<M>> {
+        let chain_id = self
+            .client
+            .get_chainid()
+            .await
+            .map_err(|e| anyhow!("[RubiconSessionBuilder::build_verified]: eth_chainId failed: {}", e))?
+            .as_u64();
+
+        let deployment = match self.deployment {
+            Some(deployment) => {
+                if deployment.chain_id() != chain_id {
+                    return Err(anyhow!(
+                        "[RubiconSessionBuilder::build_verified]: deployment is for chain id {}, but the provider is on chain id {}",
+                        deployment.chain_id(),
+                        chain_id
+                    ));
+                }
+                deployment
+            }
+            None => self
+                .manifest
+                .unwrap_or_else(DeploymentManifest::builtin)
+                .get(chain_id)
+                .cloned()
+                .ok_or(anyhow!(
+                    "[RubiconSessionBuilder::build_verified]: the manifest has no deployment for chain id {}",
+                    chain_id
+                ))?,
+        };
+
+        for (name, address) in deployment.contracts() {
+            let code = self
+                .client
+                .get_code(address, None)
+                .await
+                .map_err(|e| anyhow!("[RubiconSessionBuilder::build_verified]: eth_getCode failed: {}", e))?;
+            if code.as_ref().is_empty() {
+                return Err(anyhow!(
+                    "[RubiconSessionBuilder::build_verified]: no contract code for {} at {:?} on chain id {}",
+                    name,
+                    address,
+                    chain_id
+                ));
+            }
+        }
+
+        Self::assemble(self.client, deployment, self.registry)
+    }
+
+    fn assemble(
+        client: Arc<M>,
+        deployment: Deployment,
+        registry: Option<TokenRegistry>,
+    ) -> Result<RubiconSession<M>> {
+        let session = RubiconSession::from_deployment(client, deployment)?;
+        Ok(match registry {
+            Some(registry) => session.with_registry(registry),
+            None => session,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::mock::respond;
+    use ethers::core::types::{Bytes, U256};
+    use ethers::providers::{MockProvider, Provider};
+    use futures::executor::block_on;
+    use serde_json::json;
+
+    fn mocked_builder() -> (RubiconSessionBuilder<Provider<MockProvider>>, MockProvider) {
+        let (provider, mock) = Provider::mocked();
+        (RubiconSessionBuilder::new(provider), mock)
+    }
+
+    fn chain_id(id: u64) -> serde_json::Value {
+        json!(U256::from(id))
+    }
+
+    fn code(bytes: &[u8]) -> serde_json::Value {
+        json!(Bytes::from(bytes.to_vec()))
+    }
+
+    #[test]
+    fn build_verified_picks_the_deployment_by_chain_id() {
+        let (builder, mock) = mocked_builder();
+        let contracts = Deployment::optimism().contracts().len();
+        let mut responses = vec![chain_id(10)];
+        responses.extend((0..contracts).map(|_| code(&[0x60, 0x80])));
+        respond(&mock, responses);
+        let session = block_on(builder.build_verified()).unwrap();
+        assert_eq!(session.deployment(), &Deployment::optimism());
+        mock.assert_request("eth_chainId", ()).unwrap();
+    }
+
+    #[test]
+    fn build_verified_refuses_the_wrong_chain() {
+        let (builder, mock) = mocked_builder();
+        respond(&mock, vec![chain_id(1)]);
+        let e = block_on(builder.deployment(Deployment::optimism()).build_verified()).err().unwrap();
+        assert!(e.to_string().contains("provider is on chain id 1"), "{}", e);
+
+        // and a chain the manifest doesn't have
+        let (builder, mock) = mocked_builder();
+        respond(&mock, vec![chain_id(1)]);
+        let e = block_on(builder.build_verified()).err().unwrap();
+        assert!(e.to_string().contains("no deployment for chain id 1"), "{}", e);
+    }
+
+    #[test]
+    fn build_verified_refuses_an_address_without_code() {
+        let (builder, mock) = mocked_builder();
+        // the market, house and pair have code, but the router doesn't
+        respond(
+            &mock,
+            vec![chain_id(10), code(&[0x60]), code(&[0x60]), code(&[0x60]), code(&[]), code(&[0x60])],
+        );
+        let e = block_on(builder.build_verified()).err().unwrap();
+        assert!(e.to_string().contains("no contract code for router"), "{}", e);
+    }
+
+    #[test]
+    fn build_needs_a_deployment() {
+        let (builder, _mock) = mocked_builder();
+        assert!(builder.build().is_err());
+        let (builder, _mock) = mocked_builder();
+        assert_eq!(builder.deployment(Deployment::optimism()).build().unwrap().chain(), &ethers::core::types::Chain::Optimism);
+    }
+}
@@ -0,0 +1,401 @@
+use anyhow::{anyhow, Result};
+use ethers::core::types::{Address, Chain};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The chain id of Optimism Goerli, which ethers-rs doesn't have a [`Chain`] for.
+pub const OPTIMISM_GOERLI_CHAIN_ID: u64 = 420;
+
+/**
+ * A [`Deployment`] is the set of Rubicon contract addresses on a single chain.
+ * Deployments can be built in code, taken from the built-in constructors, or loaded as part of a [`DeploymentManifest`].
+ *
+ * `chain_id` is what `eth_chainId` must return for the deployment to be considered valid.
+ * `chain` is what the session uses to look up tokens (see [`numeraire::prelude::TokenRegistry`]).
+ * These are usually the same chain, but ethers-rs doesn't have a variant for every chain Rubicon is deployed to.
+ */
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "DeploymentRepr", into = "DeploymentRepr")]
+pub struct Deployment {
+    chain_id: u64,
+    chain: Chain,
+    market: Address,
+    house: Address,
+    pair: Address,
+    router: Address,
+    aid: Option<Address>,
+}
+
+impl Deployment {
+    /// Creates a new deployment. The chain used for token lookups is derived from `chain_id`.
+    pub fn new(
+        chain_id: u64,
+        market: Address,
+        house: Address,
+        pair: Address,
+        router: Address,
+    ) -> Result<Self> {
+        let chain = lookup_chain(chain_id)
+            .ok_or_else(|| anyhow!("[Deployment::new]: unknown chain id {}, use Deployment::with_chain to pick one", chain_id))?;
+        Ok(Self {
+            chain_id,
+            chain,
+            market,
+            house,
+            pair,
+            router,
+            aid: None,
+        })
+    }
+
+    /// The Rubicon v1.3 deployment on Optimism Mainnet.
+    pub fn optimism() -> Self {
+        Self {
+            chain_id: 10,
+            chain: Chain::Optimism,
+            market: parse_address("7a512d3609211e719737E82c7bb7271eC05Da70d"),
+            house: parse_address("203328C161D23dCEee3E439DeEB25cA19e2c4984"),
+            pair: parse_address("F8780E00Ce8ed2e79aeC10908a169900eD1D4AFe"),
+            router: parse_address("7Af14ADc8Aea70f063c7eA3B2C1AD0D7A59C4bFf"),
+            aid: Some(parse_address("1be63db5051C126f54Ad0807E1B471ad9AA0befD")),
+        }
+    }
+
+    /// The Rubicon v1.3 deployment on Optimism Kovan. OP Kovan is now considered obsolete.
+    pub fn optimism_kovan() -> Self {
+        Self {
+            chain_id: 69,
+            chain: Chain::OptimismKovan,
+            market: parse_address("45Da67AAAAa0164cC2f8789CE1B9EAcEABE70c51"),
+            house: parse_address("68B5fBd7CEFEE3076e4101920b13C9Cc1A6cbF0e"),
+            pair: parse_address("45Da67AAAAa0164cC2f8789CE1B9EAcEABE70c51"),
+            router: parse_address("8316F9a27e6F632a54D1E1F21aF347D4E0Cc38Dd"),
+            aid: None,
+        }
+    }
+
+    /// The Rubicon v1.3 deployment on Optimism Goerli (chain id 420).
+    /// ethers-rs doesn't have an Optimism Goerli chain, so its tokens are filed under [`Chain::Goerli`], which nothing else in the SDK uses.
+    pub fn optimism_goerli() -> Self {
+        Self {
+            chain_id: OPTIMISM_GOERLI_CHAIN_ID,
+            chain: Chain::Goerli,
+            market: parse_address("6cD8666aBB003073e45D69E5b3aa0b0Fe9CDBF91"),
+            house: parse_address("1229036F63679B61910CB1463e5BB57f68D19bb2"),
+            pair: parse_address("9dBf17d518f722B5Aae5573D808B94024b635529"),
+            router: parse_address("6aaEd1985a0e011ca82BB5Df8ebd92063134fd7c"),
+            aid: None,
+        }
+    }
+
+    /// Sets the chain used for token lookups, e.g. to reuse Optimism token addresses on a local anvil fork.
+    pub fn with_chain(mut self, chain: Chain) -> Self {
+        self.chain = chain;
+        self
+    }
+
+    /// Sets the address of the MarketAid contract.
+    pub fn with_aid(mut self, aid: Address) -> Self {
+        self.aid = Some(aid);
+        self
+    }
+
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// Returns a reference to the chain used for token lookups.
+    pub fn chain(&self) -> &Chain {
+        &self.chain
+    }
+
+    pub fn market(&self) -> Address {
+        self.market
+    }
+
+    pub fn house(&self) -> Address {
+        self.house
+    }
+
+    pub fn pair(&self) -> Address {
+        self.pair
+    }
+
+    pub fn router(&self) -> Address {
+        self.router
+    }
+
+    /// True if transactions have to be sent as legacy (pre EIP-1559) transactions.
+    /// This follows the chain used for token lookups, except for OP Goerli, which is filed under a chain that isn't legacy.
+    pub fn is_legacy(&self) -> bool {
+        self.chain.is_legacy() || self.chain_id == OPTIMISM_GOERLI_CHAIN_ID
+    }
+
+    /// The MarketAid contract isn't deployed everywhere, so this is optional.
+    pub fn aid(&self) -> Option<Address> {
+        self.aid
+    }
+
+    /// Every contract address in the deployment, labelled by contract name.
+    pub fn contracts(&self) -> Vec<(&'static str, Address)> {
+        let mut contracts = vec![
+            ("market", self.market),
+            ("house", self.house),
+            ("pair", self.pair),
+            ("router", self.router),
+        ];
+        if let Some(aid) = self.aid {
+            contracts.push(("aid", aid));
+        }
+        contracts
+    }
+}
+
+// the on-disk form of a deployment. `chain` is optional, and defaults to the chain with id `chain_id`
+#[derive(Serialize, Deserialize)]
+struct DeploymentRepr {
+    chain_id: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    chain: Option<String>,
+    market: Address,
+    house: Address,
+    pair: Address,
+    router: Address,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    aid: Option<Address>,
+}
+
+impl TryFrom<DeploymentRepr> for Deployment {
+    type Error = anyhow::Error;
+
+    fn try_from(repr: DeploymentRepr) -> Result<Self> {
+        let chain = match repr.chain {
+            Some(name) => name
+                .parse::<Chain>()
+                .map_err(|_| anyhow!("[Deployment]: unknown chain name {:?}", name))?,
+            None => lookup_chain(repr.chain_id).ok_or_else(|| {
+                anyhow!(
+                    "[Deployment]: unknown chain id {}, set `chain` to pick the chain used for token lookups",
+                    repr.chain_id
+                )
+            })?,
+        };
+        Ok(Self {
+            chain_id: repr.chain_id,
+            chain,
+            market: repr.market,
+            house: repr.house,
+            pair: repr.pair,
+            router: repr.router,
+            aid: repr.aid,
+        })
+    }
+}
+
+impl From<Deployment> for DeploymentRepr {
+    fn from(deployment: Deployment) -> Self {
+        Self {
+            chain_id: deployment.chain_id,
+            chain: Some(deployment.chain.to_string()),
+            market: deployment.market,
+            house: deployment.house,
+            pair: deployment.pair,
+            router: deployment.router,
+            aid: deployment.aid,
+        }
+    }
+}
+
+/**
+ * A [`DeploymentManifest`] is a list of [`Deployment`]s, at most one per chain id.
+ * Manifests can be loaded from JSON or TOML, e.g.
+ *
+ * ```toml
+ * [[deployments]]
+ * chain_id = 31337
+ * chain = "optimism" # look up tokens as if we were on OP mainnet
+ * market = "0x7a512d3609211e719737E82c7bb7271eC05Da70d"
+ * house = "0x203328C161D23dCEee3E439DeEB25cA19e2c4984"
+ * pair = "0xF8780E00Ce8ed2e79aeC10908a169900eD1D4AFe"
+ * router = "0x7Af14ADc8Aea70f063c7eA3B2C1AD0D7A59C4bFf"
+ * ```
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeploymentManifest {
+    deployments: Vec<Deployment>,
+}
+
+impl DeploymentManifest {
+    /// Creates an empty manifest.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The manifest of every deployment that ships with the SDK.
+    pub fn builtin() -> Self {
+        let mut manifest = Self::new();
+        manifest.insert(Deployment::optimism());
+        manifest.insert(Deployment::optimism_kovan());
+        manifest.insert(Deployment::optimism_goerli());
+        manifest
+    }
+
+    /// Parses a manifest from JSON.
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        Self::checked(serde_json::from_str(json)?)
+    }
+
+    /// Parses a manifest from TOML.
+    pub fn from_toml_str(toml: &str) -> Result<Self> {
+        Self::checked(toml::from_str(toml)?)
+    }
+
+    /// Loads a manifest from a file. The format is picked by the extension, which must be `.json` or `.toml`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("json") => Self::from_json_str(&contents),
+            Some("toml") => Self::from_toml_str(&contents),
+            _ => Err(anyhow!(
+                "[DeploymentManifest::from_file]: can't tell the format of {:?}, expected a .json or .toml file",
+                path
+            )),
+        }
+    }
+
+    /// Adds a deployment, replacing any existing deployment with the same chain id.
+    pub fn insert(&mut self, deployment: Deployment) {
+        self.deployments
+            .retain(|x| x.chain_id() != deployment.chain_id());
+        self.deployments.push(deployment);
+    }
+
+    /// Returns the deployment for the given chain id, if there is one.
+    pub fn get(&self, chain_id: u64) -> Option<&Deployment> {
+        self.deployments.iter().find(|x| x.chain_id() == chain_id)
+    }
+
+    pub fn deployments(&self) -> &[Deployment] {
+        &self.deployments
+    }
+
+    fn checked(manifest: Self) -> Result<Self> {
+        for (i, deployment) in manifest.deployments.iter().enumerate() {
+            if manifest.deployments[..i]
+                .iter()
+                .any(|x| x.chain_id() == deployment.chain_id())
+            {
+                return Err(anyhow!(
+                    "[DeploymentManifest]: chain id {} appears more than once",
+                    deployment.chain_id()
+                ));
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+// the chain to look tokens up under for a chain id, if we know one
+fn lookup_chain(chain_id: u64) -> Option<Chain> {
+    match chain_id {
+        OPTIMISM_GOERLI_CHAIN_ID => Some(Chain::Goerli),
+        _ => Chain::try_from(chain_id).ok(),
+    }
+}
+
+fn parse_address(address: &str) -> Address {
+    let hx = hex::decode(address).unwrap();
+    Address::from_slice(hx.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+[[deployments]]
+chain_id = 31337
+chain = "optimism"
+market = "0x7a512d3609211e719737E82c7bb7271eC05Da70d"
+house = "0x203328C161D23dCEee3E439DeEB25cA19e2c4984"
+pair = "0xF8780E00Ce8ed2e79aeC10908a169900eD1D4AFe"
+router = "0x7Af14ADc8Aea70f063c7eA3B2C1AD0D7A59C4bFf"
+
+[[deployments]]
+chain_id = 10
+market = "0x7a512d3609211e719737E82c7bb7271eC05Da70d"
+house = "0x203328C161D23dCEee3E439DeEB25cA19e2c4984"
+pair = "0xF8780E00Ce8ed2e79aeC10908a169900eD1D4AFe"
+router = "0x7Af14ADc8Aea70f063c7eA3B2C1AD0D7A59C4bFf"
+aid = "0x1be63db5051C126f54Ad0807E1B471ad9AA0befD"
+"#;
+
+    fn repr(chain_id: u64, chain: Option<&str>) -> DeploymentRepr {
+        DeploymentRepr {
+            chain_id,
+            chain: chain.map(String::from),
+            market: Address::repeat_byte(1),
+            house: Address::repeat_byte(2),
+            pair: Address::repeat_byte(3),
+            router: Address::repeat_byte(4),
+            aid: None,
+        }
+    }
+
+    #[test]
+    fn manifests_parse_from_toml_and_json() {
+        let manifest = DeploymentManifest::from_toml_str(TOML).unwrap();
+        assert_eq!(manifest.deployments().len(), 2);
+        let fork = manifest.get(31337).unwrap();
+        assert_eq!(fork.chain(), &Chain::Optimism);
+        assert_eq!(fork.market(), Deployment::optimism().market());
+        assert_eq!(fork.aid(), None);
+        // without a `chain`, tokens are looked up on the chain with that id
+        assert_eq!(manifest.get(10).unwrap(), &Deployment::optimism());
+        assert!(manifest.get(1).is_none());
+
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert_eq!(DeploymentManifest::from_json_str(&json).unwrap(), manifest);
+        let builtin = DeploymentManifest::builtin();
+        assert_eq!(DeploymentManifest::from_json_str(&serde_json::to_string(&builtin).unwrap()).unwrap(), builtin);
+    }
+
+    #[test]
+    fn a_chain_id_can_only_appear_once() {
+        let twice = TOML.replace("chain_id = 31337", "chain_id = 10");
+        let e = DeploymentManifest::from_toml_str(&twice).unwrap_err();
+        assert!(e.to_string().contains("chain id 10 appears more than once"), "{}", e);
+        // inserting replaces instead
+        let mut manifest = DeploymentManifest::from_toml_str(TOML).unwrap();
+        manifest.insert(Deployment::optimism().with_chain(Chain::Mainnet));
+        assert_eq!(manifest.deployments().len(), 2);
+        assert_eq!(manifest.get(10).unwrap().chain(), &Chain::Mainnet);
+    }
+
+    #[test]
+    fn the_lookup_chain_comes_from_the_name_or_the_id() {
+        assert_eq!(Deployment::try_from(repr(10, None)).unwrap().chain(), &Chain::Optimism);
+        assert_eq!(Deployment::try_from(repr(31337, Some("optimism"))).unwrap().chain(), &Chain::Optimism);
+        assert_eq!(Deployment::try_from(repr(31337, None)).unwrap().chain(), &Chain::AnvilHardhat);
+        assert!(Deployment::try_from(repr(10, Some("not-a-chain"))).is_err());
+        let e = Deployment::try_from(repr(8453, None)).unwrap_err();
+        assert!(e.to_string().contains("unknown chain id 8453"), "{}", e);
+        assert!(Deployment::new(8453, Address::zero(), Address::zero(), Address::zero(), Address::zero()).is_err());
+    }
+
+    #[test]
+    fn op_goerli_has_its_own_tokens_and_sends_legacy_transactions() {
+        let goerli = Deployment::try_from(repr(OPTIMISM_GOERLI_CHAIN_ID, None)).unwrap();
+        assert_eq!(goerli.chain(), &Chain::Goerli);
+        assert_eq!(Deployment::optimism_goerli().chain(), &Chain::Goerli);
+        let registry = numeraire::prelude::TokenRegistry::with_builtins();
+        let weth = registry.get(&Chain::Goerli, "WETH").unwrap();
+        assert_eq!(*weth.address(), parse_address("4200000000000000000000000000000000000006"));
+        assert!(registry.get(&Chain::Goerli, "OP").is_some());
+
+        assert!(Deployment::optimism_goerli().is_legacy());
+        assert!(Deployment::optimism().is_legacy());
+        assert!(!Deployment::try_from(repr(5, None)).unwrap().is_legacy());
+    }
+}
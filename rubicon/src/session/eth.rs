@@ -0,0 +1,256 @@
+use super::{ContractCall, RubiconSession};
+use anyhow::{anyhow, Result};
+use ethers::{
+    core::types::{Address, U256},
+    middleware::SignerMiddleware,
+    providers::Middleware,
+    signers::Signer,
+};
+use tracing::instrument;
+
+/*
+ * The RubiconRouter can wrap and unwrap WETH on the way in and out of the market,
+ * so that wallets holding native ETH can trade without calling `WETH.deposit` first.
+ *
+ * Functions that spend ETH (`*WithETH`) are payable, and the router checks `msg.value` exactly:
+ * sending too little *or* too much reverts. Where the router also takes the market fee in ETH,
+ * the value is `amount + amount * expected_fee_bps / 10_000`, computed the same way the router does.
+ * Functions that pay out ETH (`*ForETH`) spend tokens that must be approved to the router, not the market.
+ */
+impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMiddleware<M, S>> {
+    /// Places a limit order selling `pay_amt` of native ETH for at least `buy_amt` of `buy_gem`. Sends `pay_amt` as value.
+    /// The `pos` parameter should be `None` unless you know the new position of the order in the sorted orderbook.
+    #[instrument(level = "debug", skip(self))]
+    pub fn offer_with_eth(
+        &self,
+        pay_amt: U256,
+        buy_amt: U256,
+        buy_gem: Address,
+        pos: Option<U256>,
+    ) -> Result<ContractCall<SignerMiddleware<M, S>, U256>> {
+        let tx = self
+            .router()
+            .offer_with_eth(pay_amt, buy_amt, buy_gem, pos.unwrap_or(U256::zero()))
+            .value(pay_amt);
+        Ok(self.legacy_if_needed(tx))
+    }
+
+    /// Places a limit order selling `pay_amt` of `pay_gem` for at least `buy_amt` of ETH, which is unwrapped when the order fills.
+    #[instrument(level = "debug", skip(self))]
+    pub fn offer_for_eth(
+        &self,
+        pay_amt: U256,
+        pay_gem: Address,
+        buy_amt: U256,
+        pos: Option<U256>,
+    ) -> Result<ContractCall<SignerMiddleware<M, S>, U256>> {
+        let tx = self
+            .router()
+            .offer_for_eth(pay_amt, pay_gem, buy_amt, pos.unwrap_or(U256::zero()));
+        Ok(self.legacy_if_needed(tx))
+    }
+
+    /// Cancels an order that was placed with [`RubiconSession::offer_for_eth`] or [`RubiconSession::offer_with_eth`],
+    /// returning any unfilled WETH as native ETH.
+    #[instrument(level = "debug", skip(self))]
+    pub fn cancel_for_eth(&self, order_id: U256) -> Result<ContractCall<SignerMiddleware<M, S>, bool>> {
+        let tx = self.router().cancel_for_eth(order_id);
+        Ok(self.legacy_if_needed(tx))
+    }
+
+    /// Swaps `pay_amt` of native ETH along `route` for at least `buy_amt_min` of the last token in the route.
+    /// `route` must start with WETH. Sends `pay_amt` plus the expected market fee as value.
+    #[instrument(level = "debug", skip(self))]
+    pub fn swap_with_eth(
+        &self,
+        pay_amt: U256,
+        buy_amt_min: U256,
+        route: Vec<Address>,
+        expected_fee_bps: U256,
+    ) -> Result<ContractCall<SignerMiddleware<M, S>, U256>> {
+        self.check_route(&route, route.first(), "swap_with_eth", "start")?;
+        let value = with_fee(pay_amt, expected_fee_bps)?;
+        let tx = self
+            .router()
+            .swap_with_eth(pay_amt, buy_amt_min, route, expected_fee_bps)
+            .value(value);
+        Ok(self.legacy_if_needed(tx))
+    }
+
+    /// Swaps `pay_amt` of the first token in `route` for at least `buy_amt_min` of ETH. `route` must end with WETH.
+    #[instrument(level = "debug", skip(self))]
+    pub fn swap_for_eth(
+        &self,
+        pay_amt: U256,
+        buy_amt_min: U256,
+        route: Vec<Address>,
+        expected_fee_bps: U256,
+    ) -> Result<ContractCall<SignerMiddleware<M, S>, U256>> {
+        self.check_route(&route, route.last(), "swap_for_eth", "end")?;
+        let tx = self
+            .router()
+            .swap_for_eth(pay_amt, buy_amt_min, route, expected_fee_bps);
+        Ok(self.legacy_if_needed(tx))
+    }
+
+    /// This is a market buy paid for in native ETH: we buy `buy_amt` of `buy_gem`, spending no more than `max_fill_amount` of ETH.
+    /// Sends `max_fill_amount` plus the expected market fee as value. Whatever isn't spent is refunded by the router.
+    #[instrument(level = "debug", skip(self))]
+    pub fn buy_all_amount_with_eth(
+        &self,
+        buy_gem: Address,
+        buy_amt: U256,
+        max_fill_amount: U256,
+        expected_fee_bps: U256,
+    ) -> Result<ContractCall<SignerMiddleware<M, S>, U256>> {
+        let value = with_fee(max_fill_amount, expected_fee_bps)?;
+        let tx = self
+            .router()
+            .buy_all_amount_with_eth(buy_gem, buy_amt, max_fill_amount, expected_fee_bps)
+            .value(value);
+        Ok(self.legacy_if_needed(tx))
+    }
+
+    /// This is a market buy of `buy_amt` of native ETH, where we spend no more than `max_fill_amount` of `pay_gem`.
+    #[instrument(level = "debug", skip(self))]
+    pub fn buy_all_amount_for_eth(
+        &self,
+        buy_amt: U256,
+        pay_gem: Address,
+        max_fill_amount: U256,
+    ) -> Result<ContractCall<SignerMiddleware<M, S>, U256>> {
+        let tx = self
+            .router()
+            .buy_all_amount_for_eth(buy_amt, pay_gem, max_fill_amount);
+        Ok(self.legacy_if_needed(tx))
+    }
+
+    /// Deposits `amount` of native ETH into the WETH bath token at `target_pool`. Sends `amount` as value.
+    /// The returned value is the number of new shares.
+    #[instrument(level = "debug", skip(self))]
+    pub fn deposit_with_eth(
+        &self,
+        amount: U256,
+        target_pool: Address,
+    ) -> Result<ContractCall<SignerMiddleware<M, S>, U256>> {
+        let tx = self
+            .router()
+            .deposit_with_eth(amount, target_pool)
+            .value(amount);
+        Ok(self.legacy_if_needed(tx))
+    }
+
+    /// Burns `shares` of the WETH bath token at `target_pool`, and withdraws the underlying as native ETH.
+    /// The returned value is the amount of ETH withdrawn.
+    #[instrument(level = "debug", skip(self))]
+    pub fn withdraw_for_eth(
+        &self,
+        shares: U256,
+        target_pool: Address,
+    ) -> Result<ContractCall<SignerMiddleware<M, S>, U256>> {
+        let tx = self.router().withdraw_for_eth(shares, target_pool);
+        Ok(self.legacy_if_needed(tx))
+    }
+
+    // the router only unwraps at the ends of a route, so make sure that WETH is where it needs to be
+    fn check_route(&self, route: &[Address], end: Option<&Address>, func: &str, which: &str) -> Result<()> {
+        if route.len() < 2 {
+            return Err(anyhow!(
+                "[{}]: a route needs at least two tokens, got {}",
+                func,
+                route.len()
+            ));
+        }
+        // we can only check this if we know where WETH lives on this chain
+        if let Ok(weth) = self.weth_address() {
+            if end != Some(&weth) {
+                return Err(anyhow!(
+                    "[{}]: the route must {} with WETH ({:?}), got {:?}",
+                    func,
+                    which,
+                    weth,
+                    route
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// the market takes its fee on top of the amount (and the router does the same in ETH), rounding the fee down
+pub(crate) fn with_fee(amount: U256, fee_bps: U256) -> Result<U256> {
+    amount
+        .checked_mul(fee_bps)
+        .map(|x| x / U256::from(10_000))
+        .and_then(|fee| amount.checked_add(fee))
+        .ok_or(anyhow!(
+            "[RubiconSession]: {} plus a fee of {} bps overflows",
+            amount,
+            fee_bps
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::mock::mocked_signer_session;
+    use numeraire::prelude::*;
+
+    #[test]
+    fn the_fee_is_added_and_rounded_down() {
+        assert_eq!(with_fee(U256::from(10_000), U256::from(20)).unwrap(), U256::from(10_020));
+        // 9_999 * 20 / 10_000 = 19.998
+        assert_eq!(with_fee(U256::from(9_999), U256::from(20)).unwrap(), U256::from(10_018));
+        // too little to pay any fee on
+        assert_eq!(with_fee(U256::from(499), U256::from(20)).unwrap(), U256::from(499));
+        assert_eq!(with_fee(U256::from(12_345), U256::zero()).unwrap(), U256::from(12_345));
+        assert_eq!(with_fee(U256::zero(), U256::from(20)).unwrap(), U256::zero());
+    }
+
+    #[test]
+    fn the_fee_refuses_to_overflow() {
+        // the multiplication overflows
+        assert!(with_fee(U256::MAX, U256::from(2)).is_err());
+        // the multiplication doesn't, but the sum does
+        assert!(with_fee(U256::MAX - 1, U256::one()).is_err());
+        // and just short of that, it all fits
+        let largest = U256::MAX / 10_001 * 10_000;
+        assert_eq!(with_fee(largest, U256::one()).unwrap(), U256::MAX / 10_001 * 10_001);
+        assert_eq!(with_fee(U256::MAX, U256::zero()).unwrap(), U256::MAX);
+    }
+
+    #[test]
+    fn native_eth_settles_as_weth() {
+        let (session, _mock) = mocked_signer_session();
+        let chain = session.chain();
+        assert!(Asset::Eth.is_native());
+        assert!(!Asset::Weth.is_native());
+        assert_eq!(Asset::Eth.settlement_asset(), Asset::Weth);
+        assert_eq!(Asset::Usdc.settlement_asset(), Asset::Usdc);
+        assert_eq!(Asset::Eth.settlement_address(chain).unwrap(), session.weth_address().unwrap());
+        assert_eq!(
+            Asset::Usdc.settlement_address(chain).unwrap(),
+            Asset::Usdc.to_address(chain).unwrap()
+        );
+    }
+
+    #[test]
+    fn swapping_eth_sends_the_fee_and_starts_at_weth() {
+        let (session, _mock) = mocked_signer_session();
+        let weth = session.weth_address().unwrap();
+        let usdc = Asset::Usdc.to_address(session.chain()).unwrap();
+        let call = session
+            .swap_with_eth(U256::from(9_999), U256::one(), vec![weth, usdc], U256::from(20))
+            .unwrap();
+        assert_eq!(call.tx.value(), Some(&U256::from(10_018)));
+        let call = session
+            .buy_all_amount_with_eth(usdc, U256::one(), U256::from(10_000), U256::from(20))
+            .unwrap();
+        assert_eq!(call.tx.value(), Some(&U256::from(10_020)));
+
+        assert!(session.swap_with_eth(U256::one(), U256::one(), vec![usdc, weth], U256::zero()).is_err());
+        assert!(session.swap_for_eth(U256::one(), U256::one(), vec![weth, usdc], U256::zero()).is_err());
+        assert!(session.swap_for_eth(U256::one(), U256::one(), vec![usdc, weth], U256::zero()).is_ok());
+        assert!(session.swap_with_eth(U256::one(), U256::one(), vec![weth], U256::zero()).is_err());
+    }
+}
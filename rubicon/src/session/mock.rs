@@ -0,0 +1,35 @@
+// sessions over a mocked provider, for tests that don't need a node
+use super::RubiconSession;
+use ethers::{
+    abi::Token,
+    core::types::Bytes,
+    middleware::SignerMiddleware,
+    providers::{MockProvider, Provider},
+    signers::LocalWallet,
+};
+
+/// A session on Optimism mainnet whose requests are answered by the returned mock.
+pub(crate) fn mocked_session() -> (RubiconSession<Provider<MockProvider>>, MockProvider) {
+    let (provider, mock) = Provider::mocked();
+    (RubiconSession::new_mainnet(provider), mock)
+}
+
+/// Like [`mocked_session`], with a wallet to sign transactions.
+pub(crate) fn mocked_signer_session() -> (RubiconSession<SignerMiddleware<Provider<MockProvider>, LocalWallet>>, MockProvider) {
+    let (provider, mock) = Provider::mocked();
+    let wallet: LocalWallet = "4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318".parse().unwrap();
+    (RubiconSession::new_mainnet(SignerMiddleware::new(provider, wallet)), mock)
+}
+
+
+/// Queues up `responses` in the order they'll be requested (the mock answers the most recently pushed response first).
+pub(crate) fn respond(mock: &MockProvider, responses: Vec<serde_json::Value>) {
+    for response in responses.into_iter().rev() {
+        mock.push::<serde_json::Value, _>(response).unwrap();
+    }
+}
+
+/// The response to an `eth_call` that returns `tokens`.
+pub(crate) fn call_result(tokens: &[Token]) -> serde_json::Value {
+    serde_json::to_value(Bytes::from(ethers::abi::encode(tokens))).unwrap()
+}
@@ -1,9 +1,13 @@
 use anyhow::{anyhow, Result};
 
 pub use ethers::prelude::builders::ContractCall;
+use crate::contracts::{BathHouse, BathPair, RubiconMarket, RubiconRouter};
+use crate::market::{OfferId, Slippage};
+#[cfg(feature = "aid")]
+use crate::contracts::MarketAid;
 use ethers::{
-    contract::Contract,
-    core::types::{Address, Chain, U256},
+    abi::Detokenize,
+    core::types::{Address, BlockId, BlockNumber, Chain, U256},
     providers::Middleware,
     signers::Signer,
     middleware::SignerMiddleware,
@@ -12,8 +16,26 @@ use numeraire::prelude::*;
 use rust_decimal::Decimal;
 use tracing::instrument;
 use std::sync::Arc; 
-// #[cfg(feature = "streaming")]
-// mod streaming;
+use std::time::Duration;
+mod backfill;
+mod book;
+mod builder;
+mod deployment;
+mod eth;
+#[cfg(test)]
+mod mock;
+mod receipts;
+mod router_views;
+mod swap;
+mod views;
+#[cfg(feature = "streaming")]
+mod streaming;
+pub use backfill::*;
+pub use builder::*;
+pub use deployment::*;
+pub use swap::*;
+#[cfg(feature = "streaming")]
+pub use streaming::*;
 
 /*
  * TRACING METHODOLOGY:
@@ -31,16 +53,20 @@ use std::sync::Arc;
  */
 pub struct RubiconSession<M: Middleware + Clone + 'static> {
     chain: Chain,
-    rbcn_market: Contract<M>,
-    bath_house: Contract<M>,
-    bath_pair: Contract<M>,
+    rbcn_market: RubiconMarket<M>,
+    bath_house: BathHouse<M>,
+    bath_pair: BathPair<M>,
     #[cfg(feature = "aid")]
-    market_aid: Contract<M>,
-    router: Contract<M>,
+    market_aid: MarketAid<M>,
+    router: RubiconRouter<M>,
+    registry: TokenRegistry,
+    deployment: Deployment,
+    #[cfg(feature = "streaming")]
+    reconnect: Option<Reconnect<M>>,
     _internal_middleware: Arc<M>, // we just keep this around to clone if we build new contracts
 }
 
-/**
+/*
 *  impl<M: Middleware + Clone + 'static> RubiconSession<M>
    where
        <M as Middleware>::Provider: PubsubClient,
@@ -50,47 +76,46 @@ pub struct RubiconSession<M: Middleware + Clone + 'static> {
 impl<M: Middleware + Clone + 'static> RubiconSession<M> {
     /// Creates a new [`RubiconSession`] configured to Optimism Mainnet.
     pub fn new_mainnet(client: M) -> Self {
-        let arc_client = Arc::new(client);
-        Self {
-            chain: Chain::Optimism,
-            rbcn_market: crate::contracts::market::build_default(arc_client.clone()).unwrap(),
-            bath_house: crate::contracts::house::build_default(arc_client.clone()).unwrap(),
-            bath_pair: crate::contracts::pair::build_default(arc_client.clone()).unwrap(),
-            router: crate::contracts::router::build_default(arc_client.clone()).unwrap(),
-            #[cfg(feature = "aid")]
-            market_aid: crate::contracts::market_aid::build_default(arc_client.clone()).unwrap(),
-            _internal_middleware: arc_client,
-        }
+        Self::from_deployment(Arc::new(client), Deployment::optimism()).unwrap()
     }
 
     /// Creates a new [`RubiconSession`] configured to Optimism Kovan. OP Kovan is now considered obsolete.
     /// This requires that the `aid` feature is not set - the Market Aid contract is not deployed on OP Kovan.
     #[cfg(not(feature = "aid"))]
     pub fn new_kovan(client: M) -> Self {
-        let arc_client = Arc::new(client);
-        Self {
-            chain: Chain::OptimismKovan,
-            rbcn_market: crate::contracts::market::build_kovan(arc_client.clone()).unwrap(),
-            bath_house: crate::contracts::house::build_kovan(arc_client.clone()).unwrap(),
-            bath_pair: crate::contracts::pair::build_kovan(arc_client.clone()).unwrap(),
-            router: crate::contracts::router::build_kovan(arc_client.clone()).unwrap(),
-            _internal_middleware: arc_client,
-        }
+        Self::from_deployment(Arc::new(client), Deployment::optimism_kovan()).unwrap()
     }
 
     /// Creates a new [`RubiconSession`] configured to Optimism Goerli.
     /// This requires that the `aid` feature is not set - the Market Aid contract is not deployed on OP Goerli.
     #[cfg(not(feature = "aid"))]
     pub fn new_goerli(client: M) -> Self {
-        let arc_client = Arc::new(client);
-        Self {
-            chain: Chain::OptimismKovan,
-            rbcn_market: crate::contracts::market::build_goerli(arc_client.clone()).unwrap(),
-            bath_house: crate::contracts::house::build_goerli(arc_client.clone()).unwrap(),
-            bath_pair: crate::contracts::pair::build_goerli(arc_client.clone()).unwrap(),
-            router: crate::contracts::router::build_goerli(arc_client.clone()).unwrap(),
-            _internal_middleware: arc_client,
-        }
+        Self::from_deployment(Arc::new(client), Deployment::optimism_goerli()).unwrap()
+    }
+
+    /// Creates a new [`RubiconSession`] from an arbitrary [`Deployment`].
+    /// See [`RubiconSessionBuilder`] if you want the deployment checked against the chain first.
+    pub(crate) fn from_deployment(client: Arc<M>, deployment: Deployment) -> Result<Self> {
+        Ok(Self {
+            chain: *deployment.chain(),
+            rbcn_market: RubiconMarket::new(deployment.market(), client.clone()),
+            bath_house: BathHouse::new(deployment.house(), client.clone()),
+            bath_pair: BathPair::new(deployment.pair(), client.clone()),
+            router: RubiconRouter::new(deployment.router(), client.clone()),
+            #[cfg(feature = "aid")]
+            market_aid: MarketAid::new(
+                deployment.aid().ok_or(anyhow!(
+                    "[RubiconSession]: the `aid` feature is enabled, but the deployment on chain id {} has no MarketAid address",
+                    deployment.chain_id()
+                ))?,
+                client.clone(),
+            ),
+            registry: TokenRegistry::with_builtins(),
+            deployment,
+            #[cfg(feature = "streaming")]
+            reconnect: None,
+            _internal_middleware: client,
+        })
     }
 
     /// Changes the provider of the [`RubiconSession`].
@@ -99,41 +124,76 @@ impl<M: Middleware + Clone + 'static> RubiconSession<M> {
     fn change_provider(&mut self, provider: M) {
         let a = Arc::new(provider);
 
-        self.rbcn_market = self.rbcn_market.connect(a.clone());
-        self.bath_house = self.bath_house.connect(a.clone());
-        self.bath_pair = self.bath_pair.connect(a.clone());
-        self.router = self.router.connect(a.clone());
+        self.rbcn_market = RubiconMarket::new(self.rbcn_market.address(), a.clone());
+        self.bath_house = BathHouse::new(self.bath_house.address(), a.clone());
+        self.bath_pair = BathPair::new(self.bath_pair.address(), a.clone());
+        self.router = RubiconRouter::new(self.router.address(), a.clone());
+        #[cfg(feature = "aid")]
+        {
+            self.market_aid = MarketAid::new(self.market_aid.address(), a.clone());
+        }
         self._internal_middleware = a;
     }
 
     // Getter functions, and some small helper functions
     /// Returns a reference to the RubiconMarket contract.
-    pub fn market(&self) -> &Contract<M> {
+    pub fn market(&self) -> &RubiconMarket<M> {
         &self.rbcn_market
     }
 
     /// Returns a reference to the BathHouse contract.
-    pub fn bath_house(&self) -> &Contract<M> {
+    pub fn bath_house(&self) -> &BathHouse<M> {
         &self.bath_house
     }
 
     /// Returns a reference to the BathPair contract.
-    pub fn pair(&self) -> &Contract<M> {
+    pub fn pair(&self) -> &BathPair<M> {
         &self.bath_pair
     }
 
     /// Returns a reference to the Router contract.
-    pub fn router(&self) -> &Contract<M> {
+    pub fn router(&self) -> &RubiconRouter<M> {
         &self.router
     }
 
     /// Returns an Option on a reference to the MarketAid contract.
     /// Market Aid isn't deployed on Kovan and Goerli - we can't always depend on it being there.
     #[cfg(feature = "aid")]
-    pub fn market_aid(&self) -> &Contract<M> {
+    pub fn market_aid(&self) -> &MarketAid<M> {
         &self.market_aid
     }
 
+    /// Returns a reference to the [`TokenRegistry`] used to resolve token symbols and addresses.
+    pub fn registry(&self) -> &TokenRegistry {
+        &self.registry
+    }
+
+    /// Replaces the session's [`TokenRegistry`]. Sessions start out with [`TokenRegistry::with_builtins`].
+    pub fn with_registry(mut self, registry: TokenRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Resolves a token symbol (e.g. `"USDC"`) into an [`Asset`] on the current chain, using the session's registry.
+    pub fn token(&self, symbol: &str) -> Result<Asset> {
+        self.registry().asset(self.chain(), symbol)
+    }
+
+    /// The address of WETH on the current chain, which is what native ETH settles as. This is looked up in the session's registry.
+    pub fn weth_address(&self) -> Result<Address> {
+        self.registry().address_of(self.chain(), &Asset::Weth.symbol())
+    }
+
+    /// Returns a reference to the [`Deployment`] the session was built from.
+    pub fn deployment(&self) -> &Deployment {
+        &self.deployment
+    }
+
+    /// Returns an [`crate::events::EventDecoder`] for the session's contracts.
+    pub fn event_decoder(&self) -> crate::events::EventDecoder {
+        crate::events::EventDecoder::new(&self.deployment)
+    }
+
     /// Returns a reference to the ethers-rs chain enum.
     pub fn chain(&self) -> &Chain {
         &self.chain
@@ -146,7 +206,7 @@ impl<M: Middleware + Clone + 'static> RubiconSession<M> {
 
     /// Are we on a legacy chain (pre EIP-1559)? If so, we have to use legacy TX calls...
     pub fn is_legacy(&self) -> bool {
-        self.chain().is_legacy()
+        self.deployment.is_legacy()
     }
 
     // let's add in some builders for numeraire::ChainNativeAsset
@@ -204,12 +264,7 @@ impl<M: Middleware + Clone + 'static> RubiconSession<M> {
     /// This function returns true if the supplied address is an approved strategist.
     #[instrument(level = "debug", skip(self))]
     pub async fn is_approved_strategist(&self, addr: Address) -> Result<bool> {
-        let receipt = self
-            .bath_house()
-            .method::<_, bool>("isApprovedStrategist", (addr,))?
-            .call()
-            .await?;
-        Ok(receipt)
+        Ok(self.bath_house().is_approved_strategist(addr).call().await?)
     }
 
     // MarketAid functions
@@ -223,9 +278,9 @@ impl<M: Middleware + Clone + 'static> RubiconSession<M> {
         quote: Address,
         strategist: Address,
     ) -> Result<Vec<U256>> {
-        let ctr = self.market_aid();
-        Ok(ctr
-            .method::<_, Vec<U256>>("getOutstandingStrategistTrades", (asset, quote, strategist))?
+        Ok(self
+            .market_aid()
+            .get_outstanding_strategist_trades(asset, quote, strategist)
             .call()
             .await?)
     }
@@ -238,13 +293,13 @@ impl<M: Middleware + Clone + 'static> RubiconSession<M> {
     #[instrument(level = "trace", skip_all)]
     fn local_and_conjugate_rst(&self, bids: &[AssetSwap], asks: &[AssetSwap]) -> Result<()> {
         // now, we go assert that all the bids and asks are local to a single chain
-        if !bids.iter().map(|x| x.is_local_to_chain()).all(|x| x) {
+        if !bids.iter().all(|x| x.is_local_to_chain()) {
             return Err(anyhow!(
                 "[local_and_conjugate_rst]: ERROR: all bids are not local to a single chain!"
             ));
         }
 
-        if !asks.iter().map(|x| x.is_local_to_chain()).all(|x| x) {
+        if !asks.iter().all(|x| x.is_local_to_chain()) {
             return Err(anyhow!(
                 "[local_and_conjugate_rst]: ERROR: all asks are not local to a single chain!"
             ));
@@ -338,6 +393,18 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
      * - take
      */
 
+    // sends the call as a legacy transaction on chains that don't support EIP-1559
+    fn legacy_if_needed<D: Detokenize>(
+        &self,
+        tx: ContractCall<SignerMiddleware<M,S>, D>,
+    ) -> ContractCall<SignerMiddleware<M,S>, D> {
+        if self.is_legacy() {
+            tx.legacy()
+        } else {
+            tx
+        }
+    }
+
     /// This is a market buy, where we spend no more than max_fill_amount to buy buy_amt
     /// the returned value is the fill amount
     #[instrument(level = "debug", skip(self))]
@@ -348,16 +415,12 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
         pay_gem: Address,
         max_fill_amount: U256,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
-        let tx = match self.is_legacy() {
-            true => self
-                .market()
-                .method::<_, U256>("buyAllAmount", (buy_gem, buy_amt, pay_gem, max_fill_amount))?
-                .legacy(),
-            false => self
-                .market()
-                .method::<_, U256>("buyAllAmount", (buy_gem, buy_amt, pay_gem, max_fill_amount))?,
-        };
-        Ok(tx)
+        Ok(self.legacy_if_needed(self.market().buy_all_amount(
+            buy_gem,
+            buy_amt,
+            pay_gem,
+            max_fill_amount,
+        )))
     }
 
     /// This is a market sell, where we spend pay_amt to buy as much as possible of buy_gem (and we get *at least* min_fill_amount)
@@ -370,24 +433,16 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
         buy_gem: Address,
         min_fill_amount: U256,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
-        let tx = match self.is_legacy() {
-            true => self
-                .market()
-                .method::<_, U256>(
-                    "sellAllAmount",
-                    (pay_gem, pay_amt, buy_gem, min_fill_amount),
-                )?
-                .legacy(),
-            false => self.market().method::<_, U256>(
-                "sellAllAmount",
-                (pay_gem, pay_amt, buy_gem, min_fill_amount),
-            )?,
-        };
-        Ok(tx)
+        Ok(self.legacy_if_needed(self.market().sell_all_amount(
+            pay_gem,
+            pay_amt,
+            buy_gem,
+            min_fill_amount,
+        )))
     }
 
     /// This represents a market sell, where we sell the `source.size()` worth of `source.asset()`
-    /// in exchange for some undetermined amount `target`
+    /// in exchange for some undetermined amount `target`. There's no bound on the fill: see [`RubiconSession::market_sell_with_slippage`]
     #[instrument(level = "debug", skip(self))]
     pub fn market_sell(
         &self,
@@ -395,15 +450,15 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
         target: &Asset,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
         if source.chain() != self.chain() {
-            return Err(anyhow!(
+            Err(anyhow!(
                 "[market_sell]: source chain does not match session chain! ({}!={})",
                 source.chain(),
                 self.chain()
-            ));
+            ))
         } else {
             self.sell_all_amount(
                 source.address()?,
-                source.size().clone(),
+                *source.size(),
                 target.to_address(self.chain())?,
                 U256::zero(),
             )
@@ -411,7 +466,7 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
     }
 
     /// This represents a market sell, where we sell the `source.size()` worth of `source.asset()`
-    /// in exchange for some undetermined amount `target`
+    /// in exchange for some undetermined amount `target`. There's no bound on the fill: see [`RubiconSession::market_buy_with_slippage`]
     #[instrument(level = "debug", skip(self))]
     pub fn market_buy(
         &self,
@@ -419,21 +474,155 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
         target: &ChainNativeAsset,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
         if target.chain() != self.chain() {
-            return Err(anyhow!(
+            Err(anyhow!(
                 "[market_sell]: target chain does not match session chain! ({}!={})",
                 target.chain(),
                 self.chain()
-            ));
+            ))
         } else {
             self.buy_all_amount(
                 target.address()?,
-                target.size().clone(),
+                *target.size(),
                 source.to_address(self.chain())?,
                 U256::MAX,
             )
         }
     }
 
+    /*
+     * Market orders with a slippage bound.
+     * The order is quoted against the book first, with the market's `getBuyAmount`/`getPayAmount`, and the bound is worked out from the quote.
+     * (The router's `maxSellAllAmount`/`maxBuyAllAmount` aren't views: they spend the sender's whole balance, so can't quote an arbitrary size.)
+     */
+
+    /// This is a market sell of `source` for `target`, which reverts if we'd get less than `slippage` allows.
+    /// Fails without building a transaction if the book's quote is already worse than that.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn market_sell_with_slippage(
+        &self,
+        source: &ChainNativeAsset,
+        target: &Asset,
+        slippage: &Slippage,
+    ) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
+        if source.chain() != self.chain() {
+            return Err(anyhow!(
+                "[market_sell_with_slippage]: source chain does not match session chain! ({}!={})",
+                source.chain(),
+                self.chain()
+            ));
+        }
+        let quote = self.get_buy_amount(target, source).await?;
+        let min_fill = slippage.min_fill(source, &quote)?;
+        if quote.size() < min_fill.size() {
+            return Err(anyhow!(
+                "[market_sell_with_slippage]: the book only gives {} for {}, but {} needs at least {}",
+                quote,
+                source,
+                slippage,
+                min_fill
+            ));
+        }
+        self.sell_all_amount(
+            source.address()?,
+            *source.size(),
+            target.to_address(self.chain())?,
+            *min_fill.size(),
+        )
+    }
+
+    /// This is a market buy of `target` with `source`, which reverts if we'd pay more than `slippage` allows.
+    /// Fails without building a transaction if the book's quote is already worse than that.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn market_buy_with_slippage(
+        &self,
+        source: &Asset,
+        target: &ChainNativeAsset,
+        slippage: &Slippage,
+    ) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
+        if target.chain() != self.chain() {
+            return Err(anyhow!(
+                "[market_buy_with_slippage]: target chain does not match session chain! ({}!={})",
+                target.chain(),
+                self.chain()
+            ));
+        }
+        let quote = self.get_pay_amount(source, target).await?;
+        let max_fill = slippage.max_fill(target, &quote)?;
+        if quote.size() > max_fill.size() {
+            return Err(anyhow!(
+                "[market_buy_with_slippage]: the book wants {} for {}, but {} allows at most {}",
+                quote,
+                target,
+                slippage,
+                max_fill
+            ));
+        }
+        self.buy_all_amount(
+            target.address()?,
+            *target.size(),
+            source.to_address(self.chain())?,
+            *max_fill.size(),
+        )
+    }
+
+    /// This takes up to `amount` of what the offer `id` is selling, paying the offer's price plus the market's fee.
+    /// The offer is checked first: it must be active, and `amount` must be in the asset it sells. `amount` is capped to what's left of the offer.
+    /// Returns the [`ContractCall`], along with the [`AssetSwap`] we expect from our point of view: the cost (fee included) for what we take.
+    /// The cost is rounded up, so it's at least what the market will actually charge.
+    ///
+    /// The checks (and the fee) are all read at the latest block, so they agree with each other, but they're only advisory:
+    /// the offer can be taken or killed before the transaction is mined. The buy itself is bounded by the offer, though:
+    /// an offer's price never changes, and the market won't fill more than what's left of it, so at worst nothing is filled.
+    #[allow(clippy::type_complexity)]
+    #[instrument(level = "debug", skip(self))]
+    pub async fn take_offer(
+        &self,
+        id: U256,
+        amount: &ChainNativeAsset,
+    ) -> Result<(ContractCall<SignerMiddleware<M,S>, bool>, AssetSwap)> {
+        let block = self
+            ._internal_middleware
+            .get_block_number()
+            .await
+            .map_err(|e| anyhow!("[take_offer]: eth_blockNumber failed: {}", e))?;
+        let pinned = Some(BlockId::Number(BlockNumber::Number(block)));
+        if !self.is_active_at(id, pinned).await? {
+            return Err(anyhow!("[take_offer]: offer {} isn't active", id));
+        }
+        let offer = self
+            .get_offer_at(id, pinned)
+            .await?
+            .ok_or(anyhow!("[take_offer]: offer {} doesn't exist", id))?;
+        if amount.asset().settlement_asset() != *offer.pay().asset() {
+            return Err(anyhow!(
+                "[take_offer]: offer {} sells {}, not {}",
+                id,
+                offer.pay().asset(),
+                amount.asset()
+            ));
+        }
+        let quantity = (*amount.size()).min(*offer.pay().size());
+        let owed = quantity
+            .checked_mul(*offer.buy().size())
+            .ok_or(anyhow!("[take_offer]: {} of offer {} overflows", quantity, id))?;
+        // the market rounds what the taker spends down, but the cost we expect rounds up so that it's never short of it
+        let (spend, remainder) = owed.div_mod(*offer.pay().size());
+        if quantity.is_zero() || spend.is_zero() {
+            return Err(anyhow!(
+                "[take_offer]: taking {} of offer {} would cost nothing, so the market won't fill it",
+                quantity,
+                id
+            ));
+        }
+        let spend = if remainder.is_zero() { spend } else { spend + 1 };
+        let cost = eth::with_fee(spend, self.get_fee_bps_at(pinned).await?)?;
+        let expected = AssetSwap::new(
+            self.local_asset(*offer.buy().asset(), cost)?,
+            self.local_asset(*offer.pay().asset(), quantity)?,
+        );
+        Ok((self.legacy_if_needed(self.market().buy(id, quantity)), expected))
+    }
+
     /// This is used to construct a limit order, where we want to sell `pay_amt` of `pay_gem` for at least `buy_amt` of `buy_gem`.
     /// The `pos` parameter should be `None` unless you know the new position of the order in the sorted orderbook.
     /// `pay_gem` and `buy_gem` must not be equal.
@@ -456,127 +645,155 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
         }
         let internal_position = pos.unwrap_or(U256::zero());
 
-        let tx = if self.is_legacy() {
-            self.market()
-                .method::<_, U256>(
-                    "offer",
-                    (pay_amt, pay_gem, buy_amt, buy_gem, internal_position),
-                )?
-                .legacy()
-        } else {
-            self.market().method::<_, U256>(
-                "offer",
-                (pay_amt, pay_gem, buy_amt, buy_gem, internal_position),
-            )?
-        };
-
-        Ok(tx)
+        Ok(self.legacy_if_needed(self.market().offer_with_pos(
+            pay_amt,
+            pay_gem,
+            buy_amt,
+            buy_gem,
+            internal_position,
+        )))
     }
 
     /// This constructs a limit order transaction.
     /// We want to sell `source.size()` of `source.asset()` for at least `target.size()` of `target.asset()`.
     /// `source.asset()` and `target.asset()` must not be equal.
+    /// If either side is native ETH, the order is placed through the router, which wraps and unwraps it as WETH.
     #[instrument(level = "debug", skip(self))]
     pub fn limit_order_bins(
         &self,
         source: &ChainNativeAsset,
         target: &ChainNativeAsset,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
-        self.offer(
-            *source.size(),
-            source.address()?,
-            *target.size(),
-            target.address()?,
-            None,
-        )
+        match (source.asset().is_native(), target.asset().is_native()) {
+            (true, true) => Err(anyhow!("[limit_order_bins]: can't trade native ETH for native ETH")),
+            (true, false) => self.offer_with_eth(*source.size(), *target.size(), target.address()?, None),
+            (false, true) => self.offer_for_eth(*source.size(), source.address()?, *target.size(), None),
+            (false, false) => self.offer(
+                *source.size(),
+                source.address()?,
+                *target.size(),
+                target.address()?,
+                None,
+            ),
+        }
+    }
+
+    /// This constructs a limit order transaction from an [`Order`].
+    /// The order is converted to the [`AssetSwap`] it represents on the session's chain, and placed with [`RubiconSession::offer`].
+    #[instrument(level = "debug", skip(self))]
+    pub fn place(&self, order: &Order) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
+        let swap = order.to_swap(*self.chain())?;
+        self.limit_order_bins(swap.source(), swap.target())
     }
 
     /// This constructs a limit order transaction.
-    /// We sell `base_size` worth of `base` for `quote`, at a price greater than or equal to `price`.
-    /// `price` has units `quote/base`. Both `price` and `base_size` are in human readable units, not wei.
-    /// `base` and `quote` must not be equal.
+    /// We sell `base_size` worth of `pair.base()` for `pair.quote()`, at a price greater than or equal to `price`.
+    /// `price` must be quoted on `pair` (i.e. in units of `quote/base`). `base_size` is in human readable units, not wei.
     #[instrument(level = "debug", skip(self))]
     pub fn limit_sell(
         &self,
-        base: &Asset,
-        quote: &Asset,
-        price: Decimal,
+        pair: &Pair,
+        price: &Price,
         base_size: Decimal,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
-        // what's the equivalent quote size?
-        let quote_size = base_size * price;
-        let base_bin: ChainNativeAsset = self.local_asset_human_decimal(*base, base_size)?;
-        let quote_bin: ChainNativeAsset = self.local_asset_human_decimal(*quote, quote_size)?;
-        self.limit_order_bins(&base_bin, &quote_bin)
+        self.place(&Order::new(*pair, Side::Sell, *price, base_size, SizeUnit::Base)?)
     }
 
     /// This constructs a limit order transaction.
-    /// We buy `base_size` worth of `base` for `quote`, at a price less than or equal to `price`.
-    /// `price` has units `quote/base`. Both `price` and `base_size` are in human readable units, not wei.
-    /// `base` and `quote` must not be equal.
+    /// We buy `base_size` worth of `pair.base()` for `pair.quote()`, at a price less than or equal to `price`.
+    /// `price` must be quoted on `pair` (i.e. in units of `quote/base`). `base_size` is in human readable units, not wei.
     #[instrument(level = "debug", skip(self))]
     pub fn limit_buy(
         &self,
-        base: &Asset,
-        quote: &Asset,
-        price: Decimal,
+        pair: &Pair,
+        price: &Price,
         base_size: Decimal,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
-        // what's the equivalent quote size?
-        let quote_size = base_size * price;
-        let base_bin: ChainNativeAsset = self.local_asset_human_decimal(*base, base_size)?;
-        let quote_bin: ChainNativeAsset = self.local_asset_human_decimal(*quote, quote_size)?;
-        self.limit_order_bins(&quote_bin, &base_bin)
+        self.place(&Order::new(*pair, Side::Buy, *price, base_size, SizeUnit::Base)?)
     }
 
     /// This constructs a limit order transaction.
-    /// We want to sell `quute_size` worth of `quote` for `base`, at a price greater than or equal to `price`.
-    /// `price` has units `quote/base`. Both `price` and `quote_size` are in human readable units, not wei.
-    /// `base` and `quote` must not be equal.
+    /// We want to sell `quote_size` worth of `pair.quote()` for `pair.base()`, at a price less than or equal to `price`.
+    /// `price` must be quoted on `pair` (i.e. in units of `quote/base`). `quote_size` is in human readable units, not wei.
     #[instrument(level = "debug", skip(self))]
     pub fn conj_limit_sell(
         &self,
-        base: &Asset,
-        quote: &Asset,
-        price: Decimal,
+        pair: &Pair,
+        price: &Price,
         quote_size: Decimal,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
-        // what's the equivalent base size?
-        let base_size = quote_size / price;
-        let base_bin: ChainNativeAsset = self.local_asset_human_decimal(*base, base_size)?;
-        let quote_bin: ChainNativeAsset = self.local_asset_human_decimal(*quote, quote_size)?;
-        self.limit_order_bins(&quote_bin, &base_bin)
+        self.place(&Order::new(*pair, Side::Buy, *price, quote_size, SizeUnit::Quote)?)
     }
+
     /// This constructs a limit order transaction.
-    /// We want to buy `quute_size` worth of `quote` for `base`, at a price less than or equal to `price`.
-    /// `price` has units `quote/base`. Both `price` and `quote_size` are in human readable units, not wei.
-    /// `base` and `quote` must not be equal.
+    /// We want to buy `quote_size` worth of `pair.quote()` for `pair.base()`, at a price greater than or equal to `price`.
+    /// `price` must be quoted on `pair` (i.e. in units of `quote/base`). `quote_size` is in human readable units, not wei.
     #[instrument(level = "debug", skip(self))]
     pub fn conj_limit_buy(
         &self,
-        base: &Asset,
-        quote: &Asset,
-        price: Decimal,
+        pair: &Pair,
+        price: &Price,
         quote_size: Decimal,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
-        // what's the equivalent base size?
-        let base_size = quote_size / price;
-        let base_bin: ChainNativeAsset = self.local_asset_human_decimal(*base, base_size)?;
-        let quote_bin: ChainNativeAsset = self.local_asset_human_decimal(*quote, quote_size)?;
-        self.limit_order_bins(&base_bin, &quote_bin)
+        self.place(&Order::new(*pair, Side::Sell, *price, quote_size, SizeUnit::Quote)?)
     }
 
     /// Cancels an order that's already on the Rubicon book
     #[instrument(level = "debug", skip(self))]
-    pub fn cancel(&self, order_id: U256) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
-        let tx = if self.is_legacy() {
-            self.market()
-                .method::<_, U256>("cancel", (order_id,))?
-                .legacy()
-        } else {
-            self.market().method::<_, U256>("cancel", (order_id,))?
-        };
-        Ok(tx)
+    pub fn cancel(&self, order_id: U256) -> Result<ContractCall<SignerMiddleware<M,S>, bool>> {
+        Ok(self.legacy_if_needed(self.market().cancel(order_id)))
+    }
+
+    /*
+     * The v1.3 market also has a `bytes32` id API: `make`/`take`/`kill`/`bump`.
+     * These are thin wrappers around `offer`/`buy`/`cancel`, but they take amounts as `uint128`, so anything bigger is rejected here
+     * rather than being truncated by the ABI encoder (or reverting on chain).
+     */
+
+    /// Places a limit order selling `pay_amt` of `pay_gem` for at least `buy_amt` of `buy_gem`, and returns its id as a `bytes32`.
+    /// Unlike [`RubiconSession::offer`], the order goes on the unsorted list rather than being matched against the book. `pay_gem` and `buy_gem` must not be equal.
+    #[instrument(level = "debug", skip(self))]
+    pub fn make(
+        &self,
+        pay_gem: Address,
+        buy_gem: Address,
+        pay_amt: U256,
+        buy_amt: U256,
+    ) -> Result<ContractCall<SignerMiddleware<M,S>, [u8; 32]>> {
+        if pay_gem == buy_gem {
+            return Err(anyhow!(
+                "[make]: pay_gem and buy_gem are the same! ({}=={})",
+                pay_gem,
+                buy_gem
+            ));
+        }
+        Ok(self.legacy_if_needed(self.market().make(
+            pay_gem,
+            buy_gem,
+            to_uint128(pay_amt, "make", "pay_amt")?,
+            to_uint128(buy_amt, "make", "buy_amt")?,
+        )))
+    }
+
+    /// Buys up to `max_take_amount` of what the offer `id` is selling. Reverts if the offer doesn't exist.
+    #[instrument(level = "debug", skip(self))]
+    pub fn take(&self, id: OfferId, max_take_amount: U256) -> Result<ContractCall<SignerMiddleware<M,S>, ()>> {
+        Ok(self.legacy_if_needed(self.market().take(
+            id.to_bytes(),
+            to_uint128(max_take_amount, "take", "max_take_amount")?,
+        )))
+    }
+
+    /// Cancels the offer `id`, which must belong to the sender.
+    #[instrument(level = "debug", skip(self))]
+    pub fn kill(&self, id: OfferId) -> Result<ContractCall<SignerMiddleware<M,S>, ()>> {
+        Ok(self.legacy_if_needed(self.market().kill(id.to_bytes())))
+    }
+
+    /// Re-emits the `LogBump` event for the offer `id`, so that indexers that missed it can pick it up again.
+    #[instrument(level = "debug", skip(self))]
+    pub fn bump(&self, id: OfferId) -> Result<ContractCall<SignerMiddleware<M,S>, ()>> {
+        Ok(self.legacy_if_needed(self.market().bump(id.to_bytes())))
     }
 
     // RUBICON BATH PAIR FUNCTIONS
@@ -612,25 +829,14 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
         ask_den: U256,
         bid_num: U256,
         bid_den: U256,
-    ) -> Result<ContractCall<SignerMiddleware<M,S>, ()>> {
-        let tx = match self.is_legacy() {
-            true => self
-                .pair()
-                .method::<_, ()>(
-                    "placeMarketMakingTrades",
-                    (token_pair, ask_num, ask_den, bid_num, bid_den),
-                )?
-                .legacy(),
-            false => self.pair().method::<_, ()>(
-                "placeMarketMakingTrades",
-                (token_pair, ask_num, ask_den, bid_num, bid_den),
-            )?,
-        };
-        Ok(tx)
-    }
-
-    // INCOMPLETE: shouldn't this return a vector of Order IDs?
+    ) -> Result<ContractCall<SignerMiddleware<M,S>, U256>> {
+        Ok(self.legacy_if_needed(self.pair().place_market_making_trades(
+            token_pair, ask_num, ask_den, bid_num, bid_den,
+        )))
+    }
+
     /// This returns a [`ContractCall`] that will place a series of paired market making trades.
+    /// The BathPair doesn't return the new offer ids, so get them from the receipt with [`RubiconSession::trade_outcome`].
     #[instrument(level = "debug", skip(self))]
     pub fn batch_place_market_making_trades(
         &self,
@@ -647,20 +853,9 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
                 "[batch_place_market_making_trades]: mismatch in input vectors!"
             ))
         } else {
-            let tx = match self.is_legacy() {
-                true => self
-                    .pair()
-                    .method::<_, ()>(
-                        "batchMarketMakingTrades",
-                        (token_pair, ask_nums, ask_dems, bid_nums, bid_dems),
-                    )?
-                    .legacy(),
-                false => self.pair().method::<_, ()>(
-                    "batchMarketMakingTrades",
-                    (token_pair, ask_nums, ask_dems, bid_nums, bid_dems),
-                )?,
-            };
-            Ok(tx)
+            Ok(self.legacy_if_needed(self.pair().batch_market_making_trades(
+                token_pair, ask_nums, ask_dems, bid_nums, bid_dems,
+            )))
         }
     }
 
@@ -678,20 +873,9 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
         bid_num: U256,
         bid_dem: U256,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, ()>> {
-        let tx = match self.is_legacy() {
-            true => self
-                .pair()
-                .method::<_, ()>(
-                    "requote",
-                    (order_id, token_pair, ask_num, ask_dem, bid_num, bid_dem),
-                )?
-                .legacy(),
-            false => self.pair().method::<_, ()>(
-                "requote",
-                (order_id, token_pair, ask_num, ask_dem, bid_num, bid_dem),
-            )?,
-        };
-        Ok(tx)
+        Ok(self.legacy_if_needed(self.pair().requote(
+            order_id, token_pair, ask_num, ask_dem, bid_num, bid_dem,
+        )))
     }
 
     // requotes a series of paired bids and asks
@@ -709,62 +893,41 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
         bid_nums: Vec<U256>,
         bid_dems: Vec<U256>,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, ()>> {
-        assert!(
-            !(ask_nums.len() == ask_dems.len()
-                && bid_nums.len() == bid_dems.len()
-                && ask_nums.len() == ids.len())
-        );
-
-        let tx = match self.is_legacy() {
-            true => self
-                .pair()
-                .method::<_, ()>(
-                    "batchRequoteOffers",
-                    (ids, token_pair, ask_nums, ask_dems, bid_nums, bid_dems),
-                )?
-                .legacy(),
-            false => self.pair().method::<_, ()>(
-                "batchRequoteOffers",
-                (ids, token_pair, ask_nums, ask_dems, bid_nums, bid_dems),
-            )?,
-        };
-        Ok(tx)
+        // every id is requoted with its own ask and bid
+        if [ask_nums.len(), ask_dems.len(), bid_nums.len(), bid_dems.len()]
+            .iter()
+            .any(|x| *x != ids.len())
+        {
+            return Err(anyhow!(
+                "[batch_requote_offers]: mismatch in input vectors! got {} ids, {}/{} ask terms and {}/{} bid terms",
+                ids.len(),
+                ask_nums.len(),
+                ask_dems.len(),
+                bid_nums.len(),
+                bid_dems.len()
+            ));
+        }
+        Ok(self.legacy_if_needed(self.pair().batch_requote_offers(
+            ids, token_pair, ask_nums, ask_dems, bid_nums, bid_dems,
+        )))
     }
 
     // doesn't have any output
     /** This returns a [`ContractCall`] that cancels an outstanding strategist orders. */
     #[instrument(level = "debug", skip(self))]
     pub fn scrub_strategist_trade(&self, trade_id: U256) -> Result<ContractCall<SignerMiddleware<M,S>, ()>> {
-        let tx = match self.is_legacy() {
-            true => self
-                .pair()
-                .method::<_, ()>("scrubStrategistTrade", trade_id)?
-                .legacy(),
-            false => self
-                .pair()
-                .method::<_, ()>("scrubStrategistTrade", trade_id)?,
-        };
-        Ok(tx)
+        Ok(self.legacy_if_needed(self.pair().scrub_strategist_trade(trade_id)))
     }
 
     // doesn't have any output
     /** This returns a [`ContractCall`] that cancels a list of outstanding strategist orders.  */
     #[instrument(level = "debug", skip(self))]
     pub fn scrub_strategist_trades(&self, trade_ids: Vec<U256>) -> Result<ContractCall<SignerMiddleware<M,S>, ()>> {
-        let tx = match self.is_legacy() {
-            true => self
-                .pair()
-                .method::<_, ()>("scrubStrategistTrades", trade_ids)?
-                .legacy(),
-            false => self
-                .pair()
-                .method::<_, ()>("scrubStrategistTrades", trade_ids)?,
-        };
-        Ok(tx)
-    }
-
-    // in an old ethers rust UV3 project, I used a u32 for the fee type......
-    // let's fucking hope this works
+        Ok(self.legacy_if_needed(self.pair().scrub_strategist_trades(trade_ids)))
+    }
+
+    // the pool fee is a uint24, which ethers represents as a u32
+    #[allow(clippy::too_many_arguments)]
     #[instrument(level = "debug", skip(self))]
     pub fn tailoff(
         &self,
@@ -776,36 +939,15 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
         hurdle: U256,
         pool_fee: u32,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, ()>> {
-        let tx = match self.is_legacy() {
-            true => self
-                .pair()
-                .method::<_, ()>(
-                    "tailOff",
-                    (
-                        target_pool,
-                        token_to_handle,
-                        target_token,
-                        strat_util,
-                        amount,
-                        hurdle,
-                        pool_fee,
-                    ),
-                )?
-                .legacy(),
-            false => self.pair().method::<_, ()>(
-                "tailOff",
-                (
-                    target_pool,
-                    token_to_handle,
-                    target_token,
-                    strat_util,
-                    amount,
-                    hurdle,
-                    pool_fee,
-                ),
-            )?,
-        };
-        Ok(tx)
+        Ok(self.legacy_if_needed(self.pair().tail_off(
+            target_pool,
+            token_to_handle,
+            target_token,
+            strat_util,
+            amount,
+            hurdle,
+            pool_fee,
+        )))
     }
 
     // doesn't have any output
@@ -819,20 +961,10 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
         hurdle: U256,
         strat_util: Address,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, ()>> {
-        let tx = match self.is_legacy() {
-            true => self
-                .pair()
-                .method::<_, ()>(
-                    "tailOffMulti",
-                    (target_pool, amount, assets, fees, hurdle, strat_util),
-                )?
-                .legacy(),
-            false => self.pair().method::<_, ()>(
-                "tailOffMulti",
-                (target_pool, amount, assets, fees, hurdle, strat_util),
-            )?,
-        };
-        Ok(tx)
+        Ok(self.legacy_if_needed(
+            self.pair()
+                .tail_off_multi(target_pool, amount, assets, fees, hurdle, strat_util),
+        ))
     }
 
     // this has no output
@@ -844,30 +976,181 @@ impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMi
         underlying_asset: Address,
         underlying_quote: Address,
     ) -> Result<ContractCall<SignerMiddleware<M,S>, ()>> {
-        let tx = match self.is_legacy() {
-            true => self
-                .pair()
-                .method::<_, ()>(
-                    "rebalancePair",
-                    (
-                        asset_rebal_amt,
-                        quote_rebal_amt,
-                        underlying_asset,
-                        underlying_quote,
-                    ),
-                )?
-                .legacy(),
-            false => self.pair().method::<_, ()>(
-                "rebalancePair",
-                (
-                    asset_rebal_amt,
-                    quote_rebal_amt,
-                    underlying_asset,
-                    underlying_quote,
-                ),
-            )?,
-        };
-        Ok(tx)
+        Ok(self.legacy_if_needed(self.pair().rebalance_pair(
+            asset_rebal_amt,
+            quote_rebal_amt,
+            underlying_asset,
+            underlying_quote,
+        )))
     }
 }
 
+// the market's make/take API takes amounts as a uint128
+fn to_uint128(amount: U256, func: &str, name: &str) -> Result<u128> {
+    u128::try_from(amount).map_err(|_| anyhow!("[{}]: {} ({}) doesn't fit in a uint128", func, name, amount))
+}
+
+// how long to wait before the next attempt after `failures` failures in a row: `initial`, doubling each time, up to `max`
+pub(crate) fn backoff(initial: Duration, max: Duration, failures: usize) -> Duration {
+    let doublings = failures.saturating_sub(1).min(31) as u32;
+    initial.saturating_mul(1 << doublings).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::{call_result, mocked_signer_session, respond};
+    use super::*;
+    use crate::market::Slippage;
+    use ethers::abi::Token;
+    use futures::executor::block_on;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn batch_requote_offers_needs_an_ask_and_a_bid_per_id() {
+        let (session, _mock) = mocked_signer_session();
+        let pair = [Address::repeat_byte(1), Address::repeat_byte(2)];
+        let terms = |n: usize| vec![U256::one(); n];
+        assert!(session
+            .batch_requote_offers(terms(2), pair, terms(2), terms(2), terms(2), terms(2))
+            .is_ok());
+        assert!(session
+            .batch_requote_offers(terms(2), pair, terms(1), terms(1), terms(2), terms(2))
+            .is_err());
+        assert!(session
+            .batch_requote_offers(terms(2), pair, terms(2), terms(2), terms(2), terms(1))
+            .is_err());
+        assert!(session
+            .batch_requote_offers(terms(3), pair, terms(2), terms(2), terms(2), terms(2))
+            .is_err());
+    }
+
+    fn weth(wei: U256) -> ChainNativeAsset {
+        ChainNativeAsset::new(Chain::Optimism, Asset::Weth, wei).unwrap()
+    }
+
+    #[test]
+    fn market_sell_with_slippage_bounds_the_fill_by_the_quote() {
+        let (session, mock) = mocked_signer_session();
+        let one_weth = weth(U256::exp10(18));
+        // the book gives 1600 USDC for it, so 1% of slippage is 1584
+        respond(&mock, vec![call_result(&[Token::Uint(U256::from(1_600_000_000))])]);
+        let call = block_on(session.market_sell_with_slippage(&one_weth, &Asset::Usdc, &Slippage::Bps(100))).unwrap();
+        let expected = session
+            .sell_all_amount(
+                one_weth.address().unwrap(),
+                *one_weth.size(),
+                Asset::Usdc.to_address(session.chain()).unwrap(),
+                U256::from(1_584_000_000),
+            )
+            .unwrap();
+        assert_eq!(call.tx.data(), expected.tx.data());
+
+        // a limit of 1700 is already worse than what the book gives
+        let limit = Slippage::Limit(Price::new(Pair::new(Asset::Weth, Asset::Usdc), Decimal::new(1700, 0)).unwrap());
+        respond(&mock, vec![call_result(&[Token::Uint(U256::from(1_600_000_000))])]);
+        assert!(block_on(session.market_sell_with_slippage(&one_weth, &Asset::Usdc, &limit)).is_err());
+    }
+
+    #[test]
+    fn market_buy_with_slippage_bounds_the_cost_by_the_quote() {
+        let (session, mock) = mocked_signer_session();
+        let one_weth = weth(U256::exp10(18));
+        // the book wants 1600 USDC for it, so 1% of slippage is 1616
+        respond(&mock, vec![call_result(&[Token::Uint(U256::from(1_600_000_000))])]);
+        let call = block_on(session.market_buy_with_slippage(&Asset::Usdc, &one_weth, &Slippage::Bps(100))).unwrap();
+        let expected = session
+            .buy_all_amount(
+                one_weth.address().unwrap(),
+                *one_weth.size(),
+                Asset::Usdc.to_address(session.chain()).unwrap(),
+                U256::from(1_616_000_000),
+            )
+            .unwrap();
+        assert_eq!(call.tx.data(), expected.tx.data());
+
+        // a limit of 1500 is already worse than what the book wants
+        let limit = Slippage::Limit(Price::new(Pair::new(Asset::Weth, Asset::Usdc), Decimal::new(1500, 0)).unwrap());
+        respond(&mock, vec![call_result(&[Token::Uint(U256::from(1_600_000_000))])]);
+        assert!(block_on(session.market_buy_with_slippage(&Asset::Usdc, &one_weth, &limit)).is_err());
+
+        // and a quote on another chain is refused before anything is asked of the book
+        let elsewhere = ChainNativeAsset::new(Chain::Mainnet, Asset::Weth, U256::one()).unwrap();
+        assert!(block_on(session.market_buy_with_slippage(&Asset::Usdc, &elsewhere, &Slippage::Bps(100))).is_err());
+    }
+
+    #[test]
+    fn make_and_take_amounts_must_fit_in_a_uint128() {
+        let (session, _mock) = mocked_signer_session();
+        let (pay_gem, buy_gem) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let max = U256::from(u128::MAX);
+        let too_much = max + 1;
+
+        assert!(session.make(pay_gem, buy_gem, max, max).is_ok());
+        assert!(session.make(pay_gem, buy_gem, too_much, max).is_err());
+        assert!(session.make(pay_gem, buy_gem, max, too_much).is_err());
+        assert!(session.make(pay_gem, pay_gem, max, max).is_err());
+
+        let id = OfferId::new(U256::MAX);
+        let take = session.take(id, max).unwrap();
+        // the id goes in as its bytes32, right after the selector
+        assert_eq!(&take.tx.data().unwrap()[4..36], &id.to_bytes());
+        assert!(session.take(id, too_much).is_err());
+        assert!(session.take(id, U256::MAX).is_err());
+    }
+
+    // the responses to take_offer's reads: the block number, isActive, offers(id) for an offer selling `pay_amt` WETH for `buy_amt` USDC, and the fee
+    fn offer_responses(
+        session: &RubiconSession<impl Middleware + Clone>,
+        pay_amt: u128,
+        buy_amt: u128,
+        fee_bps: u64,
+    ) -> Vec<serde_json::Value> {
+        vec![
+            serde_json::json!("0x64"),
+            call_result(&[Token::Bool(true)]),
+            call_result(&[
+                Token::Uint(U256::from(pay_amt)),
+                Token::Address(Asset::Weth.to_address(session.chain()).unwrap()),
+                Token::Uint(U256::from(buy_amt)),
+                Token::Address(Asset::Usdc.to_address(session.chain()).unwrap()),
+                Token::Address(Address::repeat_byte(0xa)),
+                Token::Uint(U256::from(1_700_000_000)),
+            ]),
+            call_result(&[Token::Uint(U256::from(fee_bps))]),
+        ]
+    }
+
+    #[test]
+    fn taking_an_offer_costs_its_price_rounded_up_plus_the_fee() {
+        let (session, mock) = mocked_signer_session();
+        // 3 WETH wei for 1000 USDC wei, so a WETH wei costs 333.33.. USDC wei
+        respond(&mock, offer_responses(&session, 3, 1_000, 0));
+        let (_, expected) = block_on(session.take_offer(U256::from(7), &weth(U256::one()))).unwrap();
+        assert_eq!(*expected.source().size(), U256::from(334));
+        assert_eq!(*expected.target().size(), U256::one());
+
+        // an exact price isn't rounded, and the fee goes on top, rounded down
+        respond(&mock, offer_responses(&session, 2, 1_000_001, 20));
+        let (_, expected) = block_on(session.take_offer(U256::from(7), &weth(U256::one()))).unwrap();
+        // 500_000.5 rounds up to 500_001, and 20 bps of that is 1_000.002
+        assert_eq!(*expected.source().size(), U256::from(501_001));
+
+        // asking for more than the offer has takes all of it, at exactly its price
+        respond(&mock, offer_responses(&session, 3, 1_000, 20));
+        let (_, expected) = block_on(session.take_offer(U256::from(7), &weth(U256::from(10)))).unwrap();
+        assert_eq!(*expected.source().size(), U256::from(1_002));
+        assert_eq!(*expected.target().size(), U256::from(3));
+    }
+
+    #[test]
+    fn taking_an_offer_for_nothing_is_refused() {
+        let (session, mock) = mocked_signer_session();
+        // a WETH wei is worth less than a USDC wei, so the market would spend nothing on it
+        respond(&mock, offer_responses(&session, 1_000, 3, 0));
+        assert!(block_on(session.take_offer(U256::from(7), &weth(U256::one()))).is_err());
+        // and the offer sells WETH, not USDC
+        respond(&mock, offer_responses(&session, 1_000, 3, 0));
+        let usdc = ChainNativeAsset::new(Chain::Optimism, Asset::Usdc, U256::one()).unwrap();
+        assert!(block_on(session.take_offer(U256::from(7), &usdc)).is_err());
+    }
+}
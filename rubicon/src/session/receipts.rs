@@ -0,0 +1,335 @@
+use super::RubiconSession;
+use crate::events::{AidEvent, MarketEvent, PairEvent, RouterEvent, RubiconEvent};
+use crate::market::{Fee, Fill, Offer, TradeOutcome};
+use anyhow::{anyhow, Result};
+use ethers::{
+    core::types::{Address, TransactionReceipt, U256},
+    providers::Middleware,
+};
+use numeraire::prelude::*;
+use tracing::{instrument, warn};
+
+/*
+ * Turning receipts back into trades.
+ * The market's functions return offer ids and amounts, but a sent transaction only gives us a receipt,
+ * so everything here is pieced together from the events the protocol's contracts emitted.
+ */
+impl<M: Middleware + Clone + 'static> RubiconSession<M> {
+    /// Works out what a mined transaction did on the market: the offers it made, the offers it filled, the fees it paid, the router swaps it made, and its gas.
+    /// Only logs from the session's deployment are looked at, so this works for any transaction that touches the market,
+    /// whether it was sent to the market itself, the router, the BathPair (e.g. [`RubiconSession::batch_place_market_making_trades`]) or MarketAid.
+    /// Events involving a token that the session's registry doesn't know are left out, and the token is listed in [`TradeOutcome::unresolved_tokens`].
+    /// Receipts from before London (and from some chains) don't have an effective gas price, in which case the transaction's gas price is fetched instead.
+    ///
+    /// ```ignore
+    /// let receipt = session.offer(pay_amt, pay_gem, buy_amt, buy_gem, None)?.send().await?.await?.unwrap();
+    /// let outcome = session.trade_outcome(&receipt).await?;
+    /// println!("placed {:?}, filled {:?}", outcome.offer_ids(), outcome.fills());
+    /// ```
+    #[instrument(level = "debug", skip_all)]
+    pub async fn trade_outcome(&self, receipt: &TransactionReceipt) -> Result<TradeOutcome> {
+        let mut made = Vec::new();
+        let mut fills = Vec::new();
+        let mut trades = Vec::new();
+        let mut fees = Vec::new();
+        let mut swaps = Vec::new();
+        let mut strategist_trades = Vec::new();
+        let mut unresolved = Vec::new();
+        // a token the registry doesn't know only costs us the events that involve it, not the whole outcome
+        let mut resolve = |gem: Address, amount: U256| match self.resolve(gem, amount, "trade_outcome") {
+            Ok(x) => Some(x),
+            Err(e) => {
+                warn!("{}, leaving its events out of the outcome", e);
+                if !unresolved.contains(&gem) {
+                    unresolved.push(gem);
+                }
+                None
+            }
+        };
+        for event in self.event_decoder().decode_receipt(receipt) {
+            match event.event() {
+                RubiconEvent::Market(MarketEvent::Make(x)) => {
+                    if let (Some(pay), Some(buy)) =
+                        (resolve(x.pay_gem, x.pay_amt.into()), resolve(x.buy_gem, x.buy_amt.into()))
+                    {
+                        made.push(Offer::new(x.offer_id(), x.maker, pay, buy, x.timestamp));
+                    }
+                }
+                RubiconEvent::Market(MarketEvent::Take(x)) => {
+                    if let (Some(pay), Some(buy)) =
+                        (resolve(x.pay_gem, x.take_amt.into()), resolve(x.buy_gem, x.give_amt.into()))
+                    {
+                        fills.push(Fill::new(x.offer_id(), x.maker, x.taker, pay, buy));
+                    }
+                }
+                RubiconEvent::Market(MarketEvent::Trade(x)) => {
+                    if let (Some(pay), Some(buy)) = (resolve(x.pay_gem, x.pay_amt), resolve(x.buy_gem, x.buy_amt)) {
+                        trades.push(AssetSwap::new(pay, buy));
+                    }
+                }
+                RubiconEvent::Market(MarketEvent::FeeTake(x)) => {
+                    if let Some(amount) = resolve(x.asset, x.fee_amt) {
+                        fees.push(Fee::new(x.offer_id(), x.taker, x.fee_to, amount));
+                    }
+                }
+                RubiconEvent::Router(RouterEvent::Swap(x)) => {
+                    if let (Some(input), Some(fill)) =
+                        (resolve(x.input_erc20, x.input_amount), resolve(x.target_erc20, x.realized_fill))
+                    {
+                        swaps.push(AssetSwap::new(input, fill));
+                    }
+                }
+                RubiconEvent::Pair(PairEvent::StrategistTrade(x)) | RubiconEvent::Aid(AidEvent::StrategistTrade(x)) => {
+                    strategist_trades.push(x.strategist_trade_id)
+                }
+                _ => {}
+            }
+        }
+        let gas_used = receipt
+            .gas_used
+            .ok_or_else(|| anyhow!("[trade_outcome]: the receipt doesn't say how much gas was used"))?;
+        let gas_price = match receipt.effective_gas_price {
+            Some(x) => x,
+            None => self
+                ._internal_middleware
+                .get_transaction(receipt.transaction_hash)
+                .await
+                .map_err(|e| anyhow!("[trade_outcome]: eth_getTransactionByHash failed: {}", e))?
+                .and_then(|x| x.gas_price)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "[trade_outcome]: neither the receipt nor transaction {:?} say what it paid for gas",
+                        receipt.transaction_hash
+                    )
+                })?,
+        };
+        Ok(TradeOutcome::new(
+            receipt.transaction_hash,
+            receipt.block_number.map(|x| x.as_u64()),
+            receipt.status.is_none_or(|x| !x.is_zero()),
+            made,
+            fills,
+            trades,
+            fees,
+            swaps,
+            strategist_trades,
+            unresolved,
+            gas_used,
+            self.local_asset(Asset::Eth, gas_price)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{FeeTake, LogMake, LogStrategistTrade, LogSwap, LogTake, LogTrade};
+    use crate::session::mock::mocked_session;
+    use ethers::abi::{encode, Token};
+    use ethers::contract::EthEvent;
+    use ethers::core::types::{Log, Transaction, H256, U64};
+    use futures::executor::block_on;
+
+    fn receipt(effective_gas_price: Option<U256>) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: H256::repeat_byte(7),
+            block_number: Some(U64::from(100)),
+            status: Some(U64::one()),
+            gas_used: Some(U256::from(21_000)),
+            effective_gas_price,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn gas_price_comes_from_the_receipt_when_it_has_one() {
+        // nothing is mocked, so any request would fail
+        let (session, _mock) = mocked_session();
+        let outcome = block_on(session.trade_outcome(&receipt(Some(U256::from(3))))).unwrap();
+        assert_eq!(*outcome.effective_gas_price().size(), U256::from(3));
+        assert_eq!(*outcome.gas_cost().unwrap().size(), U256::from(63_000));
+    }
+
+    #[test]
+    fn gas_price_falls_back_to_the_transaction() {
+        let (session, mock) = mocked_session();
+        mock.push(Transaction {
+            hash: H256::repeat_byte(7),
+            gas_price: Some(U256::from(5)),
+            ..Default::default()
+        })
+        .unwrap();
+        let outcome = block_on(session.trade_outcome(&receipt(None))).unwrap();
+        assert_eq!(*outcome.effective_gas_price().size(), U256::from(5));
+
+        mock.push::<Option<Transaction>, _>(None).unwrap();
+        assert!(block_on(session.trade_outcome(&receipt(None))).is_err());
+    }
+
+    // a log as `emitter` would have emitted event `E`: its signature, then its indexed fields as topics, with the rest abi-encoded as data
+    fn log<E: EthEvent>(emitter: Address, indexed: &[H256], data: &[Token]) -> Log {
+        let mut topics = vec![E::signature()];
+        topics.extend_from_slice(indexed);
+        Log {
+            address: emitter,
+            topics,
+            data: encode(data).into(),
+            block_number: Some(U64::from(100)),
+            block_hash: Some(H256::repeat_byte(1)),
+            transaction_hash: Some(H256::repeat_byte(7)),
+            log_index: Some(U256::from(0)),
+            ..Default::default()
+        }
+    }
+
+    fn word(x: u64) -> H256 {
+        H256::from_low_u64_be(x)
+    }
+
+    fn uint(x: u64) -> Token {
+        Token::Uint(U256::from(x))
+    }
+
+    #[test]
+    fn every_event_in_the_receipt_ends_up_in_the_outcome() {
+        let (session, _mock) = mocked_session();
+        let deployment = session.deployment().clone();
+        let usdc = Asset::Usdc.to_address(session.chain()).unwrap();
+        let weth = Asset::Weth.to_address(session.chain()).unwrap();
+        let (maker, taker, fee_to, pair) = (
+            Address::repeat_byte(0xa),
+            Address::repeat_byte(0xb),
+            Address::repeat_byte(0xc),
+            H256::repeat_byte(0xd),
+        );
+
+        let mut receipt = receipt(Some(U256::from(3)));
+        receipt.logs = vec![
+            // a fill of offer 5, which pays out 1 WETH for 1500 USDC
+            log::<LogTake>(
+                deployment.market(),
+                &[pair, H256::from(maker), H256::from(taker)],
+                &[
+                    Token::FixedBytes(word(5).as_bytes().to_vec()),
+                    Token::Address(weth),
+                    Token::Address(usdc),
+                    uint(1_000_000_000_000_000_000),
+                    uint(1_500_000_000),
+                    uint(1_700_000_000),
+                ],
+            ),
+            log::<LogTrade>(
+                deployment.market(),
+                &[H256::from(weth), H256::from(usdc)],
+                &[uint(1_000_000_000_000_000_000), uint(1_500_000_000)],
+            ),
+            log::<FeeTake>(
+                deployment.market(),
+                &[word(5), pair, H256::from(taker)],
+                &[Token::Address(usdc), Token::Address(fee_to), uint(300_000), uint(1_700_000_000)],
+            ),
+            // the rest of the order, left on the book as offer 6
+            log::<LogMake>(
+                deployment.market(),
+                &[word(6), pair, H256::from(maker)],
+                &[
+                    Token::Address(usdc),
+                    Token::Address(weth),
+                    uint(500_000_000),
+                    uint(300_000_000_000_000_000),
+                    uint(1_700_000_000),
+                ],
+            ),
+            log::<LogSwap>(
+                deployment.router(),
+                &[pair],
+                &[
+                    uint(1_500_300_000),
+                    Token::Address(usdc),
+                    uint(990_000_000_000_000_000),
+                    Token::Address(weth),
+                    uint(1_000_000_000_000_000_000),
+                    Token::Address(taker),
+                ],
+            ),
+            log::<LogStrategistTrade>(
+                deployment.pair(),
+                &[],
+                &[
+                    uint(42),
+                    Token::FixedBytes(word(6).as_bytes().to_vec()),
+                    Token::FixedBytes(word(7).as_bytes().to_vec()),
+                    Token::Address(weth),
+                    Token::Address(usdc),
+                    uint(1_700_000_000),
+                    Token::Address(maker),
+                ],
+            ),
+            // a transfer from some other contract, which isn't ours to decode
+            log::<LogTrade>(Address::repeat_byte(0xee), &[H256::from(weth), H256::from(usdc)], &[uint(1), uint(1)]),
+        ];
+
+        let outcome = block_on(session.trade_outcome(&receipt)).unwrap();
+        let local = |asset: Asset, amount: u64| session.local_asset(asset, U256::from(amount)).unwrap();
+
+        assert_eq!(outcome.transaction_hash(), H256::repeat_byte(7));
+        assert_eq!(outcome.block_number(), Some(100));
+        assert!(outcome.success());
+        assert_eq!(
+            outcome.made(),
+            &[Offer::new(
+                U256::from(6),
+                maker,
+                local(Asset::Usdc, 500_000_000),
+                local(Asset::Weth, 300_000_000_000_000_000),
+                1_700_000_000
+            )]
+        );
+        assert_eq!(outcome.offer_ids(), vec![U256::from(6)]);
+        assert_eq!(
+            outcome.fills(),
+            &[Fill::new(
+                U256::from(5),
+                maker,
+                taker,
+                local(Asset::Weth, 1_000_000_000_000_000_000),
+                local(Asset::Usdc, 1_500_000_000)
+            )]
+        );
+        assert_eq!(outcome.trades().len(), 1);
+        assert_eq!(outcome.trades()[0].source(), &local(Asset::Weth, 1_000_000_000_000_000_000));
+        assert_eq!(outcome.trades()[0].target(), &local(Asset::Usdc, 1_500_000_000));
+        assert_eq!(
+            outcome.fees(),
+            &[Fee::new(U256::from(5), taker, fee_to, local(Asset::Usdc, 300_000))]
+        );
+        assert_eq!(outcome.swaps().len(), 1);
+        assert_eq!(outcome.swaps()[0].source(), &local(Asset::Usdc, 1_500_300_000));
+        assert_eq!(outcome.swaps()[0].target(), &local(Asset::Weth, 1_000_000_000_000_000_000));
+        assert_eq!(outcome.strategist_trades(), &[U256::from(42)]);
+        assert!(outcome.unresolved_tokens().is_empty());
+        assert_eq!(outcome.gas_used(), U256::from(21_000));
+        assert_eq!(*outcome.effective_gas_price().size(), U256::from(3));
+    }
+
+    #[test]
+    fn an_unknown_token_only_drops_the_events_that_involve_it() {
+        let (session, _mock) = mocked_session();
+        let market = session.deployment().market();
+        let usdc = Asset::Usdc.to_address(session.chain()).unwrap();
+        let weth = Asset::Weth.to_address(session.chain()).unwrap();
+        let unknown = Address::repeat_byte(0x99);
+
+        let mut receipt = receipt(Some(U256::from(3)));
+        receipt.logs = vec![
+            log::<LogTrade>(market, &[H256::from(unknown), H256::from(usdc)], &[uint(1), uint(2)]),
+            log::<LogTrade>(market, &[H256::from(weth), H256::from(usdc)], &[uint(3), uint(4)]),
+            log::<LogTrade>(market, &[H256::from(usdc), H256::from(unknown)], &[uint(5), uint(6)]),
+        ];
+
+        let outcome = block_on(session.trade_outcome(&receipt)).unwrap();
+        assert_eq!(outcome.trades().len(), 1);
+        assert_eq!(*outcome.trades()[0].source().size(), U256::from(3));
+        assert_eq!(outcome.unresolved_tokens(), &[unknown]);
+    }
+}
@@ -0,0 +1,150 @@
+use super::{book::settlement_pair, RubiconSession};
+use crate::market::{BookLevel, OrderBook};
+use anyhow::Result;
+use ethers::{core::types::U256, providers::Middleware};
+use numeraire::prelude::*;
+use tracing::instrument;
+
+/*
+ * The RubiconRouter has view functions that walk the market's book on chain,
+ * so a snapshot of the top of the book costs a single `eth_call` instead of one per offer.
+ */
+impl<M: Middleware + Clone + 'static> RubiconSession<M> {
+    /// Takes a snapshot of the top `depth` levels on each side of the book for `pair` with a single call to the router's `getBookFromPair`.
+    /// This is the same book as [`RubiconSession::order_book`], without the block number.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn router_order_book(&self, pair: &Pair, depth: usize) -> Result<OrderBook> {
+        let pair = settlement_pair(pair, "router_order_book")?;
+        let (asks, bids, _) = self
+            .router()
+            .get_book_from_pair(
+                pair.base().to_address(self.chain())?,
+                pair.quote().to_address(self.chain())?,
+                U256::from(depth),
+            )
+            .call()
+            .await?;
+        let bids = self.router_levels(&pair, pair.quote(), pair.base(), &bids)?;
+        let asks = self.router_levels(&pair, pair.base(), pair.quote(), &asks)?;
+        OrderBook::new(pair, bids, asks, None)
+    }
+
+    /// Returns the best level on one side of the book for `pair` with a single call to the router's `getBestOfferAndInfo`,
+    /// where [`Side::Buy`] is the best bid and [`Side::Sell`] is the best ask. Returns `None` if that side of the book is empty.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn router_best_offer(&self, pair: &Pair, side: Side) -> Result<Option<BookLevel>> {
+        let pair = settlement_pair(pair, "router_best_offer")?;
+        let (sell, buy) = match side {
+            Side::Buy => (pair.quote(), pair.base()),
+            Side::Sell => (pair.base(), pair.quote()),
+        };
+        let (id, pay_amt, pay_gem, buy_amt, buy_gem) = self
+            .router()
+            .get_best_offer_and_info(sell.to_address(self.chain())?, buy.to_address(self.chain())?)
+            .call()
+            .await?;
+        if id.is_zero() || pay_gem.is_zero() {
+            return Ok(None);
+        }
+        let swap = AssetSwap::new(
+            self.resolve(pay_gem, pay_amt, "router_best_offer")?,
+            self.resolve(buy_gem, buy_amt, "router_best_offer")?,
+        );
+        Ok(Some(BookLevel::from_swap(id, &swap, &pair)?))
+    }
+
+    // the router returns each side as `[pay_amt, buy_amt, id]` triples, padded out to the requested depth with zeroes
+    fn router_levels(&self, pair: &Pair, pay: &Asset, buy: &Asset, triples: &[[U256; 3]]) -> Result<Vec<BookLevel>> {
+        triples
+            .iter()
+            .take_while(|[pay_amt, _, id]| !id.is_zero() && !pay_amt.is_zero())
+            .map(|[pay_amt, buy_amt, id]| {
+                let swap = AssetSwap::new(self.local_asset(*pay, *pay_amt)?, self.local_asset(*buy, *buy_amt)?);
+                BookLevel::for_snapshot(*id, &swap, pair)
+            })
+            .filter_map(Result::transpose)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::mock::{call_result, mocked_session, respond};
+    use ethers::abi::Token;
+    use ethers::core::types::Chain;
+    use futures::executor::block_on;
+
+    const ETH: u128 = 1_000_000_000_000_000_000;
+    const USDC: u128 = 1_000_000;
+
+    fn pair() -> Pair {
+        Pair::new(Asset::Weth, Asset::Usdc)
+    }
+
+    fn uint(x: u128) -> Token {
+        Token::Uint(U256::from(x))
+    }
+
+    // one side of `getBookFromPair`, as `[pay_amt, buy_amt, id]` triples
+    fn side(triples: &[(u128, u128, u128)]) -> Token {
+        Token::Array(
+            triples
+                .iter()
+                .map(|(pay, buy, id)| Token::FixedArray(vec![uint(*pay), uint(*buy), uint(*id)]))
+                .collect(),
+        )
+    }
+
+    fn price(level: &BookLevel) -> String {
+        level.price().value().normalize().to_string()
+    }
+
+    #[test]
+    fn the_router_book_is_decoded_from_triples() {
+        let (session, mock) = mocked_session();
+        // asks pay WETH for USDC, bids pay USDC for WETH, and both are padded out to the depth with zeroes
+        let asks = side(&[(ETH, 2010 * USDC, 7), (0, 0, 0), (0, 0, 0)]);
+        let bids = side(&[(1990 * USDC, ETH, 8), (3960 * USDC, 2 * ETH, 9), (0, 0, 0)]);
+        respond(&mock, vec![call_result(&[asks, bids, uint(3)])]);
+
+        // the book for ETH is the book for WETH
+        let book = block_on(session.router_order_book(&Pair::new(Asset::Eth, Asset::Usdc), 3)).unwrap();
+        assert_eq!(book.pair(), &pair());
+        assert_eq!(book.block(), None);
+        let ids: Vec<U256> = book.bids().iter().map(|x| x.id()).collect();
+        assert_eq!(ids, vec![U256::from(8), U256::from(9)]);
+        assert_eq!(book.bids().iter().map(price).collect::<Vec<_>>(), vec!["1990", "1980"]);
+        assert_eq!(book.bids()[1].size().normalize().to_string(), "2");
+        assert_eq!(book.asks().len(), 1);
+        assert_eq!((book.asks()[0].id(), price(&book.asks()[0])), (U256::from(7), "2010".to_string()));
+    }
+
+    #[test]
+    fn the_best_offer_is_resolved_through_the_registry() {
+        let (session, mock) = mocked_session();
+        let weth = Asset::Weth.to_address(&Chain::Optimism).unwrap();
+        let usdc = Asset::Usdc.to_address(&Chain::Optimism).unwrap();
+        respond(
+            &mock,
+            vec![
+                call_result(&[uint(7), uint(ETH), Token::Address(weth), uint(2010 * USDC), Token::Address(usdc)]),
+                call_result(&[uint(0), uint(0), Token::Address(Default::default()), uint(0), Token::Address(Default::default())]),
+            ],
+        );
+        let ask = block_on(session.router_best_offer(&pair(), Side::Sell)).unwrap().unwrap();
+        assert_eq!((ask.id(), price(&ask)), (U256::from(7), "2010".to_string()));
+        // id 0 is an empty side
+        assert_eq!(block_on(session.router_best_offer(&pair(), Side::Buy)).unwrap(), None);
+    }
+
+    #[test]
+    fn an_eth_weth_router_book_is_an_error_not_a_panic() {
+        let (session, _mock) = mocked_session();
+        let pair = Pair::new(Asset::Eth, Asset::Weth);
+        let e = block_on(session.router_order_book(&pair, 5)).unwrap_err();
+        assert!(e.to_string().starts_with("[router_order_book]:"), "{}", e);
+        let e = block_on(session.router_best_offer(&pair, Side::Buy)).unwrap_err();
+        assert!(e.to_string().starts_with("[router_best_offer]:"), "{}", e);
+    }
+}
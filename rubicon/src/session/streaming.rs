@@ -0,0 +1,478 @@
+use super::{backoff, RubiconSession};
+use crate::events::{BookEvent, LoggedEvent};
+use anyhow::{anyhow, Result};
+use ethers::{
+    contract::EthEvent,
+    core::types::{Address, Filter, Log, U256},
+    providers::{Middleware, PubsubClient},
+};
+use futures::{future::BoxFuture, Future, StreamExt};
+use futures_timer::Delay;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, instrument, warn};
+
+/// How many times in a row we try to resubscribe after the websocket drops before giving up.
+pub const RESUBSCRIBE_ATTEMPTS: usize = 5;
+
+/// How long we wait before retrying after the first failed attempt to resubscribe. The wait doubles with every failure after that.
+pub const RESUBSCRIBE_BACKOFF: Duration = Duration::from_millis(500);
+
+/// The longest we wait between two attempts to resubscribe.
+pub const RESUBSCRIBE_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Builds a fresh client to resubscribe with, e.g. by opening a new websocket to the same endpoint. See [`RubiconSession::with_reconnect`].
+pub type Reconnect<M> = Arc<dyn Fn() -> BoxFuture<'static, Result<Arc<M>>> + Send + Sync>;
+
+/**
+ * Fans a stream of events out to any number of consumers. Every subscriber gets its own flume channel,
+ * and receives every event sent after it subscribed. Subscribers that drop their receiver are forgotten.
+ */
+pub struct EventBroadcast<E> {
+    subscribers: Arc<Mutex<Vec<flume::Sender<E>>>>,
+}
+
+impl<E> Clone for EventBroadcast<E> {
+    fn clone(&self) -> Self {
+        Self {
+            subscribers: self.subscribers.clone(),
+        }
+    }
+}
+
+impl<E> Default for EventBroadcast<E> {
+    fn default() -> Self {
+        Self {
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl<E: Clone> EventBroadcast<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a new receiver with an unbounded buffer.
+    pub fn subscribe(&self) -> flume::Receiver<E> {
+        let (tx, rx) = flume::unbounded();
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// Returns a new receiver that buffers at most `capacity` events. A full subscriber misses events rather than blocking the others.
+    pub fn subscribe_bounded(&self, capacity: usize) -> flume::Receiver<E> {
+        let (tx, rx) = flume::bounded(capacity);
+        self.subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// The number of live subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.lock().unwrap().len()
+    }
+
+    /// True once nothing could ever receive an event from this broadcast again:
+    /// every subscriber has dropped its receiver, and there's no other handle to the broadcast left to subscribe with.
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.subscribers) == 1 && self.subscribers.lock().unwrap().is_empty()
+    }
+
+    /// Sends `event` to every subscriber, and returns the number that are still live.
+    pub fn send(&self, event: E) -> usize {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|tx| match tx.try_send(event.clone()) {
+            Ok(()) => true,
+            Err(flume::TrySendError::Full(_)) => {
+                warn!("[EventBroadcast]: a subscriber is full, dropping an event for it");
+                true
+            }
+            Err(flume::TrySendError::Disconnected(_)) => false,
+        });
+        subscribers.len()
+    }
+}
+
+/*
+ * Streaming needs a websocket (or IPC) provider, since it's built on `eth_subscribe`.
+ *
+ * The session doesn't spawn anything: each `stream_*` function returns the broadcast that events are sent to,
+ * along with a future that drives the subscription. Subscribe to the broadcast, and then spawn the future on whatever runtime you use.
+ * Having no subscribers doesn't stop the future: events sent while nobody is subscribed are dropped, and anyone who subscribes later
+ * gets everything from then on. It only stops (resolving to `Ok`) once the broadcast is abandoned (see [`EventBroadcast::is_abandoned`]),
+ * which it notices at the next event. Otherwise it runs until it can't resubscribe (resolving to `Err`).
+ *
+ * When the subscription ends (e.g. the websocket dropped), we resubscribe and catch up on anything we missed with `eth_getLogs`,
+ * so consumers see every event exactly once. Logs that a reorg removes are passed on with `meta().removed()` set.
+ * Catching up is part of resubscribing: if `eth_getLogs` fails, we try the whole thing again rather than skip what we missed.
+ * Failed attempts are retried with an exponential backoff, up to `RESUBSCRIBE_ATTEMPTS` in a row.
+ * A dropped websocket doesn't come back on its own, so set [`RubiconSession::with_reconnect`] to resubscribe through a fresh client.
+ */
+impl<M: Middleware + Clone + 'static> RubiconSession<M>
+where
+    M::Provider: PubsubClient,
+{
+    /// Sets how to build a fresh client whenever a stream has to resubscribe, e.g. by connecting a new websocket to the same endpoint.
+    /// Without it, streams resubscribe through the session's client.
+    ///
+    /// ```ignore
+    /// let session = session.with_reconnect(move || {
+    ///     let url = url.clone();
+    ///     async move { Ok(Provider::new(Ws::connect(url).await?)) }
+    /// });
+    /// ```
+    pub fn with_reconnect<F, Fut>(mut self, reconnect: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<M>> + Send + 'static,
+    {
+        self.reconnect = Some(Arc::new(move || {
+            let client = reconnect();
+            Box::pin(async move { Ok(Arc::new(client.await?)) })
+        }));
+        self
+    }
+
+    /// Streams every `E` emitted by the RubiconMarket.
+    #[instrument(level = "debug", skip(self))]
+    pub fn stream_market_events<E: EthEvent + Clone + Send + 'static>(
+        &self,
+    ) -> (
+        EventBroadcast<LoggedEvent<E>>,
+        impl Future<Output = Result<()>> + Send + 'static,
+    ) {
+        self.stream_events(self.deployment().market())
+    }
+
+    /// Streams every `E` emitted by the BathPair.
+    #[instrument(level = "debug", skip(self))]
+    pub fn stream_pair_events<E: EthEvent + Clone + Send + 'static>(
+        &self,
+    ) -> (
+        EventBroadcast<LoggedEvent<E>>,
+        impl Future<Output = Result<()>> + Send + 'static,
+    ) {
+        self.stream_events(self.deployment().pair())
+    }
+
+    /// Streams every [`BookEvent`] emitted by the RubiconMarket, e.g. to keep a [`crate::market::LiveOrderBook`] current.
+    #[instrument(level = "debug", skip(self))]
+    pub fn stream_book_events(
+        &self,
+    ) -> (
+        EventBroadcast<LoggedEvent<BookEvent>>,
+        impl Future<Output = Result<()>> + Send + 'static,
+    ) {
+        let broadcast = EventBroadcast::new();
+        let filter = Filter::new()
+            .address(self.deployment().market())
+            .topic0(BookEvent::signatures());
+        let driver = drive_subscription(
+            self._internal_middleware.clone(),
+            self.reconnect.clone(),
+            filter,
+            broadcast.clone(),
+            |log: &Log| LoggedEvent::<BookEvent>::from_log(log),
+        );
+        (broadcast, driver)
+    }
+
+    /// Streams every `E` emitted by the contract at `address`.
+    #[instrument(level = "debug", skip(self))]
+    pub fn stream_events<E: EthEvent + Clone + Send + 'static>(
+        &self,
+        address: Address,
+    ) -> (
+        EventBroadcast<LoggedEvent<E>>,
+        impl Future<Output = Result<()>> + Send + 'static,
+    ) {
+        let broadcast = EventBroadcast::new();
+        let filter = Filter::new().address(address).topic0(E::signature());
+        let driver = drive_subscription(
+            self._internal_middleware.clone(),
+            self.reconnect.clone(),
+            filter,
+            broadcast.clone(),
+            |log: &Log| LoggedEvent::<E>::from_log(log),
+        );
+        (broadcast, driver)
+    }
+}
+
+pub(crate) async fn drive_subscription<M, T, F>(
+    client: Arc<M>,
+    reconnect: Option<Reconnect<M>>,
+    filter: Filter,
+    broadcast: EventBroadcast<T>,
+    decode: F,
+) -> Result<()>
+where
+    M: Middleware + 'static,
+    M::Provider: PubsubClient,
+    T: Clone,
+    F: Fn(&Log) -> Result<T>,
+{
+    // the position of the last log we passed on (or the head when we first subscribed), so that we neither repeat
+    // ourselves nor miss anything after resubscribing
+    let mut last_seen: Option<(u64, U256)> = None;
+    let mut client = client;
+    let mut failures = 0;
+    // whether the last subscription failed or ended, so that the next one goes through a fresh client
+    let mut stale = false;
+
+    loop {
+        if failures > 0 {
+            Delay::new(backoff(RESUBSCRIBE_BACKOFF, RESUBSCRIBE_MAX_BACKOFF, failures)).await;
+        }
+        if stale {
+            if let Some(reconnect) = &reconnect {
+                match reconnect().await {
+                    Ok(fresh) => client = fresh,
+                    Err(e) => {
+                        count_failure(&mut failures, "reconnect", e)?;
+                        continue;
+                    }
+                }
+            }
+        }
+        // everything up to the head is history, so if the subscription drops before its first event we still know where to catch up from
+        if last_seen.is_none() {
+            match client.get_block_number().await {
+                Ok(head) => last_seen = Some((head.as_u64(), U256::MAX)),
+                Err(e) => {
+                    count_failure(&mut failures, "get the head block", e)?;
+                    stale = true;
+                    continue;
+                }
+            }
+        }
+        let mut stream = match client.subscribe_logs(&filter).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                count_failure(&mut failures, "subscribe", e)?;
+                stale = true;
+                continue;
+            }
+        };
+
+        // catch up on whatever happened while we weren't subscribed. Skipping this would lose those events for good,
+        // so a failure here is a failure to subscribe
+        if let Some((block, _)) = last_seen {
+            match client.get_logs(&filter.clone().from_block(block)).await {
+                Ok(logs) => {
+                    for log in logs {
+                        if !emit(log, &mut last_seen, &broadcast, &decode) {
+                            debug!("[drive_subscription]: the broadcast was abandoned, stopping");
+                            return Ok(());
+                        }
+                    }
+                }
+                Err(e) => {
+                    count_failure(&mut failures, &format!("catch up from block {}", block), e)?;
+                    stale = true;
+                    continue;
+                }
+            }
+        }
+        failures = 0;
+
+        while let Some(log) = stream.next().await {
+            if !emit(log, &mut last_seen, &broadcast, &decode) {
+                debug!("[drive_subscription]: the broadcast was abandoned, stopping");
+                return Ok(());
+            }
+        }
+        warn!("[drive_subscription]: subscription ended, resubscribing");
+        stale = true;
+    }
+}
+
+// counts a failed attempt to (re)subscribe, and gives up once there have been too many in a row
+fn count_failure(failures: &mut usize, what: &str, e: impl std::fmt::Display) -> Result<()> {
+    *failures += 1;
+    if *failures >= RESUBSCRIBE_ATTEMPTS {
+        return Err(anyhow!(
+            "[drive_subscription]: giving up after {} failed attempts to {}: {}",
+            failures,
+            what,
+            e
+        ));
+    }
+    warn!("[drive_subscription]: failed to {} ({}), retrying", what, e);
+    Ok(())
+}
+
+// passes `log` on unless we've already passed it on, and moves `last_seen` past it. Returns false once the broadcast is abandoned
+fn emit<T: Clone, F: Fn(&Log) -> Result<T>>(
+    log: Log,
+    last_seen: &mut Option<(u64, U256)>,
+    broadcast: &EventBroadcast<T>,
+    decode: &F,
+) -> bool {
+    let removed = log.removed.unwrap_or(false);
+    let position = match (log.block_number, log.log_index) {
+        (Some(block), Some(index)) => (block.as_u64(), index),
+        _ => return true,
+    };
+    if !removed && last_seen.is_some_and(|x| position <= x) {
+        return true;
+    }
+    match decode(&log) {
+        Ok(event) => {
+            if !removed {
+                *last_seen = Some(position);
+            } else if last_seen.is_some_and(|x| position <= x) {
+                // the block was orphaned, so whatever replaces it is new to us
+                *last_seen = Some((position.0.saturating_sub(1), U256::MAX));
+            }
+            broadcast.send(event);
+            !broadcast.is_abandoned()
+        }
+        Err(e) => {
+            warn!("[drive_subscription]: skipping a log: {}", e);
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_broadcast_outlives_its_subscribers_until_every_handle_is_gone() {
+        let broadcast = EventBroadcast::<u64>::new();
+        let driver_side = broadcast.clone();
+        assert!(!driver_side.is_abandoned());
+
+        let rx = broadcast.subscribe();
+        assert_eq!(driver_side.send(1), 1);
+        assert_eq!(rx.try_recv().unwrap(), 1);
+
+        // nobody is subscribed, but the handle can still subscribe, so the driver should keep going
+        drop(rx);
+        assert_eq!(driver_side.send(2), 0);
+        assert!(!driver_side.is_abandoned());
+        let late = broadcast.subscribe();
+        assert_eq!(driver_side.send(3), 1);
+        assert_eq!(late.try_recv().unwrap(), 3);
+
+        // a live subscriber keeps it going after the last handle is dropped
+        drop(broadcast);
+        assert!(!driver_side.is_abandoned());
+        drop(late);
+        driver_side.send(4);
+        assert!(driver_side.is_abandoned());
+    }
+
+    #[test]
+    fn resubscribing_backs_off_exponentially_up_to_the_cap() {
+        let delays: Vec<Duration> = (1..=9)
+            .map(|x| backoff(RESUBSCRIBE_BACKOFF, RESUBSCRIBE_MAX_BACKOFF, x))
+            .collect();
+        assert_eq!(delays[0], Duration::from_millis(500));
+        assert_eq!(delays[1], Duration::from_secs(1));
+        assert_eq!(delays[3], Duration::from_secs(4));
+        assert_eq!(delays[6], Duration::from_secs(30));
+        assert_eq!(delays[8], RESUBSCRIBE_MAX_BACKOFF);
+        assert_eq!(backoff(RESUBSCRIBE_BACKOFF, RESUBSCRIBE_MAX_BACKOFF, usize::MAX), RESUBSCRIBE_MAX_BACKOFF);
+    }
+
+    // a log at (block, index) carrying `value`, which `decode_value` reads back; an empty payload doesn't decode
+    fn log_at(block: u64, index: u64, value: u8, removed: bool) -> Log {
+        Log {
+            block_number: Some(block.into()),
+            log_index: Some(index.into()),
+            data: vec![value].into(),
+            removed: Some(removed),
+            ..Default::default()
+        }
+    }
+
+    fn decode_value(log: &Log) -> Result<u64> {
+        log.data.first().map(|x| *x as u64).ok_or_else(|| anyhow!("empty log"))
+    }
+
+    fn received(rx: &flume::Receiver<u64>) -> Vec<u64> {
+        rx.try_iter().collect()
+    }
+
+    #[test]
+    fn emitting_passes_each_log_on_once_in_order() {
+        let broadcast = EventBroadcast::<u64>::new();
+        let rx = broadcast.subscribe();
+        let mut last_seen = None;
+
+        for log in [log_at(10, 0, 1, false), log_at(10, 1, 2, false), log_at(11, 0, 3, false)] {
+            assert!(emit(log, &mut last_seen, &broadcast, &decode_value));
+        }
+        assert_eq!(last_seen, Some((11, U256::zero())));
+
+        // the catch-up after a resubscribe overlaps what the old subscription already delivered
+        for log in [log_at(10, 1, 2, false), log_at(11, 0, 3, false), log_at(11, 1, 4, false)] {
+            assert!(emit(log, &mut last_seen, &broadcast, &decode_value));
+        }
+        assert_eq!(received(&rx), vec![1, 2, 3, 4]);
+        assert_eq!(last_seen, Some((11, U256::one())));
+    }
+
+    #[test]
+    fn emitting_catches_up_from_the_head_at_the_first_subscribe() {
+        let broadcast = EventBroadcast::<u64>::new();
+        let rx = broadcast.subscribe();
+        // what drive_subscription records before its first subscribe
+        let mut last_seen = Some((20, U256::MAX));
+
+        assert!(emit(log_at(20, 3, 1, false), &mut last_seen, &broadcast, &decode_value));
+        assert!(emit(log_at(21, 0, 2, false), &mut last_seen, &broadcast, &decode_value));
+        assert_eq!(received(&rx), vec![2]);
+    }
+
+    #[test]
+    fn emitting_skips_pending_and_undecodable_logs() {
+        let broadcast = EventBroadcast::<u64>::new();
+        let rx = broadcast.subscribe();
+        let mut last_seen = None;
+
+        let pending = Log { block_number: None, log_index: None, data: vec![1].into(), ..Default::default() };
+        assert!(emit(pending, &mut last_seen, &broadcast, &decode_value));
+        assert_eq!(last_seen, None);
+
+        let mut garbled = log_at(5, 0, 0, false);
+        garbled.data = Default::default();
+        assert!(emit(garbled, &mut last_seen, &broadcast, &decode_value));
+        assert_eq!(last_seen, None);
+
+        assert!(received(&rx).is_empty());
+    }
+
+    #[test]
+    fn a_removed_log_is_passed_on_and_rewinds_past_its_block() {
+        let broadcast = EventBroadcast::<u64>::new();
+        let rx = broadcast.subscribe();
+        let mut last_seen = None;
+
+        assert!(emit(log_at(30, 0, 1, false), &mut last_seen, &broadcast, &decode_value));
+        assert!(emit(log_at(31, 0, 2, false), &mut last_seen, &broadcast, &decode_value));
+        assert!(emit(log_at(31, 0, 2, true), &mut last_seen, &broadcast, &decode_value));
+        assert_eq!(last_seen, Some((30, U256::MAX)));
+
+        // the replacement block reuses the same position, and has to get through
+        assert!(emit(log_at(31, 0, 3, false), &mut last_seen, &broadcast, &decode_value));
+        assert_eq!(received(&rx), vec![1, 2, 2, 3]);
+        assert_eq!(last_seen, Some((31, U256::zero())));
+
+        // a removal from beyond what we've seen doesn't move us backwards
+        assert!(emit(log_at(40, 0, 4, true), &mut last_seen, &broadcast, &decode_value));
+        assert_eq!(last_seen, Some((31, U256::zero())));
+    }
+
+    #[test]
+    fn emitting_stops_once_the_broadcast_is_abandoned() {
+        let broadcast = EventBroadcast::<u64>::new();
+        let driver_side = broadcast.clone();
+        drop(broadcast);
+        let mut last_seen = None;
+        assert!(!emit(log_at(1, 0, 1, false), &mut last_seen, &driver_side, &decode_value));
+    }
+}
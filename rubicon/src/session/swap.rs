@@ -0,0 +1,357 @@
+use super::{ContractCall, RubiconSession};
+use crate::market::{Slippage, SwapRoute};
+use anyhow::{anyhow, Result};
+use ethers::{
+    core::types::{Address, U256},
+    middleware::SignerMiddleware,
+    providers::Middleware,
+    signers::Signer,
+};
+use futures::future::{join_all, Future};
+use numeraire::prelude::*;
+use std::collections::HashMap;
+use tracing::{debug, instrument};
+
+/// The most books a route found by [`RubiconSession::find_swap_routes`] crosses by default.
+pub const DEFAULT_MAX_HOPS: usize = 3;
+
+/// The most candidate paths that [`RubiconSession::find_swap_routes`] quotes. Paths are found shortest first, so it's the longest that are left out.
+pub const MAX_SWAP_PATHS: usize = 32;
+
+/*
+ * Multi-hop swaps through the RubiconRouter.
+ *
+ * Routes are found by walking every token in the session's registry: a hop from `x` to `y` is only taken if the market
+ * has a live offer selling `y` for `x`. Each candidate is then quoted with the router's `getExpectedSwapFill`, which walks
+ * the books on chain with the market's fee included, so the quote is what `swap` would fill if nothing changes in the meantime.
+ * The books are looked up a hop at a time, each hop's in parallel, and at most `MAX_SWAP_PATHS` candidates are quoted (also in parallel),
+ * so a large registry costs a bounded number of round trips rather than one per book.
+ * Native ETH isn't routed here: use `swap_with_eth`/`swap_for_eth`, which wrap and unwrap it at the ends of a route.
+ */
+impl<M: Middleware + Clone + 'static> RubiconSession<M> {
+    /// Finds the routes from `pay` to `buy` that cross at most `max_hops` live books, quoted and sorted best first.
+    /// Routes that the router can't fill are dropped, so the result is empty if there's no way through.
+    /// Only the [`MAX_SWAP_PATHS`] shortest paths are quoted.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn find_swap_routes(&self, pay: &ChainNativeAsset, buy: &Asset, max_hops: usize) -> Result<Vec<SwapRoute>> {
+        if pay.asset().is_native() || buy.is_native() {
+            return Err(anyhow!(
+                "[find_swap_routes]: can't route native ETH, use swap_with_eth or swap_for_eth"
+            ));
+        }
+        if pay.asset() == buy {
+            return Err(anyhow!("[find_swap_routes]: can't swap {} for itself", buy));
+        }
+        if max_hops == 0 {
+            return Err(anyhow!("[find_swap_routes]: a route crosses at least one book"));
+        }
+        let fee_bps = self.get_fee_bps().await?;
+        let paths = self.swap_paths(pay.asset(), buy, max_hops).await?;
+        let quotes = join_all(paths.iter().map(|path| async move {
+            let addresses = path
+                .iter()
+                .map(|x| x.to_address(self.chain()))
+                .collect::<Result<Vec<Address>>>()?;
+            Ok::<_, anyhow::Error>(
+                self.router()
+                    .get_expected_swap_fill(*pay.size(), U256::zero(), addresses, fee_bps)
+                    .call()
+                    .await,
+            )
+        }))
+        .await;
+        let mut routes = Vec::new();
+        for (path, quote) in paths.into_iter().zip(quotes) {
+            let fill = match quote? {
+                Ok(fill) if !fill.is_zero() => fill,
+                Ok(_) => continue,
+                Err(e) => {
+                    debug!("[find_swap_routes]: the router can't fill {:?}: {}", path, e);
+                    continue;
+                }
+            };
+            routes.push(SwapRoute::new(path, pay.clone(), self.local_asset(*buy, fill)?, fee_bps));
+        }
+        rank_routes(&mut routes);
+        Ok(routes)
+    }
+
+    // the shortest simple paths from `pay` to `buy` of at most `max_hops` live books
+    async fn swap_paths(&self, pay: &Asset, buy: &Asset, max_hops: usize) -> Result<Vec<Vec<Asset>>> {
+        let assets: Vec<Asset> = self
+            .registry()
+            .tokens()
+            .filter(|x| x.chain() == self.chain())
+            .filter_map(|x| x.to_asset().ok())
+            .filter(|x| !x.is_native())
+            .collect();
+        enumerate_paths(pay, buy, &assets, max_hops, |hops| async move {
+            // swapping `from` into `to` takes offers that sell `to` for `from`
+            join_all(hops.iter().map(|(from, to)| self.get_best_offer(to, from)))
+                .await
+                .into_iter()
+                .map(|x| x.map(|x| x.is_some()))
+                .collect()
+        })
+        .await
+    }
+}
+
+/*
+ * Every simple path from `pay` to `buy` through `assets` that crosses at most `max_hops` books, shortest first and capped at `MAX_SWAP_PATHS`.
+ * The paths are extended a hop at a time, and `lookup` is asked (once per level, and never twice for the same book)
+ * whether each hop the level needs is live.
+ */
+async fn enumerate_paths<F, Fut>(
+    pay: &Asset,
+    buy: &Asset,
+    assets: &[Asset],
+    max_hops: usize,
+    mut lookup: F,
+) -> Result<Vec<Vec<Asset>>>
+where
+    F: FnMut(Vec<(Asset, Asset)>) -> Fut,
+    Fut: Future<Output = Result<Vec<bool>>>,
+{
+    let mut candidates: Vec<Asset> = assets.iter().filter(|x| *x != pay && *x != buy).copied().collect();
+    candidates.push(*buy);
+    let mut live: HashMap<(Asset, Asset), bool> = HashMap::new();
+    let mut paths = Vec::new();
+    let mut frontier = vec![vec![*pay]];
+    for depth in 1..=max_hops {
+        // on the last hop, only the books into `buy` matter
+        let nexts = |path: &[Asset]| -> Vec<(Asset, Asset)> {
+            let last = *path.last().unwrap();
+            candidates
+                .iter()
+                .filter(|x| !path.contains(x) && (depth < max_hops || *x == buy))
+                .map(|x| (last, *x))
+                .collect()
+        };
+        let mut hops = Vec::new();
+        for hop in frontier.iter().flat_map(|x| nexts(x)) {
+            if !live.contains_key(&hop) && !hops.contains(&hop) {
+                hops.push(hop);
+            }
+        }
+        if !hops.is_empty() {
+            let found = lookup(hops.clone()).await?;
+            live.extend(hops.into_iter().zip(found));
+        }
+        let mut extended = Vec::new();
+        for path in frontier {
+            for (_, next) in nexts(&path) {
+                if !live.get(&(*path.last().unwrap(), next)).copied().unwrap_or(false) {
+                    continue;
+                }
+                let mut longer = path.clone();
+                longer.push(next);
+                if next == *buy {
+                    paths.push(longer);
+                } else {
+                    extended.push(longer);
+                }
+            }
+        }
+        if paths.len() >= MAX_SWAP_PATHS || extended.is_empty() {
+            break;
+        }
+        frontier = extended;
+    }
+    paths.truncate(MAX_SWAP_PATHS);
+    Ok(paths)
+}
+
+// best fill first, and the shorter route when two fill the same
+fn rank_routes(routes: &mut [SwapRoute]) {
+    routes.sort_by(|a, b| {
+        b.expected_fill()
+            .size()
+            .cmp(a.expected_fill().size())
+            .then(a.hops().cmp(&b.hops()))
+    });
+}
+
+impl<M: Middleware + Clone + 'static, S: Clone + Signer> RubiconSession<SignerMiddleware<M, S>> {
+    /// Swaps `pay_amt` of the first token in `route` for at least `buy_amt_min` of the last, through every book in between.
+    /// `expected_fee_bps` should be the market's fee, see [`RubiconSession::get_fee_bps`]. The returned value is the fill.
+    #[instrument(level = "debug", skip(self))]
+    pub fn swap(
+        &self,
+        pay_amt: U256,
+        buy_amt_min: U256,
+        route: Vec<Address>,
+        expected_fee_bps: U256,
+    ) -> Result<ContractCall<SignerMiddleware<M, S>, U256>> {
+        if route.len() < 2 {
+            return Err(anyhow!(
+                "[swap]: a route needs at least two tokens, got {}",
+                route.len()
+            ));
+        }
+        Ok(self.legacy_if_needed(self.router().swap(pay_amt, buy_amt_min, route, expected_fee_bps)))
+    }
+
+    /// Swaps along a route found by [`RubiconSession::find_swap_routes`], reverting if the fill is worse than `slippage` allows.
+    /// Fails without building a transaction if the route's quote is already worse than that.
+    /// The realized fill is in the router's `LogSwap`, see [`crate::market::TradeOutcome::swaps`].
+    #[instrument(level = "debug", skip(self))]
+    pub fn swap_route(&self, route: &SwapRoute, slippage: &Slippage) -> Result<ContractCall<SignerMiddleware<M, S>, U256>> {
+        let min_fill = slippage.min_fill(route.pay(), route.expected_fill())?;
+        if route.expected_fill().size() < min_fill.size() {
+            return Err(anyhow!(
+                "[swap_route]: the route only fills {} for {}, but {} needs at least {}",
+                route.expected_fill(),
+                route.pay(),
+                slippage,
+                min_fill
+            ));
+        }
+        self.swap(
+            *route.pay().size(),
+            *min_fill.size(),
+            route.addresses(self.chain())?,
+            route.fee_bps(),
+        )
+    }
+
+    /// Finds the best route from `pay` to `buy` (see [`RubiconSession::find_swap_routes`]), and swaps along it.
+    /// Returns the [`ContractCall`] along with the route, so the expected fill can be checked against the realized one.
+    #[allow(clippy::type_complexity)]
+    #[instrument(level = "debug", skip(self))]
+    pub async fn swap_best_route(
+        &self,
+        pay: &ChainNativeAsset,
+        buy: &Asset,
+        max_hops: usize,
+        slippage: &Slippage,
+    ) -> Result<(ContractCall<SignerMiddleware<M, S>, U256>, SwapRoute)> {
+        let route = self
+            .find_swap_routes(pay, buy, max_hops)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(anyhow!("[swap_best_route]: there's no route from {} to {}", pay.asset(), buy))?;
+        Ok((self.swap_route(&route, slippage)?, route))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::mock::mocked_signer_session;
+    use futures::executor::block_on;
+    use rust_decimal::Decimal;
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+
+    const ASSETS: [Asset; 4] = [Asset::Usdc, Asset::Weth, Asset::Dai, Asset::Usdt];
+
+    // the paths from USDC to DAI over `books`, and every book that was looked up, in order
+    fn paths(books: &[(Asset, Asset)], max_hops: usize) -> (Vec<Vec<Asset>>, Vec<(Asset, Asset)>) {
+        let looked_up = RefCell::new(Vec::new());
+        let paths = block_on(enumerate_paths(&Asset::Usdc, &Asset::Dai, &ASSETS, max_hops, |hops| {
+            looked_up.borrow_mut().extend(hops.iter().copied());
+            let found = hops.iter().map(|x| books.contains(x)).collect();
+            async move { Ok(found) }
+        }))
+        .unwrap();
+        (paths, looked_up.into_inner())
+    }
+
+    #[test]
+    fn paths_are_found_shortest_first_up_to_max_hops() {
+        let books = [
+            (Asset::Usdc, Asset::Dai),
+            (Asset::Usdc, Asset::Weth),
+            (Asset::Weth, Asset::Dai),
+            (Asset::Weth, Asset::Usdt),
+            (Asset::Usdt, Asset::Dai),
+        ];
+        let (found, looked_up) = paths(&books, 3);
+        assert_eq!(
+            found,
+            vec![
+                vec![Asset::Usdc, Asset::Dai],
+                vec![Asset::Usdc, Asset::Weth, Asset::Dai],
+                vec![Asset::Usdc, Asset::Weth, Asset::Usdt, Asset::Dai],
+            ]
+        );
+        // no book is looked up twice
+        assert_eq!(looked_up.iter().collect::<HashSet<_>>().len(), looked_up.len());
+
+        // the three hop path is one too many
+        let (found, _) = paths(&books, 2);
+        assert_eq!(found.len(), 2);
+        assert!(found.iter().all(|x| x.len() <= 3));
+        let (found, _) = paths(&books, 1);
+        assert_eq!(found, vec![vec![Asset::Usdc, Asset::Dai]]);
+    }
+
+    #[test]
+    fn paths_never_revisit_an_asset() {
+        // a cycle between WETH and USDT, and books back into USDC
+        let books = [
+            (Asset::Usdc, Asset::Weth),
+            (Asset::Weth, Asset::Usdt),
+            (Asset::Usdt, Asset::Weth),
+            (Asset::Weth, Asset::Usdc),
+            (Asset::Usdt, Asset::Dai),
+        ];
+        let (found, looked_up) = paths(&books, 5);
+        assert_eq!(found, vec![vec![Asset::Usdc, Asset::Weth, Asset::Usdt, Asset::Dai]]);
+        assert!(!looked_up.iter().any(|(_, to)| *to == Asset::Usdc));
+    }
+
+    #[test]
+    fn the_last_hop_only_looks_up_books_into_what_is_bought() {
+        let (found, looked_up) = paths(&[], 2);
+        assert!(found.is_empty());
+        // USDC into each of the three others, then nothing, since no first hop is live
+        assert_eq!(looked_up.len(), 3);
+        let (_, looked_up) = paths(&[(Asset::Usdc, Asset::Weth)], 2);
+        assert_eq!(looked_up.len(), 4);
+        assert_eq!(looked_up[3], (Asset::Weth, Asset::Dai));
+    }
+
+    #[test]
+    fn routes_that_fill_the_same_prefer_fewer_hops() {
+        let pay = ChainNativeAsset::new(Chain::Optimism, Asset::Usdc, U256::from(1_000)).unwrap();
+        let fill = |x: u64| ChainNativeAsset::new(Chain::Optimism, Asset::Dai, U256::from(x)).unwrap();
+        let route = |path: Vec<Asset>, x: u64| SwapRoute::new(path, pay.clone(), fill(x), U256::from(20));
+        let mut routes = vec![
+            route(vec![Asset::Usdc, Asset::Weth, Asset::Usdt, Asset::Dai], 990),
+            route(vec![Asset::Usdc, Asset::Weth, Asset::Dai], 990),
+            route(vec![Asset::Usdc, Asset::Dai], 980),
+            route(vec![Asset::Usdc, Asset::Usdt, Asset::Dai], 995),
+        ];
+        rank_routes(&mut routes);
+        let ranked: Vec<(usize, U256)> = routes.iter().map(|x| (x.hops(), *x.expected_fill().size())).collect();
+        assert_eq!(
+            ranked,
+            vec![
+                (2, U256::from(995)),
+                (2, U256::from(990)),
+                (3, U256::from(990)),
+                (1, U256::from(980)),
+            ]
+        );
+    }
+
+    #[test]
+    fn swap_route_refuses_a_stale_quote() {
+        let (session, _mock) = mocked_signer_session();
+        let pay = ChainNativeAsset::new(Chain::Optimism, Asset::Usdc, U256::from(1_000_000)).unwrap();
+        let fill = ChainNativeAsset::new(Chain::Optimism, Asset::Dai, U256::exp10(18)).unwrap();
+        let route = SwapRoute::new(vec![Asset::Usdc, Asset::Weth, Asset::Dai], pay, fill, U256::from(20));
+
+        // a DAI for a USDC is what the quote says, but we want at least 1.01
+        let limit = Price::new(Pair::new(Asset::Usdc, Asset::Dai), Decimal::new(101, 2)).unwrap();
+        assert!(session.swap_route(&route, &Slippage::Limit(limit)).is_err());
+
+        // a limit the quote meets is fine, and so is a tolerance of the quote itself
+        let limit = Price::new(Pair::new(Asset::Usdc, Asset::Dai), Decimal::new(99, 2)).unwrap();
+        assert!(session.swap_route(&route, &Slippage::Limit(limit)).is_ok());
+        assert!(session.swap_route(&route, &Slippage::Bps(50)).is_ok());
+    }
+}
@@ -0,0 +1,308 @@
+use super::{ContractCall, RubiconSession};
+use crate::market::Offer;
+use anyhow::{anyhow, Result};
+use ethers::{
+    abi::Detokenize,
+    core::types::{Address, BlockId, U256},
+    providers::Middleware,
+};
+use numeraire::prelude::*;
+use tracing::instrument;
+
+/*
+ * Typed wrappers around the RubiconMarket's view functions.
+ * Token addresses that come back from the market are resolved through the session's registry,
+ * so an offer in a token the registry doesn't know about is an error rather than a guess at its decimals.
+ *
+ * The `_at` variants pin the call to a block, so that several reads can be made against the same state.
+ */
+impl<M: Middleware + Clone + 'static> RubiconSession<M> {
+    /// Returns the offer with the given id, or `None` if it doesn't exist (i.e. it was never made, or has been filled or cancelled).
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_offer(&self, id: U256) -> Result<Option<Offer>> {
+        self.get_offer_at(id, None).await
+    }
+
+    pub(crate) async fn get_offer_at(&self, id: U256, block: Option<BlockId>) -> Result<Option<Offer>> {
+        let (pay_amt, pay_gem, buy_amt, buy_gem, owner, timestamp) = at(self.market().offers(id), block).call().await?;
+        // deleted offers are zeroed out
+        if pay_gem.is_zero() {
+            return Ok(None);
+        }
+        Ok(Some(Offer::new(
+            id,
+            owner,
+            self.resolve(pay_gem, pay_amt, "get_offer")?,
+            self.resolve(buy_gem, buy_amt, "get_offer")?,
+            timestamp,
+        )))
+    }
+
+    /// Returns what the offer with the given id is selling and what it wants in return, or `None` if it doesn't exist.
+    /// This is [`RubiconSession::get_offer`] without the owner and timestamp.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_offer_terms(&self, id: U256) -> Result<Option<AssetSwap>> {
+        let (pay_amt, pay_gem, buy_amt, buy_gem) = self.market().get_offer(id).call().await?;
+        if pay_gem.is_zero() {
+            return Ok(None);
+        }
+        Ok(Some(AssetSwap::new(
+            self.resolve(pay_gem, pay_amt, "get_offer_terms")?,
+            self.resolve(buy_gem, buy_amt, "get_offer_terms")?,
+        )))
+    }
+
+    /// Returns the id of the best offer selling `sell` for `buy`, or `None` if there are no such offers.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_best_offer(&self, sell: &Asset, buy: &Asset) -> Result<Option<U256>> {
+        self.get_best_offer_at(sell, buy, None).await
+    }
+
+    pub(crate) async fn get_best_offer_at(
+        &self,
+        sell: &Asset,
+        buy: &Asset,
+        block: Option<BlockId>,
+    ) -> Result<Option<U256>> {
+        let call = self.market().get_best_offer(
+            sell.settlement_address(self.chain())?,
+            buy.settlement_address(self.chain())?,
+        );
+        Ok(non_zero(at(call, block).call().await?))
+    }
+
+    /// Returns the id of the next better offer in the same market as `id`, or `None` if `id` is the best.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_better_offer(&self, id: U256) -> Result<Option<U256>> {
+        Ok(non_zero(self.market().get_better_offer(id).call().await?))
+    }
+
+    /// Returns the id of the next worse offer in the same market as `id`, or `None` if `id` is the worst.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_worse_offer(&self, id: U256) -> Result<Option<U256>> {
+        self.get_worse_offer_at(id, None).await
+    }
+
+    pub(crate) async fn get_worse_offer_at(&self, id: U256, block: Option<BlockId>) -> Result<Option<U256>> {
+        Ok(non_zero(at(self.market().get_worse_offer(id), block).call().await?))
+    }
+
+    /// Returns the number of sorted offers selling `sell` for `buy`.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_offer_count(&self, sell: &Asset, buy: &Asset) -> Result<U256> {
+        Ok(self
+            .market()
+            .get_offer_count(
+                sell.settlement_address(self.chain())?,
+                buy.settlement_address(self.chain())?,
+            )
+            .call()
+            .await?)
+    }
+
+    /// Returns the owner of the offer with the given id. Deleted offers are owned by the zero address.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_owner(&self, id: U256) -> Result<Address> {
+        Ok(self.market().get_owner(id).call().await?)
+    }
+
+    /// Returns true if the offer with the given id is live on the book.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn is_active(&self, id: U256) -> Result<bool> {
+        self.is_active_at(id, None).await
+    }
+
+    pub(crate) async fn is_active_at(&self, id: U256, block: Option<BlockId>) -> Result<bool> {
+        Ok(at(self.market().is_active(id), block).call().await?)
+    }
+
+    /// Returns the smallest amount of `asset` that an offer can sell.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_min_sell(&self, asset: &Asset) -> Result<ChainNativeAsset> {
+        let min = self
+            .market()
+            .get_min_sell(asset.settlement_address(self.chain())?)
+            .call()
+            .await?;
+        self.local_asset(asset.settlement_asset(), min)
+    }
+
+    /// Returns how much of `buy` the book would give for selling `pay`, walking the offers from best to worst.
+    /// Fails if the book doesn't have enough offers to take all of `pay`.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_buy_amount(&self, buy: &Asset, pay: &ChainNativeAsset) -> Result<ChainNativeAsset> {
+        let fill = self
+            .market()
+            .get_buy_amount(
+                buy.settlement_address(self.chain())?,
+                pay.asset().settlement_address(self.chain())?,
+                *pay.size(),
+            )
+            .call()
+            .await
+            .map_err(|e| anyhow!("[get_buy_amount]: can't sell {} for {} (is the book deep enough?): {}", pay, buy, e))?;
+        self.local_asset(buy.settlement_asset(), fill)
+    }
+
+    /// Returns how much of `pay` the book would want for buying `buy`, walking the offers from best to worst.
+    /// Fails if the book doesn't have enough offers to fill all of `buy`.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_pay_amount(&self, pay: &Asset, buy: &ChainNativeAsset) -> Result<ChainNativeAsset> {
+        let fill = self
+            .market()
+            .get_pay_amount(
+                pay.settlement_address(self.chain())?,
+                buy.asset().settlement_address(self.chain())?,
+                *buy.size(),
+            )
+            .call()
+            .await
+            .map_err(|e| anyhow!("[get_pay_amount]: can't buy {} with {} (is the book deep enough?): {}", buy, pay, e))?;
+        self.local_asset(pay.settlement_asset(), fill)
+    }
+
+    /// Returns the market's taker fee, in basis points.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn get_fee_bps(&self) -> Result<U256> {
+        self.get_fee_bps_at(None).await
+    }
+
+    pub(crate) async fn get_fee_bps_at(&self, block: Option<BlockId>) -> Result<U256> {
+        Ok(at(self.market().get_fee_bps(), block).call().await?)
+    }
+
+    /// Returns the id of the most recently made offer.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn last_offer_id(&self) -> Result<U256> {
+        Ok(self.market().last_offer_id().call().await?)
+    }
+
+    // turns a token address and a wei amount from the market into a ChainNativeAsset
+    pub(crate) fn resolve(&self, gem: Address, amount: U256, func: &str) -> Result<ChainNativeAsset> {
+        let asset = self
+            .registry()
+            .asset_by_address(self.chain(), &gem)
+            .map_err(|e| anyhow!("[{}]: {}, add it to the session's TokenRegistry", func, e))?;
+        self.local_asset(asset, amount)
+    }
+}
+
+// pins a call to a block, if we were given one
+pub(crate) fn at<M: Middleware, D: Detokenize>(call: ContractCall<M, D>, block: Option<BlockId>) -> ContractCall<M, D> {
+    match block {
+        Some(block) => call.block(block),
+        None => call,
+    }
+}
+
+// the market uses id 0 to mean "no offer"
+pub(crate) fn non_zero(id: U256) -> Option<U256> {
+    if id.is_zero() {
+        None
+    } else {
+        Some(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::mock::{call_result, mocked_session, respond};
+    use ethers::abi::Token;
+    use futures::executor::block_on;
+
+    fn uint(x: u64) -> Token {
+        Token::Uint(U256::from(x))
+    }
+
+    // `offers(id)`: an offer selling `pay_amt` of `pay_gem` for `buy_amt` of `buy_gem`
+    fn offer(pay_amt: u64, pay_gem: Address, buy_amt: u64, buy_gem: Address) -> serde_json::Value {
+        call_result(&[
+            uint(pay_amt),
+            Token::Address(pay_gem),
+            uint(buy_amt),
+            Token::Address(buy_gem),
+            Token::Address(Address::repeat_byte(0xa)),
+            uint(1_700_000_000),
+        ])
+    }
+
+    #[test]
+    fn an_offer_is_resolved_through_the_registry() {
+        let (session, mock) = mocked_session();
+        let weth = Asset::Weth.to_address(session.chain()).unwrap();
+        let usdc = Asset::Usdc.to_address(session.chain()).unwrap();
+        respond(&mock, vec![offer(1_000, weth, 2_000, usdc)]);
+        let got = block_on(session.get_offer(U256::from(7))).unwrap().unwrap();
+        assert_eq!(
+            got,
+            Offer::new(
+                U256::from(7),
+                Address::repeat_byte(0xa),
+                session.local_asset(Asset::Weth, U256::from(1_000)).unwrap(),
+                session.local_asset(Asset::Usdc, U256::from(2_000)).unwrap(),
+                1_700_000_000
+            )
+        );
+
+        // a deleted offer is all zeroes
+        respond(&mock, vec![offer(0, Address::zero(), 0, Address::zero())]);
+        assert_eq!(block_on(session.get_offer(U256::from(7))).unwrap(), None);
+
+        // and an offer in a token we don't know is an error, not a guess
+        respond(&mock, vec![offer(1_000, Address::repeat_byte(0x99), 2_000, usdc)]);
+        assert!(block_on(session.get_offer(U256::from(7))).is_err());
+    }
+
+    #[test]
+    fn offer_terms_are_resolved_the_same_way() {
+        let (session, mock) = mocked_session();
+        let weth = Asset::Weth.to_address(session.chain()).unwrap();
+        let usdc = Asset::Usdc.to_address(session.chain()).unwrap();
+        respond(&mock, vec![call_result(&[uint(1_000), Token::Address(usdc), uint(2_000), Token::Address(weth)])]);
+        let terms = block_on(session.get_offer_terms(U256::from(7))).unwrap().unwrap();
+        assert_eq!(terms.source(), &session.local_asset(Asset::Usdc, U256::from(1_000)).unwrap());
+        assert_eq!(terms.target(), &session.local_asset(Asset::Weth, U256::from(2_000)).unwrap());
+
+        respond(&mock, vec![call_result(&[uint(0), Token::Address(Address::zero()), uint(0), Token::Address(Address::zero())])]);
+        assert!(block_on(session.get_offer_terms(U256::from(7))).unwrap().is_none());
+    }
+
+    #[test]
+    fn offer_id_zero_means_no_offer() {
+        assert_eq!(non_zero(U256::zero()), None);
+        assert_eq!(non_zero(U256::one()), Some(U256::one()));
+        assert_eq!(non_zero(U256::MAX), Some(U256::MAX));
+
+        let (session, mock) = mocked_session();
+        respond(&mock, vec![call_result(&[uint(0)]), call_result(&[uint(42)])]);
+        assert_eq!(block_on(session.get_best_offer(&Asset::Weth, &Asset::Usdc)).unwrap(), None);
+        assert_eq!(
+            block_on(session.get_best_offer(&Asset::Eth, &Asset::Usdc)).unwrap(),
+            Some(U256::from(42))
+        );
+        // native ETH is looked up as WETH, so both asked for the same book
+        let weth_book = session.market().get_best_offer(
+            Asset::Weth.to_address(session.chain()).unwrap(),
+            Asset::Usdc.to_address(session.chain()).unwrap(),
+        );
+        let request = (weth_book.tx, "latest");
+        mock.assert_request("eth_call", &request).unwrap();
+        mock.assert_request("eth_call", &request).unwrap();
+        respond(&mock, vec![call_result(&[uint(0)])]);
+        assert_eq!(block_on(session.get_worse_offer(U256::from(42))).unwrap(), None);
+    }
+
+    #[test]
+    fn resolving_needs_a_registered_token() {
+        let (session, _mock) = mocked_session();
+        let usdc = Asset::Usdc.to_address(session.chain()).unwrap();
+        assert_eq!(
+            session.resolve(usdc, U256::from(5), "test").unwrap(),
+            ChainNativeAsset::new(*session.chain(), Asset::Usdc, U256::from(5)).unwrap()
+        );
+        let e = session.resolve(Address::repeat_byte(0x99), U256::from(5), "test").unwrap_err();
+        assert!(e.to_string().starts_with("[test]: "), "{}", e);
+        assert!(e.to_string().contains("TokenRegistry"), "{}", e);
+    }
+}